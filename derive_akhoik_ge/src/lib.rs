@@ -1,23 +1,81 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, parse_macro_input};
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
 
+/// Does the struct have a field with the name?
+fn has_field(ast: &DeriveInput, name: &str) -> bool {
+    let Data::Struct(data) = &ast.data else {
+        return false;
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return false;
+    };
+    fields
+        .named
+        .iter()
+        .any(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+}
+
+/// The fields needed by `Object3D`, and their types (used in the error message).
+const OBJECT_3D_FIELDS: [(&str, &str); 7] = [
+    ("transform", "Mat4"),
+    ("front", "Vector3"),
+    ("right", "Vector3"),
+    ("up", "Vector3"),
+    ("position", "Vector3"),
+    ("rotation", "Vector3"),
+    ("orientation", "Option<Rotor3>"),
+];
+/// The fields needed by `Object3DSize`, and their types (used in the error message).
+const OBJECT_3D_SIZE_FIELDS: [(&str, &str); 1] = [("size", "Vector3")];
+
+/// Checks the struct has every field needed by a derive.
+/// # Returns
+/// An error naming the first missing field, spanned to the struct's name
+fn check_fields(
+    ast: &DeriveInput,
+    derive_name: &str,
+    fields: &[(&str, &str)],
+) -> Result<(), syn::Error> {
+    let Some((name, ty)) = fields.iter().find(|(name, _)| !has_field(ast, name)) else {
+        return Ok(());
+    };
+    Err(syn::Error::new_spanned(
+        &ast.ident,
+        format!("{derive_name} requires a field `{name}: {ty}`."),
+    ))
+}
+
+/// Derives `Object3D` for a struct with the fields in `OBJECT_3D_FIELDS`.
+/// # Note
+/// - The fields are found by name, there is no attribute to use differently named fields.
+/// - `Object3D`, `Mat4`, `Vector3`, `euler_to_orientation` and `calculate_transform` (or
+///   `calculate_transform_with_size` with a `size` field) need to be in scope.
 #[proc_macro_derive(Object3D)]
 pub fn object_3d_derive_macro(input: TokenStream) -> TokenStream {
     // parse
     let ast = parse_macro_input!(input as DeriveInput);
-
+    if let Err(err) = check_fields(&ast, "Object3D", &OBJECT_3D_FIELDS) {
+        return err.to_compile_error().into();
+    }
+
+    // objects with a size (see Object3DSize) are scaled by it
+    let calculate = if has_field(&ast, "size") {
+        quote! { calculate_transform_with_size(self) }
+    } else {
+        quote! { calculate_transform(self) }
+    };
     let ident = ast.ident;
 
     // generate
     let expanded = quote! {
     impl Object3D for #ident {
         fn calculate_transform(&self) -> Mat4 {
-            calculate_transform(self)
+            #calculate
         }
 
         fn recalculate_transform(&mut self) {
-            self.transform = calculate_transform(self);
+            self.transform = #calculate;
         }
 
         fn get_position(&self) -> Vector3 {
@@ -35,6 +93,20 @@ pub fn object_3d_derive_macro(input: TokenStream) -> TokenStream {
 
         fn set_rotation(&mut self, rot: Vector3) {
             self.rotation = rot;
+            self.orientation = None;
+            self.recalculate_transform();
+            self.update_vectors();
+        }
+
+        fn get_orientation(&self) -> ::ultraviolet::Rotor3 {
+            self.orientation
+                .unwrap_or_else(|| euler_to_orientation(self.rotation))
+        }
+
+        fn set_orientation(&mut self, orientation: ::ultraviolet::Rotor3) {
+            self.orientation = Some(orientation.normalized());
+            self.recalculate_transform();
+            self.update_vectors();
         }
 
         fn get_front(&self) -> Vector3 {
@@ -66,10 +138,14 @@ pub fn object_3d_derive_macro(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Derives `Object3DSize` for a struct with a `size: Vector3` field.
 #[proc_macro_derive(Object3DSize)]
 pub fn object_3d_size_derive_macro(input: TokenStream) -> TokenStream {
     // parse
     let ast = parse_macro_input!(input as DeriveInput);
+    if let Err(err) = check_fields(&ast, "Object3DSize", &OBJECT_3D_SIZE_FIELDS) {
+        return err.to_compile_error().into();
+    }
 
     let ident = ast.ident;
 
@@ -82,9 +158,48 @@ pub fn object_3d_size_derive_macro(input: TokenStream) -> TokenStream {
 
             fn set_size(&mut self, size: Vector3) {
                 self.size = size;
+                self.recalculate_transform();
             }
         }
     };
 
     TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_fields_missing() {
+        let ast: DeriveInput = syn::parse_quote! {
+            struct Marker {
+                transform: Mat4,
+                right: Vector3,
+                up: Vector3,
+                position: Vector3,
+                rotation: Vector3,
+                orientation: Option<Rotor3>,
+            }
+        };
+        let err = check_fields(&ast, "Object3D", &OBJECT_3D_FIELDS).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Object3D requires a field `front: Vector3`."
+        );
+        assert!(err.to_compile_error().to_string().contains("compile_error"));
+
+        let err = check_fields(&ast, "Object3DSize", &OBJECT_3D_SIZE_FIELDS).unwrap_err();
+        assert!(err.to_string().contains("`size: Vector3`"));
+    }
+
+    #[test]
+    fn test_check_fields_unit_struct() {
+        let ast: DeriveInput = syn::parse_quote! { struct Marker; };
+        let err = check_fields(&ast, "Object3D", &OBJECT_3D_FIELDS).unwrap_err();
+        assert!(err.to_string().contains("`transform: Mat4`"));
+
+        let ast: DeriveInput = syn::parse_quote! { struct Sized { size: Vector3 } };
+        assert!(check_fields(&ast, "Object3DSize", &OBJECT_3D_SIZE_FIELDS).is_ok());
+    }
+}
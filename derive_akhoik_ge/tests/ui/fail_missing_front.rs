@@ -0,0 +1,17 @@
+use derive_akhoik_ge::Object3D;
+
+struct Mat4;
+struct Vector3;
+struct Rotor3;
+
+#[derive(Object3D)]
+struct Marker {
+    transform: Mat4,
+    right: Vector3,
+    up: Vector3,
+    position: Vector3,
+    rotation: Vector3,
+    orientation: Option<Rotor3>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use derive_akhoik_ge::Object3DSize;
+
+struct Vector3;
+
+#[derive(Object3DSize)]
+struct Marker {
+    scale: Vector3,
+}
+
+fn main() {}
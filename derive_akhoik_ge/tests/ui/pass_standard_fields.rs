@@ -0,0 +1,46 @@
+#![allow(unexpected_cfgs)] // the game client's `serde` feature isn't declared here
+
+// the game client is a binary, so the modules the derive expands against are included directly
+#[path = "../../../src/datatypes/vectors.rs"]
+#[allow(dead_code)]
+mod vectors;
+#[path = "../../../src/entities/traits/object_3d.rs"]
+#[allow(dead_code)]
+mod object_3d;
+
+mod datatypes {
+    pub(crate) use crate::vectors;
+}
+
+use derive_akhoik_ge::Object3D;
+use object_3d::{Object3D, calculate_transform, euler_to_orientation};
+use ultraviolet::{Mat4, Rotor3};
+use vectors::Vector3;
+
+#[derive(Object3D)]
+struct Marker {
+    transform: Mat4,
+    front: Vector3,
+    right: Vector3,
+    up: Vector3,
+    position: Vector3,
+    rotation: Vector3,
+    orientation: Option<Rotor3>,
+}
+
+fn main() {
+    let mut marker = Marker {
+        transform: Mat4::identity(),
+        front: -Vector3::forward(),
+        right: Vector3::right(),
+        up: Vector3::up(),
+        position: Vector3::zero(),
+        rotation: Vector3::zero(),
+        orientation: None,
+    };
+
+    // rotating updates the direction vectors
+    marker.rotate(Vector3::new(0.0, 90.0, 0.0));
+    assert!((marker.get_front() + Vector3::right()).length() < 1e-5);
+    assert_eq!(marker.transform, marker.calculate_transform());
+}
@@ -1,12 +1,23 @@
 //! Defines datatypes for colors. Stores:
 //! - `Color3`: *RGB*
-use std::{error::Error, fmt};
+//! - `Color4`: *RGBA*
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
 
 /// The floating point type used for a color's components
 pub type ColorComp = f32;
 
 /// A color with the components of red, green and blue, all between the values of 0.0 and 1.0
+/// # Note
+/// With the `serde` feature, the color is (de)serialized as an array of `[r, g, b]`.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[ColorComp; 3]", into = "[ColorComp; 3]")
+)]
 pub struct Color3 {
     /// Red component of the color
     pub r: ColorComp,
@@ -100,56 +111,30 @@ impl Color3 {
 
     /// Converts the color to HSV color space.
     /// # Returns
-    /// A tuple of (h, s, v)
-    pub fn to_hsv(&self) -> (i32, f32, f32) {
+    /// A tuple of (h, s, v), where the hue is in degrees (from 0.0 to 360.0) and the satruation
+    /// and value are from 0.0 to 1.0
+    /// # Note
+    /// Greys (where the satruation is 0.0) have a hue of 0.0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
         let Color3 { r, g, b } = *self;
 
         let v = r.max(g.max(b));
         let min = r.min(g.min(b));
-
         let c = v - min;
 
-        let s = { if v == 0.0 { 0.0 } else { c / v } };
-
-        let h_r = {
-            if v == min {
-                0.0
-            } else if v == r {
-                (g - b) / c
-            } else if v == g {
-                (b - r) / c + 2.0
-            } else if v == b {
-                (r - g) / c + 4.0
-            } else {
-                unreachable!()
-            }
-        } as i32;
+        let s = if v == 0.0 { 0.0 } else { c / v };
 
-        let h = (h_r + 360) % 360;
+        let h = if c == 0.0 {
+            0.0
+        } else if v == r {
+            60.0 * ((g - b) / c).rem_euclid(6.0)
+        } else if v == g {
+            60.0 * ((b - r) / c + 2.0)
+        } else {
+            60.0 * ((r - g) / c + 4.0)
+        };
 
         (h, s, v)
-        // let min = r.min(g.min(b));
-        // let v = r.min(g.min(b));
-        // let c = v - min;
-        // println!("{}", c);
-        //
-        // let s = { if v == 0.0 { 0.0 } else { c / v } };
-        //
-        // let h = {
-        //     if c == 0.0 {
-        //         0.0
-        //     } else if r == v {
-        //         (g - b) / c
-        //     } else if g == v {
-        //         2.0 + (b - r) / c
-        //     } else if b == v {
-        //         4.0 + (r - g) / c
-        //     } else {
-        //         unreachable!();
-        //     }
-        // } as i32;
-        //
-        // (h, s, v)
     }
 
     /// Creates a new color from RGB color space.
@@ -180,76 +165,196 @@ impl Color3 {
         Self::from_rgb(r, g, b)
     }
 
-    /// Creates a new color from HSV color space.
+    /// Creates a new color from a hex color code string.
     /// # Arguements
-    /// - `hue`: the hue (from 0 to 360)
-    /// - `sat`: the satruation (from 0.0 to 1.0)
-    /// - `val`: the value (from 0.0 to 1.0)
+    /// - `hex`: the hex code, formated `#RRGGBB` or `RRGGBB` (case-insensitive)
     /// # Returns
-    /// A result, either:
-    /// - `Color3`,
-    /// - An error message
-    pub fn from_hsv(hue: i32, sat: f32, val: f32) -> Result<Self, HSVConvertErr> {
-        if !(0..360).contains(&hue) {
-            return Err(HSVConvertErr::HueOutOfRange);
+    /// Either:
+    /// - A color,
+    /// - An error message, when the length is wrong or there are non-hex digits
+    pub fn from_hex_str(hex: &str) -> Result<Self, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if digits.len() != 6 {
+            return Err(format!(
+                "hex color '{}' should have 6 digits, got {}",
+                hex,
+                digits.len()
+            ));
         }
 
-        if !(0.0..=1.0).contains(&sat) {
-            return Err(HSVConvertErr::SatruationOutOfRange);
+        if let Some(invalid) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "hex color '{}' has an invalid digit '{}'",
+                hex, invalid
+            ));
         }
 
-        if !(0.0..=1.0).contains(&val) {
-            return Err(HSVConvertErr::ValueOutOfRange);
-        }
+        // every digit is ascii, so this can't fail
+        let code = u32::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+        Ok(Self::from_hex(code))
+    }
+
+    /// Converts the color to a hex color code string.
+    /// # Returns
+    /// A hex code (formated `#rrggbb`)
+    /// # Note
+    /// Each component is rounded to the nearest value between 0 and 255.
+    pub fn to_hex_string(&self) -> String {
+        let [r, g, b] =
+            [self.r, self.g, self.b].map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    /// Creates a new color from HSV color space.
+    /// # Arguements
+    /// - `hue`: the hue in degrees, wrapped between 0.0 and 360.0
+    /// - `sat`: the satruation, clamped between 0.0 and 1.0
+    /// - `val`: the value, clamped between 0.0 and 1.0
+    /// # Returns
+    /// A color
+    pub fn from_hsv(hue: f32, sat: f32, val: f32) -> Self {
+        let hue = hue.rem_euclid(360.0);
+        let (sat, val) = (sat.clamp(0.0, 1.0), val.clamp(0.0, 1.0));
 
         let c = val * sat;
-        let h = hue / 60;
-        let x = c * (1.0 - ((h as f32 % 2.0) - 1.0).abs());
-
-        let (r_q, g_q, b_q) = {
-            if (0..1).contains(&h) {
-                (c, x, 0.0)
-            } else if (1..2).contains(&h) {
-                (x, c, 0.0)
-            } else if (2..3).contains(&h) {
-                (0.0, c, x)
-            } else if (3..4).contains(&h) {
-                (0.0, x, c)
-            } else if (4..5).contains(&h) {
-                (x, 0.0, c)
-            } else if (5..6).contains(&h) {
-                (c, 0.0, x)
-            } else {
-                unreachable!()
-            }
+        let h = hue / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+
+        // `rem_euclid` can round up to 360.0, which is the same sector as 0.0
+        let (r_q, g_q, b_q) = match (h as u32) % 6 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
         };
 
         let m = val - c;
 
-        Ok(Self::new(r_q + m, g_q + m, b_q + m).unwrap())
+        Self {
+            r: (r_q + m).clamp(0.0, 1.0),
+            g: (g_q + m).clamp(0.0, 1.0),
+            b: (b_q + m).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Iterates over the components of the color.
+    /// # Returns
+    /// An iterator of the components, in the order of: `r`, `g` and `b` (the same order as
+    /// indexing)
+    pub fn iter(&self) -> impl Iterator<Item = ColorComp> {
+        [self.r, self.g, self.b].into_iter()
+    }
+
+    /// Applies a function to each component of the color.
+    /// # Arguements
+    /// - `f`: the function applied to each component
+    /// # Returns
+    /// The transformed color, with each component clamped between 0.0 and 1.0
+    pub fn map(self, f: impl Fn(ColorComp) -> ColorComp) -> Self {
+        Self {
+            r: f(self.r).clamp(0.0, 1.0),
+            g: f(self.g).clamp(0.0, 1.0),
+            b: f(self.b).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Linearly interpolates between 2 colors, for each component.
+    /// # Arguements
+    /// - `other`: the color interpolated to
+    /// - `t`: the interpolation, clamped between 0.0 (`self`) and 1.0 (`other`)
+    /// # Returns
+    /// The interpolated color, with each component clamped between 0.0 and 1.0
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self {
+            r: (self.r + (other.r - self.r) * t).clamp(0.0, 1.0),
+            g: (self.g + (other.g - self.g) * t).clamp(0.0, 1.0),
+            b: (self.b + (other.b - self.b) * t).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Multiplies the brightness of the color.
+    /// # Arguements
+    /// - `factor`: multiplies every component
+    /// # Returns
+    /// The scaled color, with each component clamped between 0.0 and 1.0
+    pub fn scale(self, factor: f32) -> Self {
+        self.map(|c| c * factor)
+    }
+
+    /// Checks if 2 colors are equal, allowing for rounding errors.
+    /// # Arguements
+    /// - `other`: the other color
+    /// - `epsilon`: the largest difference of a component, see `DEFAULT_EPSILON`
+    /// # Returns
+    /// If every component is within `epsilon` of the other color's
+    pub fn approx_eq(self, other: Self, epsilon: ColorComp) -> bool {
+        self.iter()
+            .zip(other.iter())
+            .all(|(a, b)| (a - b).abs() <= epsilon)
     }
 }
 
-/// An error thrown inside HSV color space conversion.
-/// # Used in
-/// - `Color3.from_hsv`
-#[derive(Debug)]
-pub enum HSVConvertErr {
-    /// When the hue is not in the range of 0 <= hue < 360.
-    HueOutOfRange,
-    /// When the satruation is not in the range of 0.0 <= satruation <= 1.0.
-    SatruationOutOfRange,
-    /// When the value is not in the range of 0.0 <= value <= 1.0/
-    ValueOutOfRange,
+impl FromIterator<ColorComp> for Color3 {
+    /// Creates a color from the components, in the order of: `r`, `g` and `b`.
+    /// # Note
+    /// - Missing components are set to 0.0, excess components are ignored.
+    /// - Components are clamped between 0.0 and 1.0.
+    fn from_iter<T: IntoIterator<Item = ColorComp>>(iter: T) -> Self {
+        let mut color = Self::black();
+        for (i, comp) in iter.into_iter().take(3).enumerate() {
+            color[i] = comp.clamp(0.0, 1.0);
+        }
+        color
+    }
 }
 
-impl fmt::Display for HSVConvertErr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "error when converting HSV color space: {self:?}")
+impl Index<usize> for Color3 {
+    type Output = ColorComp;
+
+    /// Gets a component, where 0 is `r`, 1 is `g` and 2 is `b`.
+    /// # Panics
+    /// When the index is greater than 2.
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.r,
+            1 => &self.g,
+            2 => &self.b,
+            _ => panic!("index {index} out of range for Color3"),
+        }
     }
 }
 
-impl Error for HSVConvertErr {}
+impl IndexMut<usize> for Color3 {
+    /// Gets a mutable component, where 0 is `r`, 1 is `g` and 2 is `b`.
+    /// # Panics
+    /// When the index is greater than 2.
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.r,
+            1 => &mut self.g,
+            2 => &mut self.b,
+            _ => panic!("index {index} out of range for Color3"),
+        }
+    }
+}
+
+impl From<[ColorComp; 3]> for Color3 {
+    /// Creates a color from `[r, g, b]`.
+    /// # Note
+    /// Components are clamped between 0.0 and 1.0, like `from_iter`.
+    fn from(comps: [ColorComp; 3]) -> Self {
+        comps.into_iter().collect()
+    }
+}
+
+impl From<Color3> for [ColorComp; 3] {
+    fn from(color: Color3) -> Self {
+        [color.r, color.g, color.b]
+    }
+}
 
 impl Default for Color3 {
     fn default() -> Self {
@@ -262,3 +367,104 @@ impl fmt::Display for Color3 {
         write!(formatter, "color3({}, {}, {})", self.r, self.g, self.b)
     }
 }
+
+/// A color with the components of red, green, blue and alpha, all between the values of 0.0 and 1.0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color4 {
+    /// Red component of the color
+    pub r: ColorComp,
+    /// Green component of the color
+    pub g: ColorComp,
+    /// Blue component of the color
+    pub b: ColorComp,
+    /// Alpha (opacity) component of the color, where 0.0 is transparent
+    pub a: ColorComp,
+}
+impl Color4 {
+    /// Creates a new color, with parameters all between the value of 0.0 and 1.0
+    /// # Arguements
+    /// - `r`: red
+    /// - `g`: green
+    /// - `b`: blue
+    /// - `a`: alpha
+    /// # Returns
+    /// Either:
+    /// - `None` when any of the components are out of range
+    /// - `Some`: a color
+    pub fn new(r: ColorComp, g: ColorComp, b: ColorComp, a: ColorComp) -> Option<Self> {
+        if [r, g, b, a].iter().any(|c| !(0.0..=1.0).contains(c)) {
+            // values need to be between 0.0 and 1.0
+            return None;
+        }
+
+        Some(Self { r, g, b, a })
+    }
+
+    /// Creates a new color from RGBA color space.
+    /// # Arguements
+    /// - `r`: red
+    /// - `g`: green
+    /// - `b`: blue
+    /// - `a`: alpha
+    /// # Returns
+    /// A color
+    pub fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            r: r as ColorComp / 255.0,
+            g: g as ColorComp / 255.0,
+            b: b as ColorComp / 255.0,
+            a: a as ColorComp / 255.0,
+        }
+    }
+
+    /// Converts the color to RGBA color space.
+    /// # Returns
+    /// A tuple of (r, g, b, a)
+    pub fn to_rgba(&self) -> (u8, u8, u8, u8) {
+        (
+            (self.r * 255.0) as u8,
+            (self.g * 255.0) as u8,
+            (self.b * 255.0) as u8,
+            (self.a * 255.0) as u8,
+        )
+    }
+
+    /// Gets the color without the alpha component.
+    /// # Returns
+    /// A `Color3`
+    pub fn rgb(&self) -> Color3 {
+        Color3 {
+            r: self.r,
+            g: self.g,
+            b: self.b,
+        }
+    }
+}
+
+impl From<Color3> for Color4 {
+    /// Converts the color, with an alpha of 1.0 (opaque).
+    fn from(color: Color3) -> Self {
+        Self {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: 1.0,
+        }
+    }
+}
+
+impl Default for Color4 {
+    fn default() -> Self {
+        Color3::white().into()
+    }
+}
+
+impl fmt::Display for Color4 {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "color4({}, {}, {}, {})",
+            self.r, self.g, self.b, self.a
+        )
+    }
+}
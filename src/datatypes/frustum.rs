@@ -0,0 +1,52 @@
+//! Defines the `Frustum` datatype, the volume a camera can see, used for culling.
+
+use ultraviolet::{Mat4, Vec4};
+
+use crate::datatypes::vectors::Vector3;
+
+/// The volume a camera can see, made of 6 planes facing inwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// The left, right, bottom, top, near and far planes.
+    /// # Note
+    /// Each plane is `(normal.x, normal.y, normal.z, distance)`, a point is inside of the plane
+    /// when `normal · point + distance >= 0`. The planes aren't normalised.
+    pub planes: [Vec4; 6],
+}
+impl Frustum {
+    /// Extracts the frustum from a view-projection matrix (Gribb-Hartmann).
+    /// # Arguements
+    /// - `view_projection`: the matrix, see `Camera::get_view_projection`
+    /// # Returns
+    /// The frustum, in world space
+    /// # Note
+    /// Assumes GL's clip space, where visable points are within `-w` to `w` on every axis.
+    pub fn from_matrix(view_projection: Mat4) -> Self {
+        let cols = view_projection.cols;
+        let row = |i: usize| Vec4::new(cols[0][i], cols[1][i], cols[2][i], cols[3][i]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+
+        Self {
+            planes: [w + x, w - x, w + y, w - y, w + z, w - z],
+        }
+    }
+
+    /// Checks if an axis-aligned box is inside of, or intersects, the frustum.
+    /// # Arguements
+    /// - `min`: the minimum corner of the box
+    /// - `max`: the maximum corner of the box
+    /// # Returns
+    /// `false` only if the box is fully outside of a plane
+    /// # Note
+    /// Conservative: a box near a corner of the frustum can be outside of it, but still be
+    /// kept.
+    pub fn contains_aabb(&self, min: Vector3, max: Vector3) -> bool {
+        self.planes.iter().all(|plane| {
+            // the corner furthest along the plane's normal
+            let x = if plane.x >= 0.0 { max.x } else { min.x };
+            let y = if plane.y >= 0.0 { max.y } else { min.y };
+            let z = if plane.z >= 0.0 { max.z } else { min.z };
+            plane.x * x + plane.y * y + plane.z * z + plane.w >= 0.0
+        })
+    }
+}
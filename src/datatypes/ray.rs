@@ -0,0 +1,52 @@
+//! Defines the `Ray` datatype, a half-line used for picking and casting.
+
+use ultraviolet::Mat4;
+
+use crate::datatypes::vectors::Vector3;
+
+/// A half-line, starting at the `origin` going along the `direction`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    /// Where the ray starts
+    pub origin: Vector3,
+    /// The direction of the ray
+    /// # Note
+    /// Distances along the ray are measured in lengths of `direction`, so use a unit vector to
+    /// measure in world units.
+    pub direction: Vector3,
+}
+impl Ray {
+    /// Creates a new ray.
+    /// # Arguements
+    /// - `origin`: where the ray starts
+    /// - `direction`: the direction of the ray
+    /// # Returns
+    /// A `Ray`
+    pub const fn new(origin: Vector3, direction: Vector3) -> Self {
+        Self { origin, direction }
+    }
+
+    /// Gets a point along the ray.
+    /// # Arguements
+    /// - `distance`: how far along the ray, in lengths of `direction`
+    /// # Returns
+    /// `origin + direction * distance`
+    pub fn at(&self, distance: f32) -> Vector3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Transforms the ray by a matrix.
+    /// # Arguements
+    /// - `matrix`: the transformation
+    /// # Returns
+    /// The transformed ray
+    /// # Note
+    /// The direction isn't normalised, so a distance along the transformed ray is the same
+    /// point as the distance along the original.
+    pub fn transformed(&self, matrix: Mat4) -> Self {
+        Self {
+            origin: matrix.transform_point3(self.origin.into()).into(),
+            direction: matrix.transform_vec3(self.direction.into()).into(),
+        }
+    }
+}
@@ -1,11 +1,57 @@
 //! Defines datatypes for vector, that describes a position. Stores:
 //! - `Vector3`: A 3D position
 //! - `Vector2`: A 2D position
-
-use std::ops::{Add, Div, Mul, Neg, Sub};
+//!
+//! With the `serde` feature, vectors are (de)serialized as arrays of their components.
+
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use ultraviolet::Vec3;
+
+/// The smallest length of a vector, before it is considered to be zero.
+const NEAR_ZERO_LENGTH: f32 = 1e-6;
+
+/// The default tolerance of `approx_eq`, and `assert_approx_eq!` when an epsilon isn't given.
+pub const DEFAULT_EPSILON: f32 = 1e-5;
+
+/// Asserts that 2 values are approximately equal, with their `approx_eq` method.
+/// # Arguements
+/// - `left`, `right`: the values, e.g. `Vector3`, `Vector2` or `Color3`
+/// - `epsilon`: optional, the largest difference of a component (`DEFAULT_EPSILON` by default)
+/// # Panics
+/// When the values aren't approximately equal, printing both of them.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_approx_eq!($left, $right, $crate::datatypes::vectors::DEFAULT_EPSILON)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {{
+        let (left, right, epsilon) = ($left, $right, $epsilon);
+        assert!(
+            left.approx_eq(right, epsilon),
+            "assertion `left ≈ right` failed (epsilon: {epsilon})\n  left: {left:?}\n right: {right:?}"
+        );
+    }};
+}
 
 /// A vector with 3 axes; used to describe a 3D point.
+/// # Convention
+/// The basis vectors are:
+/// - `right()`: +X, `(1, 0, 0)`
+/// - `up()`: +Y, `(0, 1, 0)`
+/// - `forward()`: +Z, `(0, 0, 1)`
+///
+/// Where `right().cross(up()) == forward()`, `up().cross(forward()) == right()` and
+/// `forward().cross(right()) == up()`.
+///
+/// Objects face -Z like OpenGL's cameras, so without a rotation their direction vectors (see
+/// `Object3D::update_vectors`) are `right()`, `up()` and a `front` of `-forward()`.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[f32; 3]", into = "[f32; 3]")
+)]
 pub struct Vector3 {
     /// The x-axis
     pub x: f32,
@@ -33,7 +79,7 @@ impl Vector3 {
         }
     }
 
-    /// A vector of 1.0, 0.0, 0.0
+    /// A vector of 1.0, 0.0, 0.0 (+X)
     pub const fn right() -> Self {
         Self {
             x: 1.0,
@@ -42,7 +88,7 @@ impl Vector3 {
         }
     }
 
-    /// A vector of 0.0, 1.0, 0.0
+    /// A vector of 0.0, 1.0, 0.0 (+Y)
     pub const fn up() -> Self {
         Self {
             x: 0.0,
@@ -51,7 +97,7 @@ impl Vector3 {
         }
     }
 
-    /// A vector of 0.0, 0.0, 1.0
+    /// A vector of 0.0, 0.0, 1.0 (+Z)
     pub const fn forward() -> Self {
         Self {
             x: 0.0,
@@ -67,7 +113,7 @@ impl Vector3 {
     /// - `z`: z axis
     /// # Returns
     /// A 3D vector
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
         Self { x, y, z }
     }
 
@@ -89,25 +135,215 @@ impl Vector3 {
         (self.x * other.x) + (self.y * other.y) + (self.z * other.z)
     }
 
+    /// Gets the length of the vector.
+    /// # Returns
+    /// The length
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    /// Gets the squared length of the vector, avoiding a square root.
+    /// # Returns
+    /// The squared length
+    /// # Note
+    /// Useful for comparing lengths.
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
     /// Gets the magnitude of the vector.
     /// # Returns
     /// The magnitude
+    /// # Note
+    /// The same as `length`.
     pub fn get_magnitude(self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+        self.length()
     }
 
     /// Gets the unit vector (where the magnitude is equal to 1.0).
     /// # Returns
     /// The unit vector
     /// # Note
-    /// If the vector's axes are all equal to 0.0, then it returns `zero()`.
+    /// If the vector's length is near 0.0, then it returns `zero()` instead of `NaN`.
     pub fn get_unit(self) -> Self {
-        let zero = Self::zero();
-        if self == zero {
-            return zero;
+        let length = self.length();
+        if length < NEAR_ZERO_LENGTH {
+            return Self::zero();
         }
 
-        self / self.get_magnitude()
+        self / length
+    }
+
+    /// Gets the distance between 2 points.
+    /// # Arguements
+    /// - `other`: the second point
+    /// # Returns
+    /// The distance
+    pub fn distance(self, other: Self) -> f32 {
+        (self - other).length()
+    }
+
+    /// Gets the squared distance between 2 points, avoiding a square root.
+    /// # Arguements
+    /// - `other`: the second point
+    /// # Returns
+    /// The squared distance
+    pub fn distance_squared(self, other: Self) -> f32 {
+        (self - other).length_squared()
+    }
+
+    /// Gets the angle between 2 vectors.
+    /// # Arguements
+    /// - `other`: the second vector
+    /// # Returns
+    /// The angle in radians, between 0.0 and `PI`
+    /// # Note
+    /// If either vector's length is near 0.0, then it returns 0.0.
+    pub fn angle_between(self, other: Self) -> f32 {
+        let lengths = self.length() * other.length();
+        if lengths < NEAR_ZERO_LENGTH {
+            return 0.0;
+        }
+
+        // clamped, since rounding can push the cosine slightly past -1.0 or 1.0
+        (self.dot(other) / lengths).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Linearly interpolates between 2 vectors.
+    /// # Arguements
+    /// - `target`: the vector interpolated to
+    /// - `t`: the interpolation, clamped between 0.0 (`self`) and 1.0 (`target`)
+    /// # Returns
+    /// The interpolated vector
+    pub fn lerp(self, target: Self, t: f32) -> Self {
+        self.lerp_unclamped(target, t.clamp(0.0, 1.0))
+    }
+
+    /// Linearly interpolates between 2 vectors, without clamping `t`.
+    /// # Arguements
+    /// - `target`: the vector interpolated to
+    /// - `t`: the interpolation, where 0.0 is `self` and 1.0 is `target`
+    /// # Returns
+    /// The interpolated vector
+    /// # Note
+    /// `t` outside of 0.0 to 1.0 extrapolates past `self` or `target`.
+    pub fn lerp_unclamped(self, target: Self, t: f32) -> Self {
+        self + (target - self) * t
+    }
+
+    /// Gets the smallest of each component of 2 vectors.
+    /// # Arguements
+    /// - `other`: the other vector
+    /// # Returns
+    /// The component-wise minimum
+    pub fn min(self, other: Self) -> Self {
+        Self::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Gets the largest of each component of 2 vectors.
+    /// # Arguements
+    /// - `other`: the other vector
+    /// # Returns
+    /// The component-wise maximum
+    pub fn max(self, other: Self) -> Self {
+        Self::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Clamps each component of the vector between 2 bounds.
+    /// # Arguements
+    /// - `min`: the lower bound
+    /// - `max`: the upper bound
+    /// # Returns
+    /// The clamped vector
+    /// # Note
+    /// Unlike `f32::clamp`, this doesn't panic when `min` is greater than `max` on an axis. The
+    /// bounds are used as-is, so that component becomes `max`.
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Checks if 2 vectors are equal, allowing for rounding errors.
+    /// # Arguements
+    /// - `other`: the other vector
+    /// - `epsilon`: the largest difference of a component, see `DEFAULT_EPSILON`
+    /// # Returns
+    /// If every component is within `epsilon` of the other vector's
+    pub fn approx_eq(self, other: Self, epsilon: f32) -> bool {
+        self.iter()
+            .zip(other.iter())
+            .all(|(a, b)| (a - b).abs() <= epsilon)
+    }
+
+    /// Iterates over the components of the vector.
+    /// # Returns
+    /// An iterator of the components, in the order of: `x`, `y` and `z` (the same order as
+    /// indexing)
+    pub fn iter(&self) -> impl Iterator<Item = f32> {
+        [self.x, self.y, self.z].into_iter()
+    }
+
+    /// Applies a function to each component of the vector.
+    /// # Arguements
+    /// - `f`: the function applied to each component
+    /// # Returns
+    /// The transformed vector
+    pub fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        Self {
+            x: f(self.x),
+            y: f(self.y),
+            z: f(self.z),
+        }
+    }
+}
+
+impl FromIterator<f32> for Vector3 {
+    /// Creates a vector from the components, in the order of: `x`, `y` and `z`.
+    /// # Note
+    /// Missing components are set to 0.0, excess components are ignored.
+    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+        let mut vector = Self::zero();
+        for (i, comp) in iter.into_iter().take(3).enumerate() {
+            vector[i] = comp;
+        }
+        vector
+    }
+}
+
+impl Index<usize> for Vector3 {
+    type Output = f32;
+
+    /// Gets a component, where 0 is `x`, 1 is `y` and 2 is `z`.
+    /// # Panics
+    /// When the index is greater than 2.
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("index {index} out of range for Vector3"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector3 {
+    /// Gets a mutable component, where 0 is `x`, 1 is `y` and 2 is `z`.
+    /// # Panics
+    /// When the index is greater than 2.
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("index {index} out of range for Vector3"),
+        }
     }
 }
 
@@ -135,6 +371,18 @@ impl Sub for Vector3 {
     }
 }
 
+impl AddAssign for Vector3 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vector3 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
 impl Mul<Vector3> for Vector3 {
     type Output = Vector3;
 
@@ -159,6 +407,18 @@ impl Mul<f32> for Vector3 {
     }
 }
 
+impl MulAssign<Vector3> for Vector3 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<f32> for Vector3 {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
 impl Div<Vector3> for Vector3 {
     type Output = Vector3;
 
@@ -197,16 +457,41 @@ impl Neg for Vector3 {
 
 impl Default for Vector3 {
     fn default() -> Self {
-        Self {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        }
+        Self::zero()
+    }
+}
+
+impl From<Vec3> for Vector3 {
+    fn from(value: Vec3) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
+}
+
+impl From<Vector3> for Vec3 {
+    fn from(value: Vector3) -> Self {
+        Vec3::new(value.x, value.y, value.z)
+    }
+}
+
+impl From<[f32; 3]> for Vector3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl From<Vector3> for [f32; 3] {
+    fn from(value: Vector3) -> Self {
+        [value.x, value.y, value.z]
     }
 }
 
 /// A vector with 2 axes; used to describe a 2D point.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[f32; 2]", into = "[f32; 2]")
+)]
 pub struct Vector2 {
     /// The x-axis
     pub x: f32,
@@ -215,22 +500,22 @@ pub struct Vector2 {
 }
 impl Vector2 {
     /// A vector of 0.0, 0.0
-    pub fn zero() -> Self {
+    pub const fn zero() -> Self {
         Self { x: 0.0, y: 0.0 }
     }
 
     /// A vector of 1.0, 1.0
-    pub fn one() -> Self {
+    pub const fn one() -> Self {
         Self { x: 1.0, y: 1.0 }
     }
 
     /// A vector of 1.0, 0.0
-    pub fn right() -> Self {
+    pub const fn right() -> Self {
         Self { x: 1.0, y: 0.0 }
     }
 
     /// A vector of 0.0, 1.0
-    pub fn up() -> Self {
+    pub const fn up() -> Self {
         Self { x: 0.0, y: 1.0 }
     }
 
@@ -240,36 +525,84 @@ impl Vector2 {
     /// - `y`: y axis
     /// # Returns
     /// A 2D vector
-    pub fn new(x: f32, y: f32) -> Self {
+    pub const fn new(x: f32, y: f32) -> Self {
         Self { x, y }
     }
 
     /// Gets the dot product of 2 vectors.
     /// # Arguements
     /// - `other`: the second vector
-    pub fn dot(&self, other: &Self) -> f32 {
+    pub fn dot(self, other: Self) -> f32 {
         (self.x * other.x) + (self.y * other.y)
     }
 
+    /// Gets the length of the vector.
+    /// # Returns
+    /// The length
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    /// Gets the squared length of the vector, avoiding a square root.
+    /// # Returns
+    /// The squared length
+    /// # Note
+    /// Useful for comparing lengths.
+    pub fn length_squared(self) -> f32 {
+        self.dot(self)
+    }
+
     /// Get the magnitude of a vector.
     /// # Returns
     /// The magnitude
-    pub fn get_magnitude(&self) -> f32 {
-        (self.x * self.x + self.y * self.y).sqrt()
+    /// # Note
+    /// The same as `length`.
+    pub fn get_magnitude(self) -> f32 {
+        self.length()
     }
 
     /// Gets the unit vector (where the magnitude is equals to 1.0).
     /// # Returns
     /// the unit vector
     /// # Note
-    /// If the vecot's axes are all equal to 0.0, then it returns `zero()`.
-    pub fn get_unit(&self) -> Self {
-        let zero = Self::zero();
-        if *self == zero {
-            return zero;
+    /// If the vector's length is near 0.0, then it returns `zero()` instead of `NaN`.
+    pub fn get_unit(self) -> Self {
+        let length = self.length();
+        if length < NEAR_ZERO_LENGTH {
+            return Self::zero();
         }
 
-        *self / self.get_magnitude()
+        self / length
+    }
+
+    /// Linearly interpolates between 2 vectors.
+    /// # Arguements
+    /// - `target`: the vector interpolated to
+    /// - `t`: the interpolation, clamped between 0.0 (`self`) and 1.0 (`target`)
+    /// # Returns
+    /// The interpolated vector
+    pub fn lerp(self, target: Self, t: f32) -> Self {
+        self.lerp_unclamped(target, t.clamp(0.0, 1.0))
+    }
+
+    /// Linearly interpolates between 2 vectors, without clamping `t`.
+    /// # Arguements
+    /// - `target`: the vector interpolated to
+    /// - `t`: the interpolation, where 0.0 is `self` and 1.0 is `target`
+    /// # Returns
+    /// The interpolated vector
+    pub fn lerp_unclamped(self, target: Self, t: f32) -> Self {
+        self + (target - self) * t
+    }
+
+    /// Checks if 2 vectors are equal, allowing for rounding errors.
+    /// # Arguements
+    /// - `other`: the other vector
+    /// - `epsilon`: the largest difference of a component, see `DEFAULT_EPSILON`
+    /// # Returns
+    /// If every component is within `epsilon` of the other vector's
+    pub fn approx_eq(self, other: Self, epsilon: f32) -> bool {
+        (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon
     }
 }
 
@@ -311,12 +644,36 @@ impl Mul<f32> for Vector2 {
 
     fn mul(self, rhs: f32) -> Self::Output {
         Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
+            x: self.x * rhs,
+            y: self.y * rhs,
         }
     }
 }
 
+impl AddAssign for Vector2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vector2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<Vector2> for Vector2 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<f32> for Vector2 {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
 impl Div<Vector2> for Vector2 {
     type Output = Vector2;
 
@@ -352,6 +709,18 @@ impl Neg for Vector2 {
 
 impl Default for Vector2 {
     fn default() -> Self {
-        Self { x: 0.0, y: 0.0 }
+        Self::zero()
+    }
+}
+
+impl From<[f32; 2]> for Vector2 {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Vector2> for [f32; 2] {
+    fn from(value: Vector2) -> Self {
+        [value.x, value.y]
     }
 }
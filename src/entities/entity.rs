@@ -1,16 +1,17 @@
 //! Contains the `Entity`, `EntityType` and many critial entity types, such as: `GameType`.
 
-use std::fmt;
+use std::{collections::HashSet, fmt};
 use uuid::Uuid;
 
-use crate::entities::types::{
-    camera_type::Camera, game_type::Game, io_service::InputService, part_type::Part,
+use crate::entities::{
+    traits::update::Update,
+    types::{camera_type::Camera, game_type::Game, io_service::InputService, part_type::Part},
 };
 
 // TODO: Wrap EntityType's items with Box<>
 trait_enum::trait_enum! {
 /// The type of entity
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EntityType: EntityTrait {
     /// The base class for enums stores `nothing`.
     Base,
@@ -26,19 +27,43 @@ pub enum EntityType: EntityTrait {
 }
 }
 
+impl EntityType {
+    /// Gets the name of the entity type.
+    /// # Returns
+    /// The name, the same as the variant's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Base(_) => "Base",
+            Self::Game(_) => "Game",
+            Self::Part(_) => "Part",
+            Self::Camera(_) => "Camera",
+            Self::InputService(_) => "InputService",
+        }
+    }
+
+    /// Gets the entity type as an `Update`, if it has per-frame logic.
+    /// # Returns
+    /// Either:
+    /// - The borrowed type
+    /// - `None`, if the type doesn't implement `Update`
+    pub fn as_update_mut(&mut self) -> Option<&mut dyn Update> {
+        match self {
+            Self::Game(game) => Some(game),
+            Self::Base(_) | Self::Part(_) | Self::Camera(_) | Self::InputService(_) => None,
+        }
+    }
+}
+
 /// A trait that every entity should use.
+/// # Note
+/// Per-frame logic uses the `Update` trait instead.
 pub trait EntityTrait {
-    /// Gets called every frame.
-    /// # Arguements
-    /// - `delta`: the time between the last to second to last frame
-    fn update(&mut self, _delta: f32) {}
-
     /// Starts and initializes the entity.
     fn start(&mut self) {}
 }
 
 /// The base entity: has no propetries or unique methods.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Base;
 impl EntityTrait for Base {}
 
@@ -58,8 +83,13 @@ pub struct Entity {
     pub children_id: Vec<Uuid>,
     /// Marks the entity as created, before the current frame.
     pub newly_created: bool,
+    /// Is the entity, and it's descendents, visable to the renderer. Unused by parts, which use
+    /// `Part::visable` instead.
+    visible: bool,
     /// The non-unique name of the entity.
     name: String,
+    /// The tags used to group entities, see `EntityTree::find_by_tag`.
+    tags: HashSet<String>,
     /// The type of entity
     entity_type: Box<EntityType>,
     /// A unique identifier of the entity
@@ -84,6 +114,20 @@ impl Entity {
         }
     }
 
+    /// Creates a new entity with a known `uuid`, e.g. when loading a scene.
+    /// # Arguements
+    /// - `name`: The name of the Entity
+    /// - `entity_type`: The type of the Entity
+    /// - `uuid`: The unique identifier, which must not be used by another entity in the tree
+    /// # Returns
+    /// `Self`
+    pub(crate) fn with_uuid(name: &str, entity_type: Box<EntityType>, uuid: Uuid) -> Self {
+        Self {
+            uuid,
+            ..Self::new(name, entity_type)
+        }
+    }
+
     /// Gets the current name of the Entity.
     /// # Returns
     /// The name of the entity.
@@ -91,7 +135,7 @@ impl Entity {
         self.name.as_str()
     }
 
-    /// Gets the read-only non-unique identifer of the Entity.
+    /// Gets the read-only unique identifer of the Entity.
     /// # Returns
     /// The `Uuid` of the entity.
     pub fn get_uuid(&self) -> Uuid {
@@ -106,6 +150,42 @@ impl Entity {
         self.name = name_str;
     }
 
+    // Tags
+
+    /// Adds a tag to the entity, used to group entities (e.g. "enemy").
+    /// # Arguements
+    /// - `tag`: the tag
+    /// # Returns
+    /// If the entity didn't already have the tag
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        self.tags.insert(tag.to_string())
+    }
+
+    /// Checks if the entity has a tag.
+    /// # Arguements
+    /// - `tag`: the tag
+    /// # Returns
+    /// If the entity has the tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Removes a tag from the entity.
+    /// # Arguements
+    /// - `tag`: the tag
+    /// # Returns
+    /// If the entity had the tag
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    /// Iterates over the tags of the entity.
+    /// # Returns
+    /// An iterator of the tags, in no particular order
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
     /// Gets the `EntityType` of the entity.
     pub fn get_type(&self) -> &EntityType {
         &self.entity_type
@@ -115,16 +195,60 @@ impl Entity {
     pub fn get_type_mut(&mut self) -> &mut EntityType {
         &mut self.entity_type
     }
+
+    /// Checks if the entity is visable, ignoring it's ancestors.
+    /// # Returns
+    /// If the entity is visable
+    /// # Note
+    /// - Use `EntityTree::is_visible` to include the ancestors.
+    /// - The same as `Part::visable` for parts.
+    pub fn is_visible(&self) -> bool {
+        match self.get_type() {
+            EntityType::Part(part) => part.visable,
+            _ => self.visible,
+        }
+    }
+
+    /// Shows or hides the entity, and it's descendents.
+    /// # Arguements
+    /// - `visible`: if the entity should be visable
+    /// # Note
+    /// - Any entity type can be hidden, e.g. hiding a `Base` hides every part under it.
+    /// - The same as setting `Part::visable` for parts.
+    pub fn set_visible(&mut self, visible: bool) {
+        match self.get_type_mut() {
+            EntityType::Part(part) => part.visable = visible,
+            _ => self.visible = visible,
+        }
+    }
+
+    /// Copies the entity's data (name, type, tags and visibility), without copying it's identity.
+    /// # Note
+    /// - The copy has a new `uuid` and isn't parented to anything or has any children.
+    /// - The copy isn't included inside of the `EntityTree`.
+    /// # Returns
+    /// A new entity with the same data
+    pub fn clone_shallow(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            entity_type: self.entity_type.clone(),
+            tags: self.tags.clone(),
+            visible: self.visible,
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for Entity {
     fn default() -> Self {
         Self {
             name: "entity".to_string(),
+            tags: HashSet::new(),
             entity_type: Box::new(EntityType::Base(Base)),
             uuid: Uuid::new_v4(),
             children_id: vec![],
             newly_created: true,
+            visible: true,
             parent_id: None,
         }
     }
@@ -2,19 +2,62 @@
 
 use std::{
     cell::{Ref, RefCell, RefMut},
-    collections::HashMap,
-    ops::{Deref, DerefMut},
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Write},
+    ops::DerefMut,
     rc::Rc,
+    slice,
 };
 
+use ultraviolet::{Bivec3, Mat4, Rotor3};
 use uuid::Uuid;
 
-use crate::entities::{
-    entity::{Entity, EntityType},
-    types::{camera_type::Camera, game_type::Game},
+use crate::{
+    datatypes::{color::Color3, vectors::Vector3},
+    entities::{
+        entity::{Base, Entity, EntityType},
+        traits::object_3d::{Object3D, Object3DSize, euler_to_orientation},
+        types::{
+            camera_type::{Camera, ProjectionKind},
+            game_type::{Game, GameGenre},
+            io_service::InputService,
+            part_type::Part,
+        },
+    },
 };
 
-// TODO: Add Child, Descendent and Ancestor iterators
+/// The name of the scene section containing the `head` and `main_camera`.
+const SCENE_TREE_SECTION_NAME: &str = "Tree";
+/// The name of the scene section containing an entity.
+const SCENE_ENTITY_SECTION_NAME: &str = "Entity";
+
+/// A line of a scene section: the line number, the key and the value.
+type SceneField<'a> = (usize, &'a str, &'a str);
+
+/// A callback fired with the ID of an entity, see `EntityTree::on_added`.
+pub type EntityCallback = Box<dyn FnMut(Uuid)>;
+/// A callback fired with the ID of an entity and the ID of it's new parent, see
+/// `EntityTree::on_reparented`.
+pub type ReparentCallback = Box<dyn FnMut(Uuid, Option<Uuid>)>;
+
+/// The lifecycle callbacks registered to an `EntityTree`.
+#[derive(Default)]
+struct TreeCallbacks {
+    added: Vec<EntityCallback>,
+    removed: Vec<EntityCallback>,
+    reparented: Vec<ReparentCallback>,
+}
+
+impl fmt::Debug for TreeCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TreeCallbacks")
+            .field("added", &self.added.len())
+            .field("removed", &self.removed.len())
+            .field("reparented", &self.reparented.len())
+            .finish()
+    }
+}
 
 /// A tree of entities.
 /// Queries by a `HashMap` and `Uuid`s.
@@ -32,8 +75,27 @@ pub struct EntityTree {
     /// # Note
     /// Not to be edited directly use the provided methods instead.
     pub entity_map: HashMap<Uuid, Rc<RefCell<Entity>>>,
+    /// The lifecycle callbacks, see `on_added`, `on_removed` and `on_reparented`
+    callbacks: TreeCallbacks,
 }
 impl EntityTree {
+    /// Creates a new tree, with space reserved for entities.
+    /// # Arguements
+    /// - `entities`: the amount of entities reserved
+    /// - `parts`: the amount of parts reserved
+    /// # Returns
+    /// An empty `EntityTree`
+    /// # Note
+    /// Used to avoid reallocation when loading large amounts of entities. For smaller trees use
+    /// `EntityTree::default`.
+    pub fn with_capacity(entities: usize, parts: usize) -> Self {
+        Self {
+            entity_map: HashMap::with_capacity(entities),
+            parts: Vec::with_capacity(parts),
+            ..Self::default()
+        }
+    }
+
     /// Creates a new entity.
     /// # Arguements
     /// - `name`: The name of the entity
@@ -47,6 +109,7 @@ impl EntityTree {
         if let EntityType::Part(_) = entity.borrow().get_type() {
             self.parts.push(id);
         }
+        self.fire_added(id);
         entity
     }
 
@@ -72,20 +135,30 @@ impl EntityTree {
     }
 
     /// Adds a new head of the `Game` entity type.
+    /// # Arguements
+    /// - `genre`: the genre of the game, which picks the defaults (see `add_default_main_camera`)
     /// # Returns
     /// A reference counted RefCell of the `Entity`.
-    pub fn add_head(&mut self, game_type: Game) -> Rc<RefCell<Entity>> {
+    pub fn add_head(&mut self, genre: GameGenre) -> Rc<RefCell<Entity>> {
         let head = Rc::new(RefCell::new(Entity::new(
             "Game",
-            Box::new(EntityType::Game(game_type)),
+            Box::new(EntityType::Game(Game::new(genre))),
         )));
         let head_borrow = head.borrow_mut();
         let id = head_borrow.get_uuid();
         self.head = Some(id);
         self.entity_map.insert(id, head.clone());
+        self.fire_added(id);
         head.clone()
     }
 
+    /// Adds a new head of the `Game` entity type, with an `Action` genre.
+    /// # Returns
+    /// A reference counted RefCell of the `Entity`.
+    pub fn add_head_default(&mut self) -> Rc<RefCell<Entity>> {
+        self.add_head(GameGenre::Action)
+    }
+
     /// Gets the head of the entity type.
     /// # Returns
     /// An option of a reference counted RefCell of the `Entity`.
@@ -100,6 +173,8 @@ impl EntityTree {
     /// - `camera_type`: the camera_type variant
     /// # Returns
     /// An option of a reference counted RefCell of the camera `Entity`
+    /// # Note
+    /// The camera is used as is, use `add_default_main_camera` for the genre's defaults.
     pub fn add_main_camera(&mut self, camera_type: Camera) -> Option<Rc<RefCell<Entity>>> {
         let camera = Rc::new(RefCell::new(Entity::new(
             "Camera",
@@ -119,11 +194,32 @@ impl EntityTree {
 
         self.main_camera = Some(id);
         self.entity_map.insert(id, camera.clone());
+        self.fire_added(id);
 
         // camera_borrow.get_type_mut().start(self);
         Some(camera.clone())
     }
 
+    /// Adds a new main camera, using the defaults of the head's genre (see
+    /// `Game::default_camera`).
+    /// # Returns
+    /// An option of a reference counted RefCell of the camera `Entity`
+    /// # Note
+    /// The `Undefined` genre's defaults are used if the head isn't a `Game`.
+    /// # Panics
+    /// When the tree doesn't have a head, like `add_main_camera`.
+    pub fn add_default_main_camera(&mut self) -> Option<Rc<RefCell<Entity>>> {
+        let camera = {
+            let head = self.get_head().unwrap();
+            let head_borrow = head.borrow();
+            match head_borrow.get_type() {
+                EntityType::Game(game) => game.default_camera(),
+                _ => Game::default().default_camera(),
+            }
+        };
+        self.add_main_camera(camera)
+    }
+
     /// Gets the main camera
     /// # Returns
     /// An option of reference counted RefCell of the camera `Entity`
@@ -133,15 +229,13 @@ impl EntityTree {
         Some(self.entity_map[&camera_id].clone())
     }
 
-    // SUGGESTION: get_entity and it's variants should return a result when borrowing is
-    // unsuccessful
-    // SUGGESTION: get_entity_refcell
-
     /// Gets an entity based on the `id`.
     /// # Arguements
     /// - `id`: The unique indentifier of the entity
     /// # Returns
     /// An option to a reference to an entity
+    /// # Panics
+    /// If the entity is mutably borrowed, use `try_get_entity` instead.
     pub fn get_entity(&self, id: Uuid) -> Option<Ref<Entity>> {
         let entity = self.entity_map.get(&id)?;
         Some(entity.borrow())
@@ -152,11 +246,47 @@ impl EntityTree {
     /// - `id`: The unique indentifier of the entity
     /// # Returns
     /// An option to a mutable reference to an entity
+    /// # Panics
+    /// If the entity is borrowed, use `try_get_entity_mut` instead.
     pub fn get_entity_mut(&self, id: Uuid) -> Option<RefMut<Entity>> {
         let entity = self.entity_map.get(&id)?;
         Some(entity.borrow_mut())
     }
 
+    /// Gets an entity based on the `id`, without panicking.
+    /// # Arguements
+    /// - `id`: The unique indentifier of the entity
+    /// # Returns
+    /// Either:
+    /// - A reference to an entity
+    /// - `EntityAccessError::NotFound` or `EntityAccessError::Borrowed`
+    pub fn try_get_entity(&self, id: Uuid) -> Result<Ref<Entity>, EntityAccessError> {
+        let entity = self
+            .entity_map
+            .get(&id)
+            .ok_or(EntityAccessError::NotFound(id))?;
+        entity
+            .try_borrow()
+            .map_err(|_| EntityAccessError::Borrowed(id))
+    }
+
+    /// Gets an entity (as an mutable reference) based on the `id`, without panicking.
+    /// # Arguements
+    /// - `id`: The unique indentifier of the entity
+    /// # Returns
+    /// Either:
+    /// - A mutable reference to an entity
+    /// - `EntityAccessError::NotFound` or `EntityAccessError::Borrowed`
+    pub fn try_get_entity_mut(&self, id: Uuid) -> Result<RefMut<Entity>, EntityAccessError> {
+        let entity = self
+            .entity_map
+            .get(&id)
+            .ok_or(EntityAccessError::NotFound(id))?;
+        entity
+            .try_borrow_mut()
+            .map_err(|_| EntityAccessError::Borrowed(id))
+    }
+
     /// Gets an entity (as an reference counted ref cell) based on the `id`.
     /// # Arguements
     /// - `id`: The unique identitier of the entity
@@ -181,6 +311,536 @@ impl EntityTree {
         self.entity_map.values().map(|e| e.borrow_mut()).collect()
     }
 
+    // Removal
+
+    /// Removes an entity from the tree.
+    /// # Arguements
+    /// - `id`: the unique identitier of the entity
+    /// # Returns
+    /// The removed entity, or `None` if it isn't in the tree or is currently borrowed.
+    /// # Note
+    /// - The entity's children are re-parented to the entity's parent (or have no parent, if the
+    ///   entity didn't have one). Use `remove_subtree` to remove the children as well.
+    /// - The removed entity has no parent and no children.
+    /// - `head` and `main_camera` are cleared, if they were the entity.
+    /// - The removed entity keeps it's GPU resources, so it can be added again. Use
+    ///   `destroy_entity` to release them.
+    pub fn remove_entity(&mut self, id: Uuid) -> Option<Rc<RefCell<Entity>>> {
+        let entity_rc = self.entity_map.get(&id)?.clone();
+        let Ok(mut entity) = entity_rc.try_borrow_mut() else {
+            println!("cannot borrow entity ID: {}", id);
+            return None;
+        };
+
+        let parent_id = entity.parent_id.take();
+        let children_id = std::mem::take(&mut entity.children_id);
+
+        if let Some(mut parent) = parent_id.and_then(|id| self.get_entity_mut(id)) {
+            parent.children_id.retain(|child_id| *child_id != id);
+            parent.children_id.extend_from_slice(&children_id);
+        }
+        for child_id in children_id.iter() {
+            if let Some(mut child) = self.get_entity_mut(*child_id) {
+                child.parent_id = parent_id;
+            }
+        }
+        drop(entity);
+        for child_id in children_id {
+            self.fire_reparented(child_id, parent_id);
+        }
+
+        self.forget_entity(id);
+        Some(entity_rc)
+    }
+
+    /// Removes an entity and all of it's descendents from the tree.
+    /// # Arguements
+    /// - `id`: the unique identitier of the entity
+    /// # Returns
+    /// The removed entities, starting with the entity. Empty if the entity isn't in the tree or
+    /// is currently borrowed.
+    /// # Note
+    /// - The removed entities keep their parent and children, except the entity which has no
+    ///   parent.
+    /// - The removed entities keep their GPU resources, use `destroy_entity` to release them.
+    pub fn remove_subtree(&mut self, id: Uuid) -> Vec<Rc<RefCell<Entity>>> {
+        let Some(entity_rc) = self.entity_map.get(&id).cloned() else {
+            return vec![];
+        };
+        let Ok(mut entity) = entity_rc.try_borrow_mut() else {
+            println!("cannot borrow entity ID: {}", id);
+            return vec![];
+        };
+
+        if let Some(mut parent) = entity
+            .parent_id
+            .take()
+            .and_then(|id| self.get_entity_mut(id))
+        {
+            parent.children_id.retain(|child_id| *child_id != id);
+        }
+        let mut stack = entity.children_id.clone();
+        drop(entity);
+
+        self.forget_entity(id);
+        let mut removed = vec![entity_rc];
+        while let Some(child_id) = stack.pop() {
+            let Some(child) = self.entity_map.get(&child_id).cloned() else {
+                continue;
+            };
+
+            stack.extend_from_slice(&child.borrow().children_id);
+            self.forget_entity(child_id);
+            removed.push(child);
+        }
+        removed
+    }
+
+    /// Removes an entity and all of it's descendents from the tree, then releases the GPU
+    /// resources of the removed parts (see `Part::release_gpu`).
+    /// # Arguements
+    /// - `id`: the unique identitier of the entity
+    /// # Returns
+    /// The amount of removed entities, 0 if the entity isn't in the tree or is currently borrowed.
+    /// # Note
+    /// The GPU resources are released even if the entities are still referenced elsewhere, use
+    /// `remove_subtree` to keep them.
+    pub fn destroy_entity(&mut self, id: Uuid) -> usize {
+        let removed = self.remove_subtree(id);
+        for entity_rc in removed.iter() {
+            if let Ok(mut entity) = entity_rc.try_borrow_mut()
+                && let EntityType::Part(part) = entity.get_type_mut()
+            {
+                part.release_gpu();
+            }
+        }
+        removed.len()
+    }
+
+    // Cloning
+
+    /// Copies an entity and all of it's descendents, with new IDs.
+    /// # Arguements
+    /// - `root`: the unique identitier of the entity being copied
+    /// # Returns
+    /// The ID of the copied `root`, or `None` if the `root` or any descendent couldn't be
+    /// borrowed.
+    /// # Note
+    /// - The copy is parented to the `root`'s parent, and has the same heirarchry.
+    /// - Copied parts are added to `parts`, `head` and `main_camera` don't change.
+    pub fn clone_subtree(&mut self, root: Uuid) -> Option<Uuid> {
+        let root_entity = self.entity_map.get(&root)?.try_borrow().ok()?;
+        let root_parent_id = root_entity.parent_id;
+        let original_ids: Vec<Uuid> = std::iter::once(root)
+            .chain(self.descendants_iter(&root_entity))
+            .collect();
+        drop(root_entity);
+
+        let mut copies = Vec::with_capacity(original_ids.len());
+        let mut new_ids = HashMap::<Uuid, Uuid>::with_capacity(original_ids.len());
+        for id in original_ids.iter() {
+            let original = self.entity_map.get(id)?.try_borrow().ok()?;
+            let copy = original.clone_shallow();
+            new_ids.insert(*id, copy.get_uuid());
+            copies.push((copy, original.parent_id, original.children_id.clone()));
+        }
+
+        for (mut copy, parent_id, children_id) in copies {
+            copy.parent_id = parent_id.map(|id| new_ids.get(&id).copied().unwrap_or(id));
+            copy.children_id = children_id
+                .iter()
+                .filter_map(|id| new_ids.get(id).copied())
+                .collect();
+
+            let id = copy.get_uuid();
+            if let EntityType::Part(_) = copy.get_type() {
+                self.parts.push(id);
+            }
+            self.entity_map.insert(id, Rc::new(RefCell::new(copy)));
+            self.fire_added(id);
+        }
+
+        let new_root = new_ids[&root];
+        if let Some(mut parent) = root_parent_id.and_then(|id| self.get_entity_mut(id)) {
+            parent.children_id.push(new_root);
+        }
+        Some(new_root)
+    }
+
+    /// Removes every reference to an entity, from the tree's collections.
+    fn forget_entity(&mut self, id: Uuid) {
+        self.entity_map.remove(&id);
+        self.parts.retain(|part_id| *part_id != id);
+        if self.head == Some(id) {
+            self.head = None;
+        }
+        if self.main_camera == Some(id) {
+            self.main_camera = None;
+        }
+        self.fire_removed(id);
+    }
+
+    // Callbacks
+
+    /// Registers a callback, fired after an entity is added to the tree.
+    /// # Arguements
+    /// - `callback`: called with the ID of the added entity
+    /// # Note
+    /// - Fired by `add_entity` (and `add_entity_with_parent`), `add_head`, `add_main_camera`
+    ///   and `clone_subtree`, but not when loading a scene.
+    /// - The tree is borrowed while the callback runs, so the callback can't access it. Record
+    ///   the ID and handle it afterwards instead.
+    pub fn on_added(&mut self, callback: EntityCallback) {
+        self.callbacks.added.push(callback);
+    }
+
+    /// Registers a callback, fired after an entity is removed from the tree.
+    /// # Arguements
+    /// - `callback`: called with the ID of the removed entity
+    /// # Note
+    /// - Fired by `remove_entity` and `remove_subtree`, once for each removed entity.
+    /// - The tree is borrowed while the callback runs, so the callback can't access it.
+    pub fn on_removed(&mut self, callback: EntityCallback) {
+        self.callbacks.removed.push(callback);
+    }
+
+    /// Registers a callback, fired after an entity's parent changes.
+    /// # Arguements
+    /// - `callback`: called with the ID of the entity, and the ID of it's new parent (`None` if
+    ///   it doesn't have one)
+    /// # Note
+    /// - Fired by `set_parent` (and `add_entity_with_parent`), and for the children re-parented
+    ///   by `remove_entity`.
+    /// - The tree is borrowed while the callback runs, so the callback can't access it.
+    pub fn on_reparented(&mut self, callback: ReparentCallback) {
+        self.callbacks.reparented.push(callback);
+    }
+
+    fn fire_added(&mut self, id: Uuid) {
+        for callback in self.callbacks.added.iter_mut() {
+            callback(id);
+        }
+    }
+
+    fn fire_removed(&mut self, id: Uuid) {
+        for callback in self.callbacks.removed.iter_mut() {
+            callback(id);
+        }
+    }
+
+    fn fire_reparented(&mut self, id: Uuid, parent_id: Option<Uuid>) {
+        for callback in self.callbacks.reparented.iter_mut() {
+            callback(id, parent_id);
+        }
+    }
+
+    // Scenes
+
+    /// Saves the tree as a scene.
+    /// # Returns
+    /// Either:
+    /// - The scene, see `from_scene_string` for the format
+    /// - `EntityAccessError::Borrowed`, if an entity is mutably borrowed
+    /// # Note
+    /// - Parts only save the path of their mesh (see `Part::get_mesh_path`), and don't save
+    ///   their texture.
+    /// - The entities are saved parents first, in the order of their parent's children.
+    pub fn to_scene_string(&self) -> Result<String, EntityAccessError> {
+        let mut scene = format!(":{SCENE_TREE_SECTION_NAME}\n");
+        if let Some(head) = self.head {
+            let _ = writeln!(scene, "head {head}");
+        }
+        if let Some(main_camera) = self.main_camera {
+            let _ = writeln!(scene, "main_camera {main_camera}");
+        }
+
+        for id in self.scene_order()? {
+            let entity = self.try_get_entity(id)?;
+
+            let _ = writeln!(scene, "\n:{SCENE_ENTITY_SECTION_NAME}");
+            let _ = writeln!(scene, "uuid {id}");
+            let _ = writeln!(scene, "name {}", entity.get_name());
+            if let Some(parent_id) = entity.parent_id {
+                let _ = writeln!(scene, "parent {parent_id}");
+            }
+            if !entity.is_visible() && !matches!(entity.get_type(), EntityType::Part(_)) {
+                let _ = writeln!(scene, "visible false");
+            }
+            let mut tags: Vec<&str> = entity.tags().collect();
+            tags.sort_unstable();
+            for tag in tags {
+                let _ = writeln!(scene, "tag {tag}");
+            }
+            write_scene_type(&mut scene, entity.get_type());
+        }
+        Ok(scene)
+    }
+
+    /// Loads a tree from a scene, keeping the IDs of the entities.
+    /// # Arguements
+    /// - `scene`: the scene, made by `to_scene_string`
+    /// # Returns
+    /// Either:
+    /// - The loaded tree
+    /// - An error message
+    /// # Note
+    /// The format is made of sections, like a mesh file. Each line of a section is a key, then
+    /// a space, then the value:
+    /// ```text
+    /// :Tree
+    /// head <uuid>
+    /// main_camera <uuid>
+    ///
+    /// :Entity
+    /// uuid <uuid>
+    /// name <name>
+    /// parent <uuid>
+    /// tag <tag>
+    /// type Part
+    /// position 0 1 0
+    /// orientation 1 0 0 0
+    /// mesh assets/meshs/plane.mesh
+    /// ```
+    pub fn from_scene_string(scene: &str) -> Result<Self, String> {
+        Self::from_scene_string_with(scene, false)
+    }
+
+    /// Loads a tree from a scene.
+    /// # Arguements
+    /// - `scene`: the scene, made by `to_scene_string`
+    /// - `regenerate_ids`: gives every entity a new ID, so the scene can be loaded more than once
+    /// # Returns
+    /// Either:
+    /// - The loaded tree
+    /// - An error message
+    pub fn from_scene_string_with(scene: &str, regenerate_ids: bool) -> Result<Self, String> {
+        let mut sections: Vec<(&str, Vec<SceneField>)> = vec![];
+        for (i, line) in scene.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(section_name) = line.strip_prefix(':') {
+                sections.push((section_name, vec![]));
+                continue;
+            }
+
+            let Some((_, fields)) = sections.last_mut() else {
+                return Err(format!("line {}: expected a section", i + 1));
+            };
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            fields.push((i + 1, key, value.trim()));
+        }
+
+        let mut new_ids = HashMap::<Uuid, Uuid>::new();
+        let mut map_id = |id: Uuid| {
+            if regenerate_ids {
+                *new_ids.entry(id).or_insert_with(Uuid::new_v4)
+            } else {
+                id
+            }
+        };
+
+        let mut tree = Self::default();
+        let mut parents = Vec::<(Uuid, Uuid, usize)>::new();
+        for (section_name, fields) in sections {
+            match section_name {
+                SCENE_TREE_SECTION_NAME => {
+                    for (line, key, value) in fields {
+                        let id = map_id(parse_scene_uuid(line, value)?);
+                        match key {
+                            "head" => tree.head = Some(id),
+                            "main_camera" => tree.main_camera = Some(id),
+                            _ => return Err(format!("line {line}: unknown key {key}")),
+                        }
+                    }
+                }
+                SCENE_ENTITY_SECTION_NAME => {
+                    let (entity, parent_id) = parse_scene_entity(&fields, &mut map_id)?;
+                    let id = entity.get_uuid();
+                    if tree.entity_map.contains_key(&id) {
+                        return Err(format!("duplicate entity ID: {id}"));
+                    }
+                    if let Some((parent_id, line)) = parent_id {
+                        parents.push((id, parent_id, line));
+                    }
+                    if let EntityType::Part(_) = entity.get_type() {
+                        tree.parts.push(id);
+                    }
+                    tree.entity_map.insert(id, Rc::new(RefCell::new(entity)));
+                }
+                _ => return Err(format!("invalid section name: {section_name}")),
+            }
+        }
+
+        for (id, parent_id, line) in parents {
+            let Some(parent) = tree.entity_map.get(&parent_id) else {
+                return Err(format!("line {line}: couldn't find parent ID: {parent_id}"));
+            };
+            parent.borrow_mut().children_id.push(id);
+            tree.entity_map[&id].borrow_mut().parent_id = Some(parent_id);
+        }
+
+        for id in [tree.head, tree.main_camera].into_iter().flatten() {
+            if !tree.entity_map.contains_key(&id) {
+                return Err(format!("couldn't find entity ID: {id}"));
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Gets the order entities are saved in a scene.
+    /// # Returns
+    /// Either:
+    /// - The ID of every entity; each root (starting with the `head`) followed by it's
+    ///   descendents
+    /// - `EntityAccessError::Borrowed`, if an entity is mutably borrowed
+    fn scene_order(&self) -> Result<Vec<Uuid>, EntityAccessError> {
+        let mut roots = Vec::new();
+        for id in self.entity_map.keys() {
+            let entity = self.try_get_entity(*id)?;
+            if entity
+                .parent_id
+                .is_none_or(|id| !self.entity_map.contains_key(&id))
+            {
+                roots.push(*id);
+            }
+        }
+        roots.sort_by_key(|id| (Some(*id) != self.head, *id));
+
+        let mut order = Vec::with_capacity(self.entity_map.len());
+        for root in roots {
+            order.push(root);
+            order.extend(self.descendants_iter(&*self.try_get_entity(root)?));
+        }
+        Ok(order)
+    }
+
+    // Updating
+
+    /// Starts newly created entities, then updates every entity whose type implements
+    /// `Update`.
+    /// # Arguements
+    /// - `delta`: the seconds since the last frame
+    /// # Note
+    /// Entities that are currently borrowed are skipped.
+    pub fn update_all(&mut self, delta: f32) {
+        for entity_ref in self.entity_map.values() {
+            let Ok(mut entity) = entity_ref.try_borrow_mut() else {
+                continue;
+            };
+
+            if entity.newly_created {
+                entity.get_type_mut().start();
+                entity.newly_created = false;
+            }
+
+            if let Some(update) = entity.get_type_mut().as_update_mut() {
+                update.update(delta);
+            }
+        }
+    }
+
+    // Rendering
+
+    /// Gets the parts that should be rendered.
+    /// # Returns
+    /// A collection of IDs of every part that is visable, and doesn't have a hidden ancestor.
+    pub fn render_order(&self) -> Vec<Uuid> {
+        self.parts
+            .iter()
+            .copied()
+            .filter(|id| {
+                let Some(entity) = self.entity_map.get(id) else {
+                    return false;
+                };
+                let Ok(entity) = entity.try_borrow() else {
+                    return false;
+                };
+                self.is_visible(&entity)
+            })
+            .collect()
+    }
+
+    /// Iterates over the parts that should be rendered.
+    /// # Returns
+    /// An iterator of every part entity that is visable, and doesn't have a hidden ancestor
+    /// # Note
+    /// The same as `render_order`, but yields the entities instead of their IDs.
+    pub fn visible_parts(&self) -> impl Iterator<Item = Rc<RefCell<Entity>>> {
+        self.render_order()
+            .into_iter()
+            .filter_map(|id| self.entity_map.get(&id).cloned())
+    }
+
+    /// Checks if the entity and all of it's ancestors are visable.
+    /// # Arguements
+    /// - `entity`: the entity
+    /// # Returns
+    /// If the entity is visable
+    /// # Note
+    /// Hidden entities hide their descendents (see `Entity::set_visible`).
+    pub fn is_visible(&self, entity: &Entity) -> bool {
+        let is_hidden = |entity: &Entity| !entity.is_visible();
+
+        if is_hidden(entity) {
+            return false;
+        }
+
+        let mut visited = HashSet::<Uuid>::new();
+        let mut parent_id = entity.parent_id;
+        while let Some(id) = parent_id {
+            if !visited.insert(id) {
+                break;
+            }
+
+            let Some(parent) = self.entity_map.get(&id) else {
+                break;
+            };
+            let Ok(parent) = parent.try_borrow() else {
+                break;
+            };
+
+            if is_hidden(&parent) {
+                return false;
+            }
+            parent_id = parent.parent_id;
+        }
+        true
+    }
+
+    /// Gets the transform of an entity in world space, which includes the transform of every
+    /// ancestor.
+    /// # Arguements
+    /// - `id`: the ID of the entity
+    /// # Returns
+    /// The ancestors' transforms (from the root), multiplied with the entity's own transform
+    /// # Note
+    /// - Only parts and cameras have a transform, other entities (and entities that don't exist
+    ///   or are mutably borrowed) count as the identity.
+    /// - A part's transform includes it's size, so children are scaled with their parent.
+    pub fn world_transform(&self, id: Uuid) -> Mat4 {
+        let local_transform = |id: &Uuid| {
+            let Some(Ok(entity)) = self.entity_map.get(id).map(|e| e.try_borrow()) else {
+                return Mat4::identity();
+            };
+            match entity.get_type() {
+                EntityType::Part(part) => part.transform,
+                EntityType::Camera(camera) => camera.transform,
+                _ => Mat4::identity(),
+            }
+        };
+
+        let Some(Ok(entity)) = self.entity_map.get(&id).map(|e| e.try_borrow()) else {
+            return Mat4::identity();
+        };
+        self.ancestors_iter(&entity)
+            .fold(local_transform(&id), |transform, ancestor| {
+                local_transform(&ancestor) * transform
+            })
+    }
+
     // Parent
 
     /// Gets an entity's parent.
@@ -223,23 +883,19 @@ impl EntityTree {
     /// - `parent`: A entity used as `entity`'s new parent
     /// # Returns
     /// An error message if a parent was unsuccessful.
+    /// # Note
+    /// Parenting to a descendent is checked by walking up from `parent` (see `ancestors_iter`),
+    /// the walk stops at an ancestor that is mutably borrowed.
     pub fn set_parent(
         &mut self,
-        mut entity: &mut Entity,
+        entity: &mut Entity,
         parent: Option<&mut Entity>,
     ) -> Result<(), &'static str> {
         let self_id = entity.get_uuid();
 
         let Some(new_parent) = parent else {
-            entity.parent_id = None;
-            if let Some(mut former_parent) = self.get_parent_mut(entity.deref()) {
-                let index = former_parent
-                    .children_id
-                    .iter()
-                    .position(|x| *x == self_id)
-                    .unwrap();
-                former_parent.children_id.remove(index);
-            }
+            self.detach_from_parent(entity);
+            self.fire_reparented(self_id, None);
             return Ok(());
         };
 
@@ -247,27 +903,105 @@ impl EntityTree {
             return Err("can't parent to self");
         }
 
-        for desc_id in self.get_descendents_id(entity.deref()) {
-            if desc_id == self_id {
-                return Err("can't parent to descendent");
-            }
+        if self.ancestors_iter(new_parent).any(|id| id == self_id) {
+            return Err("can't parent to descendent");
         }
 
-        let new_id = new_parent.get_uuid();
-        let entity_mut = entity.deref_mut();
-        if let Some(mut former_parent) = self.get_parent_mut(entity_mut) {
-            let index = former_parent
-                .children_id
-                .iter()
-                .position(|x| *x == self_id)
-                .unwrap();
-            former_parent.children_id.remove(index);
-        }
-        entity_mut.parent_id = Some(new_id);
+        self.detach_from_parent(entity);
+        entity.parent_id = Some(new_parent.get_uuid());
         new_parent.children_id.push(self_id);
+        self.fire_reparented(self_id, entity.parent_id);
         Ok(())
     }
 
+    /// Removes the entity from it's parent's children, and clears the entity's parent.
+    /// # Note
+    /// Doesn't panic if the parent is missing, borrowed or doesn't list the entity as a child.
+    fn detach_from_parent(&self, entity: &mut Entity) {
+        let self_id = entity.get_uuid();
+        if let Some(mut former_parent) = self.get_parent_mut(entity) {
+            former_parent.children_id.retain(|id| *id != self_id);
+        }
+        entity.parent_id = None;
+    }
+
+    // Debugging
+
+    /// Renders the tree as indented text, starting from the `head`, for debugging.
+    /// # Returns
+    /// A line for each entity, containing it's name, the start of it's `Uuid` and it's type:
+    /// ```text
+    /// Game [1a2b3c4d] Game
+    /// |-- Camera [5e6f7a8b] Camera (main camera)
+    /// `-- Level [9c0d1e2f] Base
+    ///     `-- Floor [3a4b5c6d] Part
+    /// ```
+    /// # Note
+    /// - Entities that aren't descendents of the `head` aren't included.
+    /// - Mutably borrowed entities are shown as `<borrowed>`, without their children, and
+    ///   children missing from the tree as `<missing>`.
+    pub fn print_tree(&self) -> String {
+        let mut output = String::new();
+        if let Some(head) = self.head {
+            let mut visited = HashSet::new();
+            self.print_entity(&mut output, head, "", None, &mut visited);
+        }
+        output
+    }
+
+    /// Writes an entity and it's descendents, see `print_tree`.
+    /// # Arguements
+    /// - `prefix`: the indentation of the entity's ancestors
+    /// - `is_last`: if the entity is it's parent's last child, `None` for the root
+    fn print_entity(
+        &self,
+        output: &mut String,
+        id: Uuid,
+        prefix: &str,
+        is_last: Option<bool>,
+        visited: &mut HashSet<Uuid>,
+    ) {
+        let (connector, child_prefix) = match is_last {
+            None => ("", prefix.to_string()),
+            Some(false) => ("|-- ", format!("{prefix}|   ")),
+            Some(true) => ("`-- ", format!("{prefix}    ")),
+        };
+        let short_id = &id.simple().to_string()[..8];
+
+        let entity = match self.entity_map.get(&id).map(|e| e.try_borrow()) {
+            Some(Ok(entity)) => entity,
+            Some(Err(_)) => {
+                let _ = writeln!(output, "{prefix}{connector}<borrowed> [{short_id}]");
+                return;
+            }
+            None => {
+                let _ = writeln!(output, "{prefix}{connector}<missing> [{short_id}]");
+                return;
+            }
+        };
+        let _ = write!(
+            output,
+            "{prefix}{connector}{} [{short_id}] {}",
+            entity.get_name(),
+            entity.get_type().name()
+        );
+        if self.main_camera == Some(id) {
+            output.push_str(" (main camera)");
+        }
+        output.push('\n');
+
+        if !visited.insert(id) {
+            return;
+        }
+        let children_id = entity.children_id.clone();
+        drop(entity);
+
+        for (i, child_id) in children_id.iter().enumerate() {
+            let is_last = i + 1 == children_id.len();
+            self.print_entity(output, *child_id, &child_prefix, Some(is_last), visited);
+        }
+    }
+
     // Heirarchry Selection
 
     /// Finds the first child that has the name that is equal to `name`.
@@ -354,31 +1088,140 @@ impl EntityTree {
         Some(entity)
     }
 
-    // Ancestors
+    /// Finds every entity inside of the tree that has the name that is equal to `name`.
+    /// # Arguements
+    /// - `name`: the name
+    /// # Returns
+    /// A collection of IDs of every matching entity, empty if there are none
+    /// # Note
+    /// The order of the IDs is not guaranteed.
+    pub fn find_by_name(&self, name: &str) -> Vec<Uuid> {
+        self.entity_map
+            .iter()
+            .filter(|(_, e)| e.borrow().get_name() == name)
+            .map(|(id, _)| *id)
+            .collect()
+    }
 
-    /// Gets an entity's ancestors.
+    /// Finds an entity inside of the tree that has the name that is equal to `name`.
     /// # Arguements
-    /// - `entity`: An entity
+    /// - `name`: the name
     /// # Returns
-    /// A collection of `uuid`s referencing an entity
-    pub fn get_ancestors_id(&self, entity: &Entity) -> Vec<Uuid> {
-        let mut parent;
-        let mut current = entity;
-        let mut ancestors = Vec::<Uuid>::with_capacity(16);
+    /// An optional ID of the entity
+    /// # Note
+    /// If multiple entities share the name, which one is returned is not guaranteed. Use
+    /// `find_by_name` to find all of them.
+    pub fn find_first_by_name(&self, name: &str) -> Option<Uuid> {
+        self.entity_map
+            .iter()
+            .find(|(_, e)| e.borrow().get_name() == name)
+            .map(|(id, _)| *id)
+    }
 
-        while current.parent_id.is_some() {
-            let parent_id_null = entity.parent_id;
-            let Some(parent_id) = parent_id_null else {
-                break;
-            };
+    /// Finds every entity inside of the tree that has a tag (see `Entity::add_tag`).
+    /// # Arguements
+    /// - `tag`: the tag
+    /// # Returns
+    /// A collection of IDs of every matching entity, empty if there are none
+    /// # Note
+    /// The order of the IDs is not guaranteed.
+    pub fn find_by_tag(&self, tag: &str) -> Vec<Uuid> {
+        self.entity_map
+            .iter()
+            .filter(|(_, e)| e.borrow().has_tag(tag))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    // Iterators
+
+    /// Iterates over an entity's children.
+    /// # Arguements
+    /// - `entity`: A borrow of an entity
+    /// # Returns
+    /// An iterator of the children's IDs
+    pub fn children_iter<'a>(&self, entity: &'a Entity) -> ChildIter<'a> {
+        ChildIter {
+            ids: entity.children_id.iter(),
+        }
+    }
 
-            parent = self.get_parent(entity).unwrap();
-            current = &parent;
-            ancestors.push(parent_id);
+    /// Iterates over an entity's descendents, depth-first (a child then it's descendents, before
+    /// the next child).
+    /// # Arguements
+    /// - `entity`: A borrow of an entity
+    /// # Returns
+    /// A lazy iterator of the descendents' IDs
+    pub fn descendants_iter(&self, entity: &Entity) -> DescendantIter {
+        DescendantIter {
+            tree: self,
+            stack: entity.children_id.iter().rev().copied().collect(),
+            visited: HashSet::from([entity.get_uuid()]),
         }
+    }
 
-        ancestors.shrink_to_fit();
-        ancestors
+    /// Iterates over an entity's ancestors, from it's parent to the root.
+    /// # Arguements
+    /// - `entity`: A borrow of an entity
+    /// # Returns
+    /// A lazy iterator of the ancestors' IDs
+    pub fn ancestors_iter(&self, entity: &Entity) -> AncestorIter {
+        AncestorIter {
+            tree: self,
+            next: entity.parent_id,
+            visited: HashSet::from([entity.get_uuid()]),
+        }
+    }
+
+    /// Gets an entity inside of the tree that has the name that is equal to `name`.
+    /// # Arguements
+    /// - `name`: the name
+    /// # Returns
+    /// An optional reference to the entity
+    /// # Note
+    /// The same as `find_first_by_name`, so which entity is returned is not guaranteed when
+    /// multiple share the name.
+    pub fn get_entity_by_name(&self, name: &str) -> Option<Ref<Entity>> {
+        self.get_entity(self.find_first_by_name(name)?)
+    }
+
+    /// Finds an entity by a path of names, starting at the head, e.g. `"Game/Level/Player"`.
+    /// # Arguements
+    /// - `path`: the names of each entity, seperated by `/`; the first is the head's name
+    /// # Returns
+    /// The ID of the entity, or `None` if any name in the path doesn't match.
+    /// # Note
+    /// When siblings share a name, the first child (see `find_first_child`) is followed.
+    pub fn find_by_path(&self, path: &str) -> Option<Uuid> {
+        let mut names = path.split('/').filter(|name| !name.is_empty());
+
+        let head = self.get_head()?;
+        let mut current_id = {
+            let head = head.try_borrow().ok()?;
+            if head.get_name() != names.next()? {
+                return None;
+            }
+            head.get_uuid()
+        };
+
+        for name in names {
+            let current = self.entity_map.get(&current_id)?.try_borrow().ok()?;
+            current_id = self.find_first_child(&current, name)?.get_uuid();
+        }
+        Some(current_id)
+    }
+
+    // Ancestors
+
+    /// Gets an entity's ancestors.
+    /// # Arguements
+    /// - `entity`: An entity
+    /// # Returns
+    /// A collection of `uuid`s referencing an entity, from the parent to the root
+    /// # Note
+    /// Stops at a cycle, see `ancestors_iter`.
+    pub fn get_ancestors_id(&self, entity: &Entity) -> Vec<Uuid> {
+        self.ancestors_iter(entity).collect()
     }
 
     /// Gets an entity's ancestors as mutable references.
@@ -453,27 +1296,52 @@ impl EntityTree {
             .collect()
     }
 
+    // Siblings
+
+    /// Gets an entity's siblings as identitiers.
+    /// # Arguements
+    /// - `entity`: A borrow of an entity
+    /// # Returns
+    /// A collection of IDs of entities sharing the same parent, excluding the entity itself.
+    /// Empty if the entity doesn't have a parent.
+    pub fn get_siblings_id(&self, entity: &Entity) -> Vec<Uuid> {
+        let Some(parent) = self.get_parent(entity) else {
+            return vec![];
+        };
+
+        let self_id = entity.get_uuid();
+        parent
+            .children_id
+            .iter()
+            .copied()
+            .filter(|id| *id != self_id)
+            .collect()
+    }
+
+    /// Gets an entity's siblings as references.
+    /// # Arguements
+    /// - `entity`: A borrow of an entity
+    /// # Returns
+    /// A collection of references to entities sharing the same parent, excluding the entity
+    /// itself. IDs that aren't in the tree are skipped.
+    pub fn get_siblings(&self, entity: &Entity) -> Vec<Ref<Entity>> {
+        self.get_siblings_id(entity)
+            .iter()
+            .filter_map(|id| self.entity_map.get(id))
+            .map(|sibling| sibling.borrow())
+            .collect()
+    }
+
+    // Descendents
+
     /// Gets an entity's descendent as identitiers.
     /// # Arguement
     /// - `entity`: A borrow of an entity
     /// # Retutrns
-    /// A collection of IDs representing the entity's descendent.
+    /// A collection of IDs representing the entity's descendent, each descendent appears once.
+    /// The order is the same as `descendants_iter`.
     pub fn get_descendents_id(&self, entity: &Entity) -> Vec<Uuid> {
-        let mut descendents = self.get_children(entity);
-        let mut stack_rel: Vec<Uuid> = entity.children_id.clone();
-
-        while !stack_rel.is_empty() {
-            let rel_id_null = stack_rel.pop();
-            let Some(rel_id) = rel_id_null else {
-                break;
-            };
-
-            let ent = self.entity_map.get(&rel_id).unwrap().borrow();
-            let mut children = ent.children_id.to_owned();
-            stack_rel.append(&mut children);
-            descendents.push(ent);
-        }
-        descendents.iter().map(|e| e.get_uuid()).collect()
+        self.descendants_iter(entity).collect()
     }
 
     /// Gets an entity's descendents as a reference.
@@ -500,3 +1368,306 @@ impl EntityTree {
             .collect()
     }
 }
+
+/// Writes the type of an entity, and the type's data to a scene.
+fn write_scene_type(scene: &mut String, entity_type: &EntityType) {
+    let write_vector3 = |scene: &mut String, key: &str, v: Vector3| {
+        let _ = writeln!(scene, "{key} {} {} {}", v.x, v.y, v.z);
+    };
+    // only set by `set_orientation`, otherwise it's the same as the euler rotation
+    let write_orientation = |scene: &mut String, obj: &dyn Object3D| {
+        let orientation = obj.get_orientation();
+        if orientation != euler_to_orientation(obj.get_rotation()) {
+            let (s, bv) = (orientation.s, orientation.bv);
+            let _ = writeln!(scene, "orientation {} {} {} {}", s, bv.xy, bv.xz, bv.yz);
+        }
+    };
+
+    match entity_type {
+        EntityType::Base(_) => scene.push_str("type Base\n"),
+        EntityType::InputService(_) => scene.push_str("type InputService\n"),
+        EntityType::Game(game) => {
+            let _ = writeln!(scene, "type Game\ngenre {}", game.genre.name());
+        }
+        EntityType::Camera(camera) => {
+            scene.push_str("type Camera\n");
+            let _ = writeln!(scene, "fov {}", camera.fov);
+            let _ = writeln!(scene, "near_view {}", camera.near_view);
+            let _ = writeln!(scene, "far_view {}", camera.far_view);
+            if let ProjectionKind::Orthographic { size } = camera.projection {
+                let _ = writeln!(scene, "orthographic {size}");
+            }
+            write_vector3(scene, "position", camera.get_position());
+            write_vector3(scene, "rotation", camera.get_rotation());
+            write_orientation(scene, camera);
+        }
+        EntityType::Part(part) => {
+            scene.push_str("type Part\n");
+            let color = part.color;
+            let _ = writeln!(scene, "color {} {} {}", color.r, color.g, color.b);
+            let _ = writeln!(scene, "visable {}", part.visable);
+            write_vector3(scene, "position", part.get_position());
+            write_vector3(scene, "rotation", part.get_rotation());
+            write_orientation(scene, part);
+            write_vector3(scene, "size", part.get_size());
+            if let Some(path) = part.get_mesh_path() {
+                let _ = writeln!(scene, "mesh {path}");
+            }
+        }
+    }
+}
+
+/// Parses a `Uuid` from a scene.
+fn parse_scene_uuid(line: usize, value: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|e| format!("line {line}: invalid ID {value} ({e})"))
+}
+
+/// Parses a number from a scene.
+fn parse_scene_f32(line: usize, value: &str) -> Result<f32, String> {
+    value
+        .parse::<f32>()
+        .map_err(|e| format!("line {line}: invalid number {value} ({e})"))
+}
+
+/// Parses 3 numbers seperated by spaces from a scene.
+fn parse_scene_vector3(line: usize, value: &str) -> Result<Vector3, String> {
+    let values = value
+        .split_whitespace()
+        .map(|v| parse_scene_f32(line, v))
+        .collect::<Result<Vec<f32>, String>>()?;
+    let [x, y, z] = values[..] else {
+        return Err(format!("line {line}: expected 3 numbers, got {value}"));
+    };
+    Ok(Vector3::new(x, y, z))
+}
+
+/// Parses a rotor from a scene, 4 numbers seperated by spaces: `s`, `xy`, `xz` then `yz`.
+fn parse_scene_rotor(line: usize, value: &str) -> Result<Rotor3, String> {
+    let values = value
+        .split_whitespace()
+        .map(|v| parse_scene_f32(line, v))
+        .collect::<Result<Vec<f32>, String>>()?;
+    let [s, xy, xz, yz] = values[..] else {
+        return Err(format!("line {line}: expected 4 numbers, got {value}"));
+    };
+    Ok(Rotor3::new(s, Bivec3::new(xy, xz, yz)))
+}
+
+/// Parses an entity section of a scene.
+/// # Returns
+/// Either:
+/// - The entity and the ID of it's parent (with the line number)
+/// - An error message
+fn parse_scene_entity(
+    fields: &[SceneField],
+    map_id: &mut impl FnMut(Uuid) -> Uuid,
+) -> Result<(Entity, Option<(Uuid, usize)>), String> {
+    let mut uuid = None;
+    let mut name = "entity";
+    let mut parent_id = None;
+    let mut entity_type = None;
+    let mut visible = true;
+    let mut tags = vec![];
+
+    for &(line, key, value) in fields {
+        match key {
+            "tag" => tags.push(value),
+            "visible" => {
+                visible = value
+                    .parse()
+                    .map_err(|e| format!("line {line}: invalid bool {value} ({e})"))?;
+            }
+            "uuid" => uuid = Some(map_id(parse_scene_uuid(line, value)?)),
+            "name" => name = value,
+            "parent" => parent_id = Some((map_id(parse_scene_uuid(line, value)?), line)),
+            "type" => {
+                entity_type = Some(match value {
+                    "Base" => EntityType::Base(Base),
+                    "Game" => EntityType::Game(Game::default()),
+                    "InputService" => EntityType::InputService(InputService::default()),
+                    "Camera" => EntityType::Camera(Camera::default()),
+                    "Part" => EntityType::Part(Part::default()),
+                    _ => return Err(format!("line {line}: unknown entity type {value}")),
+                })
+            }
+            _ => {
+                let Some(entity_type) = entity_type.as_mut() else {
+                    return Err(format!("line {line}: {key} must be after the type"));
+                };
+                parse_scene_type_field(entity_type, line, key, value)?;
+            }
+        }
+    }
+
+    let Some(uuid) = uuid else {
+        return Err(format!("entity {name} doesn't have an uuid"));
+    };
+    let entity_type = entity_type.unwrap_or(EntityType::Base(Base));
+    let mut entity = Entity::with_uuid(name, Box::new(entity_type), uuid);
+    if !visible {
+        entity.set_visible(false);
+    }
+    for tag in tags {
+        entity.add_tag(tag);
+    }
+    Ok((entity, parent_id))
+}
+
+/// Parses a field of an entity type, from a scene.
+fn parse_scene_type_field(
+    entity_type: &mut EntityType,
+    line: usize,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    match (entity_type, key) {
+        (EntityType::Game(game), "genre") => {
+            game.genre = GameGenre::from_name(value)
+                .ok_or_else(|| format!("line {line}: unknown genre {value}"))?;
+        }
+        (EntityType::Camera(camera), "fov") => camera.fov = parse_scene_f32(line, value)?,
+        (EntityType::Camera(camera), "near_view") => {
+            camera.near_view = parse_scene_f32(line, value)?
+        }
+        (EntityType::Camera(camera), "far_view") => camera.far_view = parse_scene_f32(line, value)?,
+        (EntityType::Camera(camera), "orthographic") => {
+            let size = parse_scene_f32(line, value)?;
+            camera.projection = ProjectionKind::Orthographic { size };
+        }
+        (EntityType::Camera(camera), "position") => {
+            camera.set_position(parse_scene_vector3(line, value)?)
+        }
+        (EntityType::Camera(camera), "rotation") => {
+            camera.set_rotation(parse_scene_vector3(line, value)?)
+        }
+        (EntityType::Camera(camera), "orientation") => {
+            camera.set_orientation(parse_scene_rotor(line, value)?)
+        }
+        (EntityType::Part(part), "color") => {
+            let rgb = parse_scene_vector3(line, value)?;
+            part.color = Color3::new(rgb.x, rgb.y, rgb.z)
+                .ok_or_else(|| format!("line {line}: invalid color {value}"))?;
+        }
+        (EntityType::Part(part), "visable") => {
+            part.visable = value
+                .parse()
+                .map_err(|e| format!("line {line}: invalid bool {value} ({e})"))?;
+        }
+        (EntityType::Part(part), "position") => {
+            part.set_position(parse_scene_vector3(line, value)?)
+        }
+        (EntityType::Part(part), "rotation") => {
+            part.set_rotation(parse_scene_vector3(line, value)?)
+        }
+        (EntityType::Part(part), "orientation") => {
+            part.set_orientation(parse_scene_rotor(line, value)?)
+        }
+        (EntityType::Part(part), "size") => {
+            part.set_size(parse_scene_vector3(line, value)?);
+            part.recalculate_transform();
+        }
+        (EntityType::Part(part), "mesh") => part
+            .load_mesh_from_file(value)
+            .map_err(|e| format!("line {line}: couldn't load mesh {value} ({e})"))?,
+        (_, key) => return Err(format!("line {line}: unknown key {key}")),
+    }
+    Ok(())
+}
+
+/// Errors relating to accessing an entity inside of the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityAccessError {
+    /// Thrown when the entity isn't inside of the tree.
+    NotFound(Uuid),
+    /// Thrown when the entity is already borrowed, in a way that conflicts.
+    Borrowed(Uuid),
+}
+
+impl fmt::Display for EntityAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(id) => write!(f, "couldn't find entity ID: {id}"),
+            Self::Borrowed(id) => write!(f, "cannot borrow entity ID: {id}"),
+        }
+    }
+}
+
+impl Error for EntityAccessError {}
+
+/// An iterator over an entity's children IDs, see `EntityTree::children_iter`.
+#[derive(Debug, Clone)]
+pub struct ChildIter<'a> {
+    ids: slice::Iter<'a, Uuid>,
+}
+impl Iterator for ChildIter<'_> {
+    type Item = Uuid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ids.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+/// A depth-first iterator over an entity's descendent IDs, see
+/// `EntityTree::descendants_iter`.
+/// # Note
+/// - Each descendent is visited once, even if the tree contains a cycle.
+/// - The children of entities that are mutably borrowed are skipped.
+#[derive(Debug, Clone)]
+pub struct DescendantIter<'a> {
+    tree: &'a EntityTree,
+    stack: Vec<Uuid>,
+    visited: HashSet<Uuid>,
+}
+impl Iterator for DescendantIter<'_> {
+    type Item = Uuid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let id = self.stack.pop()?;
+            if !self.visited.insert(id) {
+                continue;
+            }
+
+            let Some(entity) = self.tree.entity_map.get(&id) else {
+                continue;
+            };
+            if let Ok(entity) = entity.try_borrow() {
+                self.stack.extend(entity.children_id.iter().rev());
+            }
+            return Some(id);
+        }
+    }
+}
+
+/// An iterator over an entity's ancestor IDs, from the parent to the root, see
+/// `EntityTree::ancestors_iter`.
+/// # Note
+/// Stops at a cycle, a missing entity or an entity that is mutably borrowed.
+#[derive(Debug, Clone)]
+pub struct AncestorIter<'a> {
+    tree: &'a EntityTree,
+    next: Option<Uuid>,
+    visited: HashSet<Uuid>,
+}
+impl Iterator for AncestorIter<'_> {
+    type Item = Uuid;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next.take()?;
+        if !self.visited.insert(id) {
+            return None;
+        }
+
+        self.next = self
+            .tree
+            .entity_map
+            .get(&id)
+            .and_then(|entity| entity.try_borrow().ok())
+            .and_then(|entity| entity.parent_id);
+        Some(id)
+    }
+}
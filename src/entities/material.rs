@@ -0,0 +1,122 @@
+//! Contains the `Material` struct, which describes how a part is shaded.
+
+use std::rc::Rc;
+
+use crate::{
+    datatypes::color::Color3,
+    gl_helper::{ShaderProgram, UniformValue},
+    texture::{Texture, TextureSlots},
+};
+
+/// The name of the base color uniform, set by `Material::apply`.
+pub const MATERIAL_COLOR_UNIFORM: &str = "obj_color";
+
+/// Bundles a shader program with the values it's drawn with, so parts aren't limited to the
+/// window's shader program.
+#[derive(Debug, Clone)]
+pub struct Material {
+    /// The shader program, shared between materials
+    pub program: Rc<ShaderProgram>,
+    /// The base color, uploaded to `obj_color`
+    pub color: Color3,
+    /// The textures, bound when the material is applied
+    textures: TextureSlots,
+    /// Extra uniform values, uploaded every time the material is applied
+    uniforms: Vec<(String, UniformValue)>,
+}
+
+impl Material {
+    /// Creates a new material, without any textures or extra uniforms.
+    /// # Arguements
+    /// - `program`: the shader program
+    /// - `color`: the base color
+    /// # Returns
+    /// A new material
+    pub fn new(program: Rc<ShaderProgram>, color: Color3) -> Self {
+        Self {
+            program,
+            color,
+            textures: TextureSlots::default(),
+            uniforms: Vec::new(),
+        }
+    }
+
+    // Textures
+
+    /// Gets a texture of the material by it's slot.
+    /// # Arguements
+    /// - `name`: the name of the slot, which is the sampler uniform's name
+    /// # Returns
+    /// Either:
+    /// - The borrowed texture
+    /// - `None`, if the slot is empty
+    pub fn get_texture(&self, name: &str) -> Option<&Texture> {
+        self.textures.get(name)
+    }
+
+    /// Sets a texture of the material.
+    /// # Arguements
+    /// - `name`: the name of the slot, which is the sampler uniform's name
+    /// - `texture`: the new texture to be assigned
+    /// # Note
+    /// - A new slot is bound to the next texture unit, replacing a texture keeps it's unit.
+    /// - Uploads the texture to GL, so requires a GL context.
+    pub fn set_texture(&mut self, name: &str, texture: Texture) {
+        self.textures.set(name, texture);
+    }
+
+    /// Does any texture of the material have a pixel that isn't fully opaque?
+    /// # Returns
+    /// If parts with the material should be drawn after the opaque parts
+    pub fn is_translucent(&self) -> bool {
+        self.textures.is_translucent()
+    }
+
+    // Uniforms
+
+    /// Gets an extra uniform value of the material.
+    /// # Arguements
+    /// - `name`: the name of the uniform
+    /// # Returns
+    /// Either:
+    /// - The value
+    /// - `None`, if the uniform isn't set
+    pub fn get_uniform(&self, name: &str) -> Option<UniformValue> {
+        self.uniforms
+            .iter()
+            .find(|(uniform, _)| uniform == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Sets an extra uniform value of the material, replacing the old value.
+    /// # Arguements
+    /// - `name`: the name of the uniform
+    /// - `value`: the value uploaded by `apply`
+    pub fn set_uniform(&mut self, name: &str, value: UniformValue) {
+        match self
+            .uniforms
+            .iter_mut()
+            .find(|(uniform, _)| uniform == name)
+        {
+            Some((_, old)) => *old = value,
+            None => self.uniforms.push((name.to_string(), value)),
+        }
+    }
+
+    // Rendering
+
+    /// Uses the material's program, then uploads the color, textures and extra uniforms.
+    /// # Note
+    /// - Each texture is bound to it's texture unit, and it's sampler uniform is set to the unit.
+    /// - The active texture unit is reset to `GL_TEXTURE0` afterwards.
+    pub fn apply(&self) {
+        self.program.use_program();
+        self.program.set_color3(MATERIAL_COLOR_UNIFORM, self.color);
+
+        self.textures.bind(&self.program);
+
+        for (name, value) in self.uniforms.iter() {
+            self.program.set_uniform(name, *value);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 //! Contains the traits `Object3D` and `Object3DSize`. Useful for handling transformations for
 //! entities.
 use crate::datatypes::vectors::Vector3;
-use ultraviolet::{Mat4, Vec3};
+use ultraviolet::{Mat3, Mat4, Rotor3, Vec3};
 
 /// A trait for any 3D object with a position and rotation.
 pub trait Object3D {
@@ -21,13 +21,30 @@ pub trait Object3D {
 
     /// Gets the rotation.
     /// # Returns
-    /// An euler rotation
+    /// An euler rotation, see `euler_to_orientation` for the convention
     fn get_rotation(&self) -> Vector3;
     /// Sets the rotation.
     /// # Arguement
     /// - `rot`: the rotation euler
+    /// # Note
+    /// - Clears the orientation set by `set_orientation`, so the euler rotation is used again.
+    /// - Implementations should call `recalculate_transform` and `update_vectors`, like the
+    ///   derive macro does.
     fn set_rotation(&mut self, rot: Vector3);
 
+    /// Gets the orientation, as a rotor (quaternion).
+    /// # Returns
+    /// The orientation given to `set_orientation`, otherwise the euler rotation converted with
+    /// `euler_to_orientation`
+    fn get_orientation(&self) -> Rotor3;
+    /// Sets the orientation, used instead of the euler rotation until `set_rotation` is called.
+    /// # Arguement
+    /// - `orientation`: the orientation, it is normalised
+    /// # Note
+    /// Rotors don't suffer from gimbal lock, and can be smoothly interpolated with `Slerp`. The
+    /// euler rotation (`get_rotation`) isn't updated.
+    fn set_orientation(&mut self, orientation: Rotor3);
+
     /// Gets the front.
     /// # Returns
     /// The _front_ vector (normalised)
@@ -55,21 +72,54 @@ pub trait Object3D {
     /// - `up`: The _up_ vector (normalised)
     fn set_up(&mut self, up: Vector3);
 
-    /// Updates the `front`, `right` and `up` vector
+    /// Updates the `front`, `right` and `up` vector, by rotating the basis vectors (see
+    /// `Vector3`) with `get_orientation`.
+    /// # Note
+    /// The vectors match the columns of `calculate_transform`: `right` is +X, `up` is +Y and
+    /// `front` is -Z, the direction a camera looks.
     fn update_vectors(&mut self) {
-        let rot = self.get_rotation();
+        let orientation = self.get_orientation();
+
+        self.set_front(Vector3::from(orientation * -Vec3::unit_z()).get_unit());
+        self.set_right(Vector3::from(orientation * Vec3::unit_x()).get_unit());
+        self.set_up(Vector3::from(orientation * Vec3::unit_y()).get_unit());
+    }
+
+    /// Moves the object.
+    /// # Arguements
+    /// - `delta`: added to the position
+    fn translate(&mut self, delta: Vector3) {
+        self.set_position(self.get_position() + delta);
+    }
+
+    /// Rotates the object.
+    /// # Arguements
+    /// - `delta_euler`: added to the euler rotation, in degrees
+    /// # Note
+    /// Clears the orientation set by `set_orientation`, see `set_rotation`.
+    fn rotate(&mut self, delta_euler: Vector3) {
+        self.set_rotation(self.get_rotation() + delta_euler);
+    }
 
-        let (pitch, yaw) = (rot.y.to_radians(), rot.x.to_radians());
-        let pitch_cos = pitch.cos();
+    /// Moves the object along it's `front` vector.
+    /// # Arguements
+    /// - `amount`: how far to move, negative moves backwards
+    fn move_forward(&mut self, amount: f32) {
+        self.translate(self.get_front() * amount);
+    }
 
-        let front =
-            Vector3::new(pitch_cos * yaw.cos(), pitch.sin(), pitch_cos * yaw.sin()).get_unit();
-        let right = front.cross(Vector3::up()).get_unit();
-        let up = right.cross(front).get_unit();
+    /// Moves the object along it's `right` vector.
+    /// # Arguements
+    /// - `amount`: how far to move, negative moves left
+    fn move_right(&mut self, amount: f32) {
+        self.translate(self.get_right() * amount);
+    }
 
-        self.set_front(front);
-        self.set_right(right);
-        self.set_up(up);
+    /// Moves the object along it's `up` vector.
+    /// # Arguements
+    /// - `amount`: how far to move, negative moves down
+    fn move_up(&mut self, amount: f32) {
+        self.translate(self.get_up() * amount);
     }
 }
 
@@ -84,6 +134,36 @@ pub trait Object3DSize {
     /// # Arguement
     /// - `size`: the size
     fn set_size(&mut self, size: Vector3);
+
+    /// Multiplies the size.
+    /// # Arguements
+    /// - `factor`: multiplied with each axis of the size
+    fn scale_by(&mut self, factor: Vector3) {
+        self.set_size(self.get_size() * factor);
+    }
+}
+
+/// Converts an euler rotation into a rotor, in the same way `calculate_transform` uses it.
+/// # Arguements
+/// - `rotation`: the euler rotation, in degrees
+/// # Returns
+/// A normalised rotor
+/// # Convention
+/// Each component is the (right-handed) rotation around that axis:
+/// - `x`: the pitch, around +X, a positive pitch turns the front (-Z) up
+/// - `y`: the yaw, around +Y, a positive yaw turns the front from -Z to -X (left)
+/// - `z`: the roll, around +Z
+///
+/// The roll is applied first, then the pitch, then the yaw.
+pub fn euler_to_orientation(rotation: Vector3) -> Rotor3 {
+    // ultraviolet's yaw turns +Z to -X, so it's negated to be right-handed like the others
+    let (roll, pitch, yaw) = (
+        rotation.z.to_radians(),
+        rotation.x.to_radians(),
+        -rotation.y.to_radians(),
+    );
+
+    Rotor3::from_euler_angles(roll, pitch, yaw).normalized()
 }
 
 /// Calculates the transformation of the object.
@@ -91,20 +171,42 @@ pub trait Object3DSize {
 /// - `obj`: the `Object3D`
 /// # Returns
 /// A Matrix4x4
+/// # Note
+/// The rotation comes from `Object3D::get_orientation`.
 pub fn calculate_transform<T: Object3D>(obj: &T) -> Mat4 {
-    let rotation = obj.get_rotation();
     let position = obj.get_position();
-    let (roll, pitch, yaw) = (
-        rotation.x.to_radians(),
-        rotation.y.to_radians(),
-        rotation.z.to_radians(),
-    );
 
     Mat4::from_translation(Vec3 {
         x: position.x,
         y: position.y,
         z: position.z,
-    }) * Mat4::from_euler_angles(roll, pitch, yaw)
+    }) * obj.get_orientation().into_matrix().into_homogeneous()
+}
+
+/// The smallest determinant, before a matrix is considered non-invertible by `normal_matrix`.
+const NORMAL_MATRIX_EPSILON: f32 = 1e-8;
+
+/// Calculates the matrix used to transform normals, the inverse-transpose of the upper-left 3x3
+/// of the transform.
+/// # Arguements
+/// - `transform`: the model transform
+/// # Returns
+/// The normal matrix
+/// # Note
+/// - Unlike the transform, this keeps normals perpendicular to the surface when scaled
+///   non-uniformly. The transformed normals need to be normalised again.
+/// - If the 3x3 isn't invertible (e.g. a size of zero), it is returned as is.
+pub fn normal_matrix(transform: &Mat4) -> Mat3 {
+    let upper = Mat3::new(
+        transform.cols[0].truncated(),
+        transform.cols[1].truncated(),
+        transform.cols[2].truncated(),
+    );
+
+    if upper.determinant().abs() < NORMAL_MATRIX_EPSILON {
+        return upper;
+    }
+    upper.inversed().transposed()
 }
 
 /// Calculates the transformation of the object with a size.
@@ -1,9 +1,12 @@
 //! Contains the `Update` entity trait
 
 /// Fires the `update` method, every frame.
+/// # Note
+/// Implemented by entity types with per-frame logic, see `EntityType::as_update_mut` and
+/// `EntityTree::update_all`.
 pub trait Update {
     /// Fires, every frame.
     /// # Arguements
     /// - `delta`: the time between the last frame and the second to last frame
-    fn update(delta: f32);
+    fn update(&mut self, delta: f32);
 }
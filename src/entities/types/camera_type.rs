@@ -1,18 +1,45 @@
 //! Contains the `CameraType` entity variant
 
-use ultraviolet::{Mat4, projection::perspective_gl};
+use std::f32::consts::FRAC_PI_2;
+
+use ultraviolet::{
+    Mat4, Rotor3,
+    projection::{orthographic_gl, perspective_gl},
+};
 
 use crate::{
-    datatypes::vectors::Vector3,
+    datatypes::{frustum::Frustum, vectors::Vector3},
     entities::{entity::EntityTrait, traits::object_3d::*},
 };
 use derive_akhoik_ge::Object3D;
 
+/// The narrowest vertical field of view, in degrees.
+pub const MIN_FOV_DEGREES: f32 = 1.0;
+/// The widest vertical field of view, in degrees.
+pub const MAX_FOV_DEGREES: f32 = 179.0;
+
+/// How a camera projects the world onto the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ProjectionKind {
+    /// Far away objects are smaller, uses the camera's `fov`
+    #[default]
+    Perspective,
+    /// Objects are the same size at any distance, used for UI, maps and 2D overlays
+    Orthographic {
+        /// The height of the view, in world units
+        size: f32,
+    },
+}
+
 /// A camera used for rendering
-#[derive(Debug, Object3D)]
+#[derive(Debug, Clone, Object3D)]
 pub struct Camera {
-    /// The vertical field of view
+    /// The vertical field of view, in radians
+    /// # Note
+    /// Use `set_fov_degrees` when working in degrees.
     pub fov: f32,
+    /// How the camera projects the world, see `get_projection`
+    pub projection: ProjectionKind,
     /// The transform of the camera
     pub transform: Mat4,
 
@@ -26,11 +53,12 @@ pub struct Camera {
     up: Vector3,
     position: Vector3,
     rotation: Vector3,
+    orientation: Option<Rotor3>,
 }
 impl Camera {
     /// Create a new `CameraType`.
     /// # Arguements
-    /// - `fov`: the vertical field of view
+    /// - `fov`: the vertical field of view, in radians
     /// - `near_view`: how close an vertex can be until it won't be rendered
     /// - `far_view`: how far an vertex can be until it won't be rendered
     /// # Returns
@@ -47,13 +75,127 @@ impl Camera {
         new
     }
 
-    /// Gets the perspective projection of the camera
+    /// Create a new orthographic `CameraType`.
+    /// # Arguements
+    /// - `size`: the height of the view, in world units
+    /// - `near_view`: how close an vertex can be until it won't be rendered
+    /// - `far_view`: how far an vertex can be until it won't be rendered
+    /// # Returns
+    /// A new `CameraType`
+    pub fn new_orthographic(size: f32, near_view: f32, far_view: f32) -> Self {
+        let mut new = Self {
+            projection: ProjectionKind::Orthographic { size },
+            near_view,
+            far_view,
+            ..Default::default()
+        };
+
+        new.recalculate_transform();
+        new
+    }
+
+    /// Sets the vertical field of view in degrees.
+    /// # Arguements
+    /// - `deg`: the field of view in degrees, clamped between `MIN_FOV_DEGREES` and
+    ///   `MAX_FOV_DEGREES`
+    pub fn set_fov_degrees(&mut self, deg: f32) {
+        self.fov = deg.clamp(MIN_FOV_DEGREES, MAX_FOV_DEGREES).to_radians();
+    }
+
+    /// Gets the vertical field of view in degrees.
+    /// # Returns
+    /// The field of view in degrees
+    pub fn fov_degrees(&self) -> f32 {
+        self.fov.to_degrees()
+    }
+
+    /// Points the camera at a target.
+    ///
+    /// Updates the `front`, `right` and `up` vectors, and the rotation, so that later changes to
+    /// the rotation start from the new direction.
+    /// # Arguements
+    /// - `target`: the position being looked at
+    /// - `up`: the world's up direction, usually `Vector3::up()`
+    /// # Note
+    /// - Nothing happens, if `target` is the same as the camera's position.
+    /// - If `up` is parallel with the direction, another axis is used instead.
+    /// - The roll is reset to zero.
+    pub fn look_at(&mut self, target: Vector3, up: Vector3) {
+        let direction = target - self.position;
+        if direction.length() < f32::EPSILON {
+            return;
+        }
+
+        let front = direction.get_unit();
+        let mut right = front.cross(up).get_unit();
+        if right == Vector3::zero() {
+            right = front.cross(Vector3::forward()).get_unit();
+        }
+        if right == Vector3::zero() {
+            right = Vector3::right();
+        }
+
+        self.front = front;
+        self.right = right;
+        self.up = right.cross(front).get_unit();
+
+        // the inverse of `Object3D::update_vectors`, see `euler_to_orientation`
+        let pitch = front.y.clamp(-1.0, 1.0).asin();
+        let yaw = (-front.x).atan2(-front.z);
+        self.rotation = Vector3::new(pitch.to_degrees(), yaw.to_degrees(), 0.0);
+        self.orientation = None;
+        self.recalculate_transform();
+    }
+
+    /// Gets the view matrix of the camera, which transforms world space into the camera's space.
+    /// # Returns
+    /// The inverse of the camera's `transform`
+    /// # Note
+    /// The camera looks along it's `front`, which is -Z with no rotation (like OpenGL).
+    pub fn get_view_matrix(&self) -> Mat4 {
+        self.transform.inversed()
+    }
+
+    /// Gets the view matrix multiplied by the projection.
+    /// # Arguements
+    /// - `aspect_ratio`: the aspect ratio of the screen
+    /// # Returns
+    /// `get_projection(aspect_ratio) * get_view_matrix()`
+    pub fn get_view_projection(&self, aspect_ratio: f32) -> Mat4 {
+        self.get_projection(aspect_ratio) * self.get_view_matrix()
+    }
+
+    /// Gets the volume the camera can see, used for culling.
+    /// # Arguements
+    /// - `aspect_ratio`: the aspect ratio of the screen
+    /// # Returns
+    /// The frustum of `get_view_projection`, in world space
+    pub fn get_frustum(&self, aspect_ratio: f32) -> Frustum {
+        Frustum::from_matrix(self.get_view_projection(aspect_ratio))
+    }
+
+    /// Gets the projection of the camera, depending on it's `projection`
     /// # Arguements
     /// - `aspect_ratio`: the aspect ratio of the screen
     /// # Returns
     /// A projection matrix
     pub fn get_projection(&self, aspect_ratio: f32) -> Mat4 {
-        perspective_gl(self.fov, aspect_ratio, self.near_view, self.far_view)
+        match self.projection {
+            ProjectionKind::Perspective => {
+                perspective_gl(self.fov, aspect_ratio, self.near_view, self.far_view)
+            }
+            ProjectionKind::Orthographic { size } => {
+                let (half_width, half_height) = (size * aspect_ratio / 2.0, size / 2.0);
+                orthographic_gl(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.near_view,
+                    self.far_view,
+                )
+            }
+        }
     }
 }
 
@@ -62,13 +204,15 @@ impl EntityTrait for Camera {}
 impl Default for Camera {
     fn default() -> Self {
         Self {
-            fov: 90.0,
+            fov: FRAC_PI_2,
+            projection: ProjectionKind::Perspective,
             near_view: 0.1,
             far_view: 100.0,
             transform: Mat4::default(),
             position: Vector3::zero(),
             rotation: Vector3::zero(),
-            front: Vector3::forward(),
+            orientation: None,
+            front: -Vector3::forward(),
             right: Vector3::right(),
             up: Vector3::up(),
         }
@@ -0,0 +1,86 @@
+//! Contains the `FlyController`, a first-person camera controller
+
+use beryllium::events::{SDLK_a, SDLK_d, SDLK_s, SDLK_w};
+
+use crate::{
+    datatypes::vectors::Vector3,
+    entities::{
+        traits::object_3d::Object3D,
+        types::{camera_type::Camera, io_service::InputService},
+    },
+};
+
+/// The highest pitch (and lowest negated), in degrees. Stops the camera from flipping over.
+pub const MAX_PITCH_DEGREES: f32 = 89.0;
+
+/// Moves and rotates a camera, like a first-person fly camera.
+/// - `W`/`S`: moves along the camera's front
+/// - `A`/`D`: moves along the camera's right
+/// - Mouse: rotates the camera
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlyController {
+    /// How far the camera moves every second, in world units
+    pub speed: f32,
+    /// How many degrees the camera rotates, for each pixel the mouse moves
+    pub sensitivity: f32,
+}
+impl FlyController {
+    /// Creates a new fly controller.
+    /// # Arguements
+    /// - `speed`: how far the camera moves every second
+    /// - `sensitivity`: the degrees rotated, for each pixel the mouse moves
+    /// # Returns
+    /// `FlyController`
+    pub fn new(speed: f32, sensitivity: f32) -> Self {
+        Self { speed, sensitivity }
+    }
+
+    /// Moves and rotates the camera, from this frame's input.
+    /// # Arguements
+    /// - `camera`: the camera being controlled
+    /// - `input`: the input of this frame
+    /// - `delta`: the seconds since the last frame
+    /// # Note
+    /// The camera is rotated before it is moved, the pitch is clamped to `MAX_PITCH_DEGREES`.
+    pub fn update(&self, camera: &mut Camera, input: &InputService, delta: f32) {
+        let mouse_delta = input.get_mouse_delta();
+        if mouse_delta.x != 0.0 || mouse_delta.y != 0.0 {
+            // see `euler_to_orientation`, a positive yaw turns left and a positive pitch turns up,
+            // so moving the mouse right (+X) or down (+Y) lowers them
+            let mut rotation = camera.get_rotation();
+            rotation.y -= mouse_delta.x * self.sensitivity;
+            rotation.x = (rotation.x - mouse_delta.y * self.sensitivity)
+                .clamp(-MAX_PITCH_DEGREES, MAX_PITCH_DEGREES);
+
+            camera.set_rotation(rotation);
+        }
+
+        let is_held = |keycode| input.is_key_pressed(keycode) || input.is_key_down(keycode);
+        let mut direction = Vector3::zero();
+        if is_held(SDLK_w) {
+            direction += camera.get_front();
+        }
+        if is_held(SDLK_s) {
+            direction -= camera.get_front();
+        }
+        if is_held(SDLK_d) {
+            direction += camera.get_right();
+        }
+        if is_held(SDLK_a) {
+            direction -= camera.get_right();
+        }
+
+        let direction = direction.get_unit();
+        if direction == Vector3::zero() {
+            return;
+        }
+
+        camera.translate(direction * (self.speed * delta));
+    }
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self::new(5.0, 0.1)
+    }
+}
@@ -1,9 +1,14 @@
 //! Contains the `GameType` entity variant
 
-use crate::entities::entity::EntityTrait;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::entities::{entity::EntityTrait, traits::update::Update, types::camera_type::Camera};
+
+/// The near view of the default camera, for every genre.
+pub const DEFAULT_NEAR_VIEW: f32 = 0.1;
 
 /// The game's genre
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub enum GameGenre {
     /// Action
     Action,
@@ -14,12 +19,69 @@ pub enum GameGenre {
     Undefined,
 }
 
+impl GameGenre {
+    /// Gets the name of the genre.
+    /// # Returns
+    /// The name, the same as the variant's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Action => "Action",
+            Self::Adventure => "Adventure",
+            Self::Undefined => "Undefined",
+        }
+    }
+
+    /// Gets the vertical field of view of the genre's default camera.
+    /// # Returns
+    /// The field of view in radians:
+    /// - Action: 90°
+    /// - Adventure: 100°, wider to show more of the world
+    /// - Undefined: 90°
+    pub fn default_fov(&self) -> f32 {
+        match self {
+            Self::Action | Self::Undefined => FRAC_PI_2,
+            Self::Adventure => 100.0_f32.to_radians(),
+        }
+    }
+
+    /// Gets the far view of the genre's default camera.
+    /// # Returns
+    /// The far view:
+    /// - Action: 250.0, further so fast moving players can see what's ahead
+    /// - Adventure: 100.0
+    /// - Undefined: 100.0
+    pub fn default_far_view(&self) -> f32 {
+        match self {
+            Self::Action => 250.0,
+            Self::Adventure | Self::Undefined => 100.0,
+        }
+    }
+
+    /// Gets the genre from it's name.
+    /// # Arguements
+    /// - `name`: the name of the genre, see `name`
+    /// # Returns
+    /// The genre, or `None` if the name isn't a genre
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Action" => Some(Self::Action),
+            "Adventure" => Some(Self::Adventure),
+            "Undefined" => Some(Self::Undefined),
+            _ => None,
+        }
+    }
+}
+
 /// The game entity type.
 /// Used as a head of a `EntityTree`.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Game {
     /// The game genre
     pub genre: GameGenre,
+    /// The seconds the game has been updated for
+    pub elapsed_time: f32,
+    /// The amount of frames the game has been updated for
+    pub frame_count: u64,
 }
 impl Game {
     /// Creates a new Game entity.
@@ -28,16 +90,39 @@ impl Game {
     /// # Return
     /// `Game` entity type
     pub fn new(genre: GameGenre) -> Self {
-        Self { genre }
+        Self {
+            genre,
+            ..Default::default()
+        }
+    }
+
+    /// Creates the default camera of the game's genre.
+    /// # Returns
+    /// A perspective camera using `GameGenre::default_fov` and `GameGenre::default_far_view`
+    pub fn default_camera(&self) -> Camera {
+        Camera::new(
+            self.genre.default_fov(),
+            DEFAULT_NEAR_VIEW,
+            self.genre.default_far_view(),
+        )
     }
 }
 
 impl EntityTrait for Game {}
 
+impl Update for Game {
+    fn update(&mut self, delta: f32) {
+        self.elapsed_time += delta;
+        self.frame_count += 1;
+    }
+}
+
 impl Default for Game {
     fn default() -> Self {
         Self {
             genre: GameGenre::Undefined,
+            elapsed_time: 0.0,
+            frame_count: 0,
         }
     }
 }
@@ -1,11 +1,10 @@
 //! Handles the use of Inputs
 
-// TODO: Mouse support later
 use std::collections::HashMap;
 
-use beryllium::events::SDL_Keycode as Keycode;
+use beryllium::events::{Event, SDL_Keycode as Keycode, SDL_Keymod as Keymod};
 
-use crate::entities::entity::EntityTrait;
+use crate::{datatypes::vectors::Vector2, entities::entity::EntityTrait};
 
 /// The status of a key on a keyboard
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -20,21 +19,27 @@ pub enum PressedStatus {
     None,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct KeyStatus {
     pressed_status: PressedStatus,
 }
 
 /// Handles key inputs
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InputService {
     global_key_status: HashMap<Keycode, KeyStatus>,
     has_changed: bool,
+    mouse_delta: Vector2,
+    scroll_delta: Vector2,
+    modifiers: Keymod,
 }
 
 impl InputService {
     /// Removes all Keys marked as `Released`, convert Keys marked as `Pressed` to `Down`.
+    /// Also resets the mouse and scroll deltas.
     pub fn mark_cleanup(&mut self) {
+        self.mouse_delta = Vector2::zero();
+        self.scroll_delta = Vector2::zero();
         if !self.has_changed {
             return;
         }
@@ -52,28 +57,129 @@ impl InputService {
         })
     }
 
+    /// Clears all of the input state, as if no key was ever pressed.
+    /// # Note
+    /// - Used when the window loses or regains focus, so that keys aren't reported as held.
+    /// - Clears the keys, the mouse and scroll deltas and the modifiers.
+    pub fn reset(&mut self) {
+        self.global_key_status.clear();
+        self.has_changed = false;
+        self.mouse_delta = Vector2::zero();
+        self.scroll_delta = Vector2::zero();
+        self.modifiers = Keymod::default();
+    }
+
+    /// Updates the input state from an SDL event.
+    /// # Arguements
+    /// - `event`: the event, polled by the window
+    /// # Returns
+    /// If the event was used by the service
+    /// # Note
+    /// - Key events are given to `provide_input`, and update the modifiers.
+    /// - Mouse motion events are given to `provide_mouse_motion`.
+    /// - Mouse wheel events are given to `provide_scroll`.
+    /// - Gaining or losing keyboard focus calls `reset`.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key {
+                pressed,
+                keycode,
+                modifiers,
+                ..
+            } => {
+                self.provide_modifiers(*modifiers);
+                self.provide_input(*keycode, *pressed);
+                true
+            }
+            Event::MouseMotion {
+                x_delta, y_delta, ..
+            } => {
+                self.provide_mouse_motion(Vector2::new(*x_delta as f32, *y_delta as f32));
+                true
+            }
+            Event::MouseWheel { x, y, .. } => {
+                self.provide_scroll(Vector2::new(*x as f32, *y as f32));
+                true
+            }
+            Event::WindowGainedKeyboardFocus { .. } | Event::WindowLostKeyboardFocus { .. } => {
+                self.reset();
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Adds or mutates a new entry inside of InputService.
     /// # Arguements
     /// - `keycode`: the keycode
     /// - `pressed`: if the button has been pressed
+    /// # Note
+    /// - Pressing a key that is already `Pressed` or `Down` is ignored, e.g. auto-repeats.
+    /// - Releasing a key that isn't active is ignored.
     pub fn provide_input(&mut self, keycode: Keycode, pressed: bool) {
-        if let Some(key_status) = self.global_key_status.get_mut(&keycode) {
+        let Some(key_status) = self.global_key_status.get_mut(&keycode) else {
             if pressed {
-                eprintln!("pressed status is set to down, but the entry exists");
+                let key_status = KeyStatus {
+                    pressed_status: PressedStatus::Pressed,
+                };
+                self.global_key_status.insert(keycode, key_status);
+                self.has_changed = true;
             }
-
-            key_status.pressed_status = PressedStatus::Released;
-            self.has_changed = true;
             return;
-        }
+        };
 
-        let key_status = KeyStatus {
-            pressed_status: PressedStatus::Pressed,
+        let new_status = match (pressed, key_status.pressed_status) {
+            (true, PressedStatus::Pressed | PressedStatus::Down) => return,
+            (true, _) => PressedStatus::Pressed,
+            (false, _) => PressedStatus::Released,
         };
-        self.global_key_status.insert(keycode, key_status);
+
+        key_status.pressed_status = new_status;
         self.has_changed = true;
     }
 
+    /// Adds to the mouse's movement of this frame.
+    /// # Arguements
+    /// - `delta`: the movement in pixels, +Y is down the screen
+    pub fn provide_mouse_motion(&mut self, delta: Vector2) {
+        self.mouse_delta += delta;
+    }
+
+    /// Gets how far the mouse moved, since the last `mark_cleanup`.
+    /// # Returns
+    /// The movement in pixels, +Y is down the screen
+    pub fn get_mouse_delta(&self) -> Vector2 {
+        self.mouse_delta
+    }
+
+    /// Sets the modifier keys (e.g. shift and control), held during the last key event.
+    /// # Arguements
+    /// - `modifiers`: the modifiers, see the `KMOD_*` constants
+    pub fn provide_modifiers(&mut self, modifiers: Keymod) {
+        self.modifiers = modifiers;
+    }
+
+    /// Gets the modifier keys (e.g. shift and control) held during the last key event.
+    /// # Returns
+    /// The modifiers, see the `KMOD_*` constants
+    pub fn get_modifiers(&self) -> Keymod {
+        self.modifiers
+    }
+
+    /// Adds to the mouse wheel's scroll of this frame.
+    /// # Arguements
+    /// - `delta`: the scroll, +Y is away from the user
+    pub fn provide_scroll(&mut self, delta: Vector2) {
+        self.scroll_delta += delta;
+    }
+
+    /// Gets how far the mouse wheel scrolled, since the last `mark_cleanup`.
+    /// # Returns
+    /// The scroll, +Y is away from the user
+    pub fn get_scroll_delta(&self) -> Vector2 {
+        self.scroll_delta
+    }
+
     /// Has the `keycode` been pressed?
     /// # Arguements
     /// - `keycode`: the keycode being checked
@@ -180,6 +286,9 @@ impl Default for InputService {
         Self {
             global_key_status: HashMap::with_capacity(64),
             has_changed: false,
+            mouse_delta: Vector2::zero(),
+            scroll_delta: Vector2::zero(),
+            modifiers: Keymod::default(),
         }
     }
 }
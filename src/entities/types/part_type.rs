@@ -1,24 +1,32 @@
 //! Contains the `PartType` entity which is used to make a visable object like a building block.
 
 use derive_akhoik_ge::{Object3D, Object3DSize};
-use ultraviolet::Mat4;
+use ultraviolet::{Mat3, Mat4, Rotor3};
 
 use crate::{
-    datatypes::{color::Color3, vectors::Vector3},
-    entities::{entity::EntityTrait, traits::object_3d::*},
+    datatypes::{color::Color3, ray::Ray, vectors::Vector3},
+    entities::{entity::EntityTrait, material::Material, traits::object_3d::*},
     mesh::{Mesh, MeshParseError},
-    texture::Texture,
+    texture::{Texture, TextureSlots},
 };
 
+/// The slot of the diffuse texture, the name of the sampler uniform in the fragment shader.
+pub const DIFFUSE_TEXTURE_SLOT: &str = "texture0";
+
 /// The part entity type.
 /// Used as a building block.
-#[derive(Debug, Object3D, Object3DSize)]
+#[derive(Debug, Clone, Object3D, Object3DSize)]
 pub struct Part {
     /// The mesh of the part
     mesh: Mesh,
-    texture: Option<Texture>,
+    /// The file the mesh was loaded from, used when saving scenes
+    mesh_path: Option<String>,
+    /// The textures of the part, shared between clones of the part
+    textures: TextureSlots,
     /// The color assigned
     pub color: Color3,
+    /// The material, drawn with instead of the window's shader program, `color` and textures
+    pub material: Option<Material>,
     /// Is the the part visable to the renderer
     pub visable: bool,
     /// The transformation
@@ -31,6 +39,8 @@ pub struct Part {
     position: Vector3,
     /// The euler rotation
     rotation: Vector3,
+    /// The orientation, used instead of `rotation` when set
+    orientation: Option<Rotor3>,
     /// The size of the part
     size: Vector3,
 }
@@ -60,6 +70,71 @@ impl Part {
         &self.mesh
     }
 
+    /// Gets the file the mesh was loaded from.
+    /// # Returns
+    /// The path given to `load_mesh_from_file`, or `None` if the mesh wasn't loaded from a file
+    pub fn get_mesh_path(&self) -> Option<&str> {
+        self.mesh_path.as_deref()
+    }
+
+    /// Gets the axis-aligned bounding box of the mesh, in world space.
+    /// # Returns
+    /// The minimum and maximum corners, or zero bounds if the mesh has no vertices
+    /// # Note
+    /// The corners of the mesh's box are transformed by `transform`, so a rotated part gets a
+    /// box that fits the rotated box, not the mesh.
+    pub fn world_bounding_box(&self) -> (Vector3, Vector3) {
+        self.bounding_box_with(self.transform)
+    }
+
+    /// Gets the axis-aligned bounding box of the mesh, transformed by a matrix.
+    /// # Arguements
+    /// - `transform`: the transformation, e.g. `EntityTree::world_transform` which includes
+    ///   the ancestors
+    /// # Returns
+    /// The minimum and maximum corners, or zero bounds if the mesh has no vertices
+    pub fn bounding_box_with(&self, transform: Mat4) -> (Vector3, Vector3) {
+        if self.mesh.vertices.is_empty() {
+            return (Vector3::zero(), Vector3::zero());
+        }
+
+        let (min, max) = self.mesh.bounding_box();
+        let mut corners = (0..8).map(|i| {
+            let corner = Vector3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            );
+            Vector3::from(transform.transform_point3(corner.into()))
+        });
+
+        let first = corners.next().unwrap_or_default();
+        corners.fold((first, first), |(min, max), pos| {
+            (min.min(pos), max.max(pos))
+        })
+    }
+
+    /// Finds where a ray first hits the part's mesh, see `Mesh::raycast`.
+    /// # Arguements
+    /// - `ray`: the ray, in world space
+    /// # Returns
+    /// Either:
+    /// - `Some`: the distance along the ray (in lengths of it's direction) of the nearest hit
+    /// - `None`: the ray didn't hit the part
+    /// # Note
+    /// The ray is moved into the part's local space, with the inverse of `transform`.
+    pub fn raycast(&self, ray: &Ray) -> Option<f32> {
+        self.mesh
+            .raycast(&ray.transformed(self.transform.inversed()))
+    }
+
+    /// Gets the matrix used to transform the mesh's normals, see `normal_matrix`.
+    /// # Returns
+    /// The inverse-transpose of the upper-left 3x3 of `transform`
+    pub fn get_normal_matrix(&self) -> Mat3 {
+        normal_matrix(&self.transform)
+    }
+
     /// Gets the mesh of the part as a mutable borrow.
     /// # Returns
     /// A mutable borrow of a mesh
@@ -67,24 +142,80 @@ impl Part {
         &mut self.mesh
     }
 
-    /// Gets the texture of the part.
+    /// Gets the diffuse texture of the part.
     /// # Returns
     /// Either:
     /// - The borrowed texture
     /// - `None`
+    /// # Note
+    /// The same as `get_texture_slot(DIFFUSE_TEXTURE_SLOT)`.
     pub fn get_texture(&self) -> Option<&Texture> {
-        let Some(texture) = &self.texture else {
-            return None;
-        };
-        Some(texture)
+        self.get_texture_slot(DIFFUSE_TEXTURE_SLOT)
+    }
+
+    /// Does the part need blending, because it's diffuse texture is translucent?
+    /// # Returns
+    /// If the part should be drawn after the opaque parts
+    /// # Note
+    /// With a material, the material's textures are checked instead, since they're drawn
+    /// instead of the part's.
+    pub fn is_translucent(&self) -> bool {
+        match &self.material {
+            Some(material) => material.is_translucent(),
+            None => self
+                .get_texture()
+                .is_some_and(|texture| texture.is_translucent()),
+        }
+    }
+
+    /// Sets the diffuse texture of the part.
+    /// # Arguements
+    /// - `texture`: the new texture to be assigned
+    /// # Note
+    /// The same as `set_texture_slot(DIFFUSE_TEXTURE_SLOT, texture)`.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.set_texture_slot(DIFFUSE_TEXTURE_SLOT, texture);
+    }
+
+    /// Gets a texture of the part by it's slot.
+    /// # Arguements
+    /// - `name`: the name of the slot, which is the sampler uniform's name
+    /// # Returns
+    /// Either:
+    /// - The borrowed texture
+    /// - `None`, if the slot is empty
+    pub fn get_texture_slot(&self, name: &str) -> Option<&Texture> {
+        self.textures.get(name)
     }
 
-    /// Sets the texture of the part.
+    /// Sets a texture of the part, e.g. a normal or specular map.
     /// # Arguements
+    /// - `name`: the name of the slot, which is the sampler uniform's name
     /// - `texture`: the new texture to be assigned
-    pub fn set_texture(&mut self, mut texture: Texture) {
-        texture.load_to_gl();
-        self.texture = Some(texture);
+    /// # Note
+    /// - A new slot is bound to the next texture unit, replacing a texture keeps it's unit.
+    /// - Uploads the texture to GL, so requires a GL context.
+    pub fn set_texture_slot(&mut self, name: &str, texture: Texture) {
+        self.textures.set(name, texture);
+    }
+
+    /// Releases the GPU resources of the part, it's textures and material.
+    /// # Note
+    /// - Textures and materials are shared between clones of the part, so a GL texture is only
+    ///   deleted once the last part using it is released or dropped.
+    /// - Meshes are uploaded to the window's buffers every draw, so a part doesn't own any GL
+    ///   buffers.
+    /// - The part is drawn with only it's `color` afterwards, until a new texture is set.
+    pub fn release_gpu(&mut self) {
+        self.textures.clear();
+        self.material = None;
+    }
+
+    /// Gets every texture of the part.
+    /// # Returns
+    /// The texture slots, which bind each texture to it's texture unit
+    pub fn texture_slots(&self) -> &TextureSlots {
+        &self.textures
     }
 
     /// Loads a new mesh for the part.
@@ -93,6 +224,7 @@ impl Part {
     pub fn load_mesh(&mut self, mesh: &Mesh) {
         let cloned_mesh = mesh.clone();
         self.mesh = cloned_mesh;
+        self.mesh_path = None;
     }
 
     /// Loads a new mesh for the part from a file.
@@ -112,6 +244,7 @@ impl Part {
     pub fn load_mesh_from_file(&mut self, path: &str) -> Result<(), MeshParseError> {
         let mesh = Mesh::load_mesh_from_file(path)?;
         self.mesh = mesh;
+        self.mesh_path = Some(path.to_string());
         Ok(())
     }
 }
@@ -122,13 +255,16 @@ impl Default for Part {
     fn default() -> Self {
         Self {
             mesh: Mesh::default(),
-            texture: None,
+            mesh_path: None,
+            textures: TextureSlots::default(),
             color: Color3::default(),
+            material: None,
             position: Vector3::zero(),
             rotation: Vector3::zero(),
+            orientation: None,
             transform: Mat4::identity(),
             visable: true,
-            front: Vector3::forward(),
+            front: -Vector3::forward(),
             right: Vector3::right(),
             up: Vector3::up(),
             size: Vector3::one(),
@@ -1,10 +1,49 @@
 //! Adds many utility functions and types to help with rendering
-use std::fs;
+use std::{borrow::Cow, cell::Cell, ffi::c_void, fs, mem, ops::Deref, time::SystemTime};
 
 use ogl33::*;
-use ultraviolet::Mat4;
+use ultraviolet::{Mat3, Mat4};
 
-use crate::datatypes::{color::Color3, vectors::Vector3};
+use crate::datatypes::{
+    color::Color3,
+    vectors::{Vector2, Vector3},
+};
+
+thread_local! {
+    /// The amount of live GL contexts on this thread, see `ContextGuard`.
+    static LIVE_CONTEXTS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Checks if a GL context is alive on this thread.
+/// # Returns
+/// If a `ContextGuard` (owned by `Window`) hasn't been dropped yet
+/// # Note
+/// The GL object wrappers only delete their objects when dropped while a context is alive, as
+/// calling GL without a context is undefined. Otherwise the objects were freed with the context.
+pub fn has_gl_context() -> bool {
+    LIVE_CONTEXTS.with(|count| count.get() > 0)
+}
+
+/// Marks a GL context as alive on this thread (see `has_gl_context`), until it's dropped.
+/// # Note
+/// Must be dropped after the context is destroyed.
+#[derive(Debug)]
+pub struct ContextGuard(());
+impl ContextGuard {
+    /// Marks a GL context as alive.
+    /// # Returns
+    /// The guard, which marks the context as destroyed when dropped
+    pub fn acquire() -> Self {
+        LIVE_CONTEXTS.with(|count| count.set(count.get() + 1));
+        Self(())
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        LIVE_CONTEXTS.with(|count| count.set(count.get().saturating_sub(1)));
+    }
+}
 
 /// A `vertex array object` used for rendering meshes.
 pub struct VertexArray(pub GLuint);
@@ -12,14 +51,20 @@ impl VertexArray {
     /// Creates a new VAO
     /// # Returns
     /// Either:
-    /// - `None`,
-    /// - A new Vertex Array.
-    pub fn new() -> Option<Self> {
+    /// - A new Vertex Array,
+    /// - An error message, usually when there is no current GL context.
+    pub fn new() -> Result<Self, String> {
         let mut vao = 0_u32;
         unsafe {
             glGenVertexArrays(1, &mut vao);
         };
-        if vao != 0 { Some(Self(vao)) } else { None }
+        let vao = Self(vao);
+        check_gl_error("couldn't make a vertex array")?;
+        if vao.0 != 0 {
+            Ok(vao)
+        } else {
+            Err(no_object_error("vertex array"))
+        }
     }
 
     /// Binds the Vertex Array to GL.
@@ -37,15 +82,93 @@ impl VertexArray {
     }
 }
 
+impl Drop for VertexArray {
+    fn drop(&mut self) {
+        if self.0 != 0 && has_gl_context() {
+            unsafe { glDeleteVertexArrays(1, &self.0) };
+        }
+    }
+}
+
+/// The error message when a GL object couldn't be made, without GL reporting an error.
+fn no_object_error(object: &str) -> String {
+    format!("couldn't make a {}, is there a current GL context?", object)
+}
+
+/// Gets the name of a GL error code.
+/// # Arguements
+/// - `code`: the error code, from `glGetError`
+/// # Returns
+/// The name of the error, such as `GL_INVALID_ENUM`
+pub fn gl_error_name(code: GLenum) -> &'static str {
+    match code {
+        GL_NO_ERROR => "GL_NO_ERROR",
+        GL_INVALID_ENUM => "GL_INVALID_ENUM",
+        GL_INVALID_VALUE => "GL_INVALID_VALUE",
+        GL_INVALID_OPERATION => "GL_INVALID_OPERATION",
+        GL_INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        GL_OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        _ => "unknown GL error",
+    }
+}
+
+/// Checks if GL has reported an error.
+/// # Arguements
+/// - `message`: describes what failed, used as the start of the error message
+/// # Returns
+/// Either:
+/// - Nothing, when there is no error
+/// - An error message, containing the GL error code
+pub fn check_gl_error(message: &str) -> Result<(), String> {
+    let code = unsafe { glGetError() };
+    if code == GL_NO_ERROR {
+        return Ok(());
+    }
+    Err(format!(
+        "{}: {} ({:#x})",
+        message,
+        gl_error_name(code),
+        code
+    ))
+}
+
+/// Reads an info log of a shader or program.
+/// # Arguements
+/// - `needed_len`: the length of the log, including the null terminator
+/// - `read_log`: reads the log, given the max length, the written length and the buffer
+/// # Returns
+/// The log, empty if there is no log
+fn read_info_log(
+    needed_len: GLint,
+    read_log: impl FnOnce(GLsizei, *mut GLsizei, *mut GLchar),
+) -> String {
+    if needed_len <= 0 {
+        return String::new();
+    }
+
+    let mut v = vec![0_u8; needed_len as usize];
+    let mut len_written: GLsizei = 0;
+    read_log(needed_len, &mut len_written, v.as_mut_ptr().cast());
+
+    v.truncate(len_written.clamp(0, needed_len) as usize);
+    String::from_utf8_lossy(&v)
+        .trim_end_matches('\0')
+        .to_owned()
+}
+
 /// The type of `Shader`
 pub enum ShaderType {
     /// Vertex Shader
     Vertex = GL_VERTEX_SHADER as isize,
     /// Fragment Shader
     Fragment = GL_FRAGMENT_SHADER as isize,
+    /// Geometry Shader
+    Geometry = GL_GEOMETRY_SHADER as isize,
+    /// Compute Shader, requires GL 4.3 (see `ComputeProgram`)
+    Compute = GL_COMPUTE_SHADER as isize,
 }
 
-/// A shader which could either be: `Vertex` or `Fragment`.
+/// A shader which could either be: `Vertex`, `Fragment` or `Geometry`.
 pub struct Shader(pub GLuint);
 impl Shader {
     /// Creates a new Shader.
@@ -53,14 +176,15 @@ impl Shader {
     /// - `ty`: the shader type
     /// # Returns
     /// Either:
-    /// - `None`,
-    /// - A shader
-    pub fn new(ty: ShaderType) -> Option<Self> {
-        let shader = unsafe { glCreateShader(ty as GLenum) };
-        if shader != 0 {
-            Some(Self(shader))
+    /// - A shader,
+    /// - An error message, usually when there is no current GL context.
+    pub fn new(ty: ShaderType) -> Result<Self, String> {
+        let shader = Self(unsafe { glCreateShader(ty as GLenum) });
+        check_gl_error("couldn't make a shader")?;
+        if shader.0 != 0 {
+            Ok(shader)
         } else {
-            None
+            Err(no_object_error("shader"))
         }
     }
 
@@ -101,23 +225,16 @@ impl Shader {
     pub fn info_log(&self) -> String {
         let mut needed_len = 0;
         unsafe { glGetShaderiv(self.0, GL_INFO_LOG_LENGTH, &mut needed_len) };
-        let mut v: Vec<u8> = Vec::with_capacity(needed_len.try_into().unwrap());
-        let mut len_written = 0_i32;
-        unsafe {
-            glGetShaderInfoLog(
-                self.0,
-                v.capacity().try_into().unwrap(),
-                &mut len_written,
-                v.as_mut_ptr().cast(),
-            );
-            v.set_len(len_written.try_into().unwrap());
-        }
-        String::from_utf8_lossy(&v).into_owned()
+        read_info_log(needed_len, |max_len, len_written, buf| unsafe {
+            glGetShaderInfoLog(self.0, max_len, len_written, buf)
+        })
     }
 
     /// Deletes the `shader`
+    /// # Note
+    /// The same as dropping the shader.
     pub fn delete(self) {
-        unsafe { glDeleteShader(self.0) };
+        drop(self);
     }
 
     /// Creates and compiles a shader from it's type and source code.
@@ -129,7 +246,7 @@ impl Shader {
     /// - A shader,
     /// - An info log when an error occures from `info_log`
     pub fn from_source(ty: ShaderType, source: &str) -> Result<Self, String> {
-        let id = Self::new(ty).ok_or_else(|| "couldn't allocate new shader".to_string())?;
+        let id = Self::new(ty)?;
         id.set_source(source);
         id.compile();
         if id.compile_success() {
@@ -142,17 +259,31 @@ impl Shader {
     }
 }
 
+impl Drop for Shader {
+    fn drop(&mut self) {
+        if self.0 != 0 && has_gl_context() {
+            unsafe { glDeleteShader(self.0) };
+        }
+    }
+}
+
 /// A program used in GL.
+#[derive(Debug)]
 pub struct ShaderProgram(pub GLuint);
 impl ShaderProgram {
     /// Creates a new shader program.
     /// # Returns
     /// Either:
-    /// - `None`: when the creation was unsuccessful
-    /// - A new shader program
-    pub fn new() -> Option<Self> {
-        let prog = unsafe { glCreateProgram() };
-        if prog != 0 { Some(Self(prog)) } else { None }
+    /// - A new shader program,
+    /// - An error message, usually when there is no current GL context.
+    pub fn new() -> Result<Self, String> {
+        let prog = Self(unsafe { glCreateProgram() });
+        check_gl_error("couldn't make a shader program")?;
+        if prog.0 != 0 {
+            Ok(prog)
+        } else {
+            Err(no_object_error("shader program"))
+        }
     }
 
     /// Attaches the shader to the shader program
@@ -183,22 +314,10 @@ impl ShaderProgram {
     /// The info log
     pub fn info_log(&self) -> String {
         let mut needed_len = 0;
-        unsafe {
-            glGetProgramiv(self.0, GL_INFO_LOG_LENGTH, &mut needed_len);
-        };
-
-        let mut v: Vec<u8> = Vec::with_capacity(needed_len.try_into().unwrap());
-        let mut len_written = 0_i32;
-        unsafe {
-            glGetProgramInfoLog(
-                self.0,
-                v.capacity().try_into().unwrap(),
-                &mut len_written,
-                v.as_mut_ptr().cast(),
-            );
-            v.set_len(len_written.try_into().unwrap());
-        }
-        String::from_utf8_lossy(&v).into_owned()
+        unsafe { glGetProgramiv(self.0, GL_INFO_LOG_LENGTH, &mut needed_len) };
+        read_info_log(needed_len, |max_len, len_written, buf| unsafe {
+            glGetProgramInfoLog(self.0, max_len, len_written, buf)
+        })
     }
 
     /// Uses the shader program in GL.
@@ -207,8 +326,10 @@ impl ShaderProgram {
     }
 
     /// Deletes the shader program.
+    /// # Note
+    /// The same as dropping the shader program.
     pub fn delete(self) {
-        unsafe { glDeleteProgram(self.0) };
+        drop(self);
     }
 
     /// Creates a new program and links the fragmentation and vertex shader source code.
@@ -220,15 +341,43 @@ impl ShaderProgram {
     /// - The shader program
     /// - An error when linking or compiling shader.
     pub fn from_vert_frag(vert: &str, frag: &str) -> Result<Self, String> {
-        let p = Self::new().ok_or_else(|| "couldn't allocate a program".to_string())?;
+        Self::from_stages(vert, None, frag)
+    }
+
+    /// Creates a new program and links the vertex, geometry and fragmentation shader source code.
+    /// # Arguements
+    /// - `vert`: the vertex shader source code
+    /// - `geo`: the geometry shader source code
+    /// - `frag`: the fragmentation shader source code
+    /// # Returns
+    /// Either:
+    /// - The shader program
+    /// - An error when linking or compiling shader.
+    pub fn from_vert_geo_frag(vert: &str, geo: &str, frag: &str) -> Result<Self, String> {
+        Self::from_stages(vert, Some(geo), frag)
+    }
+
+    /// Compiles and links the shader stages, the geometry stage is optional.
+    fn from_stages(vert: &str, geo: Option<&str>, frag: &str) -> Result<Self, String> {
+        let p = Self::new()?;
         let v = Shader::from_source(ShaderType::Vertex, vert)
             .map_err(|e| format!("vertex compile error: {}", e))?;
+        let g = geo
+            .map(|geo| Shader::from_source(ShaderType::Geometry, geo))
+            .transpose()
+            .map_err(|e| format!("geometry compile error: {}", e))?;
         let f = Shader::from_source(ShaderType::Fragment, frag)
             .map_err(|e| format!("fragment compile error: {}", e))?;
         p.attach_shader(&v);
+        if let Some(g) = &g {
+            p.attach_shader(g);
+        }
         p.attach_shader(&f);
         p.link_program();
         v.delete();
+        if let Some(g) = g {
+            g.delete();
+        }
         f.delete();
         if p.link_success() {
             Ok(p)
@@ -248,12 +397,81 @@ impl ShaderProgram {
     /// - The shader program
     /// - An error when linking, opening files or compiling shaders.
     pub fn from_vert_frag_file(vert_path: &str, frag_path: &str) -> Result<Self, String> {
-        let (vert, frag) = (
-            fs::read_to_string(vert_path).expect("couldn't read vert shader file"),
-            fs::read_to_string(frag_path).expect("couldn't read frag shader file"),
-        );
+        Self::from_vert_frag(&read_shader_file(vert_path)?, &read_shader_file(frag_path)?)
+    }
+
+    /// Recompiles and relinks the program from the vertex and fragmentation shader files, e.g.
+    /// after editing them.
+    /// # Arguements
+    /// - `vert_path`: the vertex shader file path
+    /// - `frag_path`: the fragmentation shader file path
+    /// # Returns
+    /// Either:
+    /// - `Ok`, the old program is deleted and replaced
+    /// - An error when linking, opening files or compiling shaders. The old program is kept, so a
+    ///   typo doesn't stop rendering.
+    /// # Note
+    /// The new program has a different GL object, so it's uniforms must be set again.
+    pub fn reload_from_files(&mut self, vert_path: &str, frag_path: &str) -> Result<(), String> {
+        *self = Self::from_vert_frag_file(vert_path, frag_path)?;
+        Ok(())
+    }
+
+    /// Creates a new program and links the vertex, geometry and fragmentation shader source code
+    /// from the files.
+    /// # Arguements
+    /// - `vert_path`: the vertex shader file path
+    /// - `geo_path`: the geometry shader file path
+    /// - `frag_path`: the fragmentation shader file path
+    /// # Returns
+    /// Either:
+    /// - The shader program
+    /// - An error when linking, opening files or compiling shaders.
+    pub fn from_vert_geo_frag_file(
+        vert_path: &str,
+        geo_path: &str,
+        frag_path: &str,
+    ) -> Result<Self, String> {
+        Self::from_vert_geo_frag(
+            &read_shader_file(vert_path)?,
+            &read_shader_file(geo_path)?,
+            &read_shader_file(frag_path)?,
+        )
+    }
+
+    /// Binds a uniform block of the program to a binding point, so it reads the `UniformBuffer`
+    /// bound to the same point.
+    /// # Arguements
+    /// - `name`: the name of the block, the null terminator is optional
+    /// - `point`: the binding point
+    /// # Returns
+    /// If the block exists, blocks that aren't used by the shaders may be removed by GL
+    pub fn bind_uniform_block(&self, name: &str, point: GLuint) -> bool {
+        let name = format!("{}\0", name.trim_end_matches('\0'));
+        let index = unsafe { glGetUniformBlockIndex(self.0, name.as_ptr().cast()) };
+        if index == INVALID_INDEX {
+            return false;
+        }
+
+        unsafe { glUniformBlockBinding(self.0, index, point) };
+        true
+    }
+
+    /// Gets the location of a uniform in the program.
+    /// # Arguements
+    /// - `name`: the name of the uniform, the null terminator is optional
+    /// # Returns
+    /// The location, -1 if the uniform doesn't exist (setting it is silently ignored by GL)
+    pub fn uniform_location(&self, name: &str) -> GLint {
+        let name: Cow<str> = if name.ends_with('\0') {
+            Cow::Borrowed(name)
+        } else if name.contains('\0') {
+            return -1; // GL would only read up to the null byte
+        } else {
+            Cow::Owned(format!("{}\0", name))
+        };
 
-        Self::from_vert_frag(vert.as_str(), frag.as_str())
+        unsafe { glGetUniformLocation(self.0, name.as_ptr().cast()) }
     }
 
     /// Sets the a `bool` uniform value in the program.
@@ -262,10 +480,7 @@ impl ShaderProgram {
     /// - `value`: a boolean value
     pub fn set_bool(&self, name: &str, value: bool) {
         unsafe {
-            glUniform1i(
-                glGetUniformLocation(self.0, name.as_ptr().cast()),
-                value as i32,
-            );
+            glUniform1i(self.uniform_location(name), value as i32);
         }
     }
 
@@ -275,7 +490,7 @@ impl ShaderProgram {
     /// - `value`: a integer value
     pub fn set_int(&self, name: &str, value: i32) {
         unsafe {
-            glUniform1i(glGetUniformLocation(self.0, name.as_ptr().cast()), value);
+            glUniform1i(self.uniform_location(name), value);
         }
     }
 
@@ -285,7 +500,7 @@ impl ShaderProgram {
     /// - `value`: a float value
     pub fn set_float(&self, name: &str, value: f32) {
         unsafe {
-            glUniform1f(glGetUniformLocation(self.0, name.as_ptr().cast()), value);
+            glUniform1f(self.uniform_location(name), value);
         }
     }
 
@@ -295,12 +510,27 @@ impl ShaderProgram {
     /// - `value`: a 4x4 Matrix value
     pub fn set_matrix4(&self, name: &str, value: Mat4) {
         unsafe {
-            glUniformMatrix4fv(
-                glGetUniformLocation(self.0, name.as_ptr().cast()),
-                1,
-                GL_FALSE,
-                value.as_ptr(),
-            );
+            glUniformMatrix4fv(self.uniform_location(name), 1, GL_FALSE, value.as_ptr());
+        }
+    }
+
+    /// Sets the a `Mat3` uniform value in the program.
+    /// # Arguements
+    /// - `name`: the name of the value
+    /// - `value`: a 3x3 Matrix value, such as a normal matrix
+    pub fn set_matrix3(&self, name: &str, value: Mat3) {
+        unsafe {
+            glUniformMatrix3fv(self.uniform_location(name), 1, GL_FALSE, value.as_ptr());
+        }
+    }
+
+    /// Sets the a `Vector2` uniform value in the program.
+    /// # Arguements
+    /// - `name`: the name of the value
+    /// - `value`: a Vector2 value
+    pub fn set_vector2(&self, name: &str, vec: Vector2) {
+        unsafe {
+            glUniform2f(self.uniform_location(name), vec.x, vec.y);
         }
     }
 
@@ -310,12 +540,7 @@ impl ShaderProgram {
     /// - `value`: a Vector3 value
     pub fn set_vector3(&self, name: &str, vec: Vector3) {
         unsafe {
-            glUniform3f(
-                glGetUniformLocation(self.0, name.as_ptr().cast()),
-                vec.x,
-                vec.y,
-                vec.z,
-            );
+            glUniform3f(self.uniform_location(name), vec.x, vec.y, vec.z);
         }
     }
 
@@ -325,13 +550,221 @@ impl ShaderProgram {
     /// - `value`: a Color3 value
     pub fn set_color3(&self, name: &str, color: Color3) {
         unsafe {
-            glUniform3f(
-                glGetUniformLocation(self.0, name.as_ptr().cast()),
-                color.r,
-                color.g,
-                color.b,
-            );
+            glUniform3f(self.uniform_location(name), color.r, color.g, color.b);
+        }
+    }
+
+    /// Sets a uniform value of any supported type in the program.
+    /// # Arguements
+    /// - `name`: the name of the value
+    /// - `value`: the value, which picks the setter used
+    pub fn set_uniform(&self, name: &str, value: UniformValue) {
+        match value {
+            UniformValue::Bool(value) => self.set_bool(name, value),
+            UniformValue::Int(value) => self.set_int(name, value),
+            UniformValue::Float(value) => self.set_float(name, value),
+            UniformValue::Vector2(value) => self.set_vector2(name, value),
+            UniformValue::Vector3(value) => self.set_vector3(name, value),
+            UniformValue::Color3(value) => self.set_color3(name, value),
+            UniformValue::Matrix3(value) => self.set_matrix3(name, value),
+            UniformValue::Matrix4(value) => self.set_matrix4(name, value),
+        }
+    }
+}
+
+/// A uniform value that can be stored, then uploaded to a program later.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    /// A `bool` uniform
+    Bool(bool),
+    /// An `int` uniform
+    Int(i32),
+    /// A `float` uniform
+    Float(f32),
+    /// A `vec2` uniform
+    Vector2(Vector2),
+    /// A `vec3` uniform
+    Vector3(Vector3),
+    /// A `vec3` uniform, holding a color
+    Color3(Color3),
+    /// A `mat3` uniform
+    Matrix3(Mat3),
+    /// A `mat4` uniform
+    Matrix4(Mat4),
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        if self.0 != 0 && has_gl_context() {
+            unsafe { glDeleteProgram(self.0) };
+        }
+    }
+}
+
+/// Reads the source code of a shader file.
+fn read_shader_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("couldn't read shader {}: {}", path, e))
+}
+
+/// Watches shader files for changes, used to hot-reload shaders while editing them.
+#[derive(Debug, Clone)]
+pub struct ShaderWatcher {
+    /// The watched files
+    paths: Vec<String>,
+    /// The newest modification time of the files, when last checked
+    last_modified: Option<SystemTime>,
+}
+impl ShaderWatcher {
+    /// Starts watching shader files.
+    /// # Arguements
+    /// - `paths`: the files, e.g. the vertex and fragmentation shader
+    /// # Returns
+    /// A watcher, that doesn't need a reload until the files are modified
+    pub fn new(paths: &[&str]) -> Self {
+        let mut watcher = Self {
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            last_modified: None,
+        };
+        watcher.last_modified = watcher.newest_modified();
+        watcher
+    }
+
+    /// Checks if any file was modified since the last check.
+    /// # Returns
+    /// If the shaders should be reloaded (see `ShaderProgram::reload_from_files`)
+    /// # Note
+    /// Files that can't be read (e.g. while an editor is saving them) are ignored.
+    pub fn needs_reload(&mut self) -> bool {
+        let modified = self.newest_modified();
+        if modified > self.last_modified {
+            self.last_modified = modified;
+            return true;
+        }
+        false
+    }
+
+    /// Gets the newest modification time of the files.
+    fn newest_modified(&self) -> Option<SystemTime> {
+        self.paths
+            .iter()
+            .filter_map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+            .max()
+    }
+}
+
+/// GL_INVALID_INDEX, returned when a uniform block doesn't exist.
+const INVALID_INDEX: GLuint = GLuint::MAX;
+
+/// GL_COMPUTE_SHADER, missing from GL 3.3.
+const GL_COMPUTE_SHADER: GLenum = 0x91B9;
+/// GL_ALL_BARRIER_BITS, used with `ComputeProgram::memory_barrier` to wait on every access.
+pub const GL_ALL_BARRIER_BITS: GLbitfield = 0xFFFF_FFFF;
+/// GL_SHADER_STORAGE_BARRIER_BIT, used with `ComputeProgram::memory_barrier` to wait on shader
+/// storage buffer writes.
+pub const GL_SHADER_STORAGE_BARRIER_BIT: GLbitfield = 0x2000;
+
+/// Gets the version of the current GL context.
+/// # Returns
+/// A tuple of (major, minor)
+pub fn gl_version() -> (i32, i32) {
+    let (mut major, mut minor) = (0, 0);
+    unsafe {
+        glGetIntegerv(GL_MAJOR_VERSION, &mut major);
+        glGetIntegerv(GL_MINOR_VERSION, &mut minor);
+    }
+    (major, minor)
+}
+
+type DispatchComputeFn = unsafe extern "system" fn(GLuint, GLuint, GLuint);
+type MemoryBarrierFn = unsafe extern "system" fn(GLbitfield);
+
+/// A program built from a single compute shader, used to run work on the GPU.
+/// # Note
+/// - Requires GL 4.3, since the engine is loaded as GL 3.3 the functions for compute are loaded
+///   seperately.
+/// - Derefs to `ShaderProgram`, so the uniform setters can be used.
+pub struct ComputeProgram {
+    program: ShaderProgram,
+    dispatch_compute: DispatchComputeFn,
+    memory_barrier: MemoryBarrierFn,
+}
+impl ComputeProgram {
+    /// Creates a new program from the compute shader source code.
+    /// # Arguements
+    /// - `source`: the compute shader source code
+    /// - `get_proc_address`: loads a GL function from it's null-terminated name (such as
+    ///   `GlWindow::get_proc_address`)
+    /// # Returns
+    /// Either:
+    /// - The compute program
+    /// - An error when the context is older than GL 4.3, the functions couldn't be loaded or
+    ///   when linking or compiling the shader.
+    pub fn new(
+        source: &str,
+        get_proc_address: impl Fn(*const u8) -> *const c_void,
+    ) -> Result<Self, String> {
+        let (major, minor) = gl_version();
+        if (major, minor) < (4, 3) {
+            return Err(format!(
+                "compute shaders require GL 4.3, the context is GL {}.{}",
+                major, minor
+            ));
+        }
+
+        let dispatch_ptr = get_proc_address(c"glDispatchCompute".as_ptr().cast());
+        let barrier_ptr = get_proc_address(c"glMemoryBarrier".as_ptr().cast());
+        if dispatch_ptr.is_null() || barrier_ptr.is_null() {
+            return Err("couldn't load glDispatchCompute or glMemoryBarrier".to_string());
+        }
+
+        let program = ShaderProgram::new()?;
+        let shader = Shader::from_source(ShaderType::Compute, source)
+            .map_err(|e| format!("compute compile error: {}", e))?;
+        program.attach_shader(&shader);
+        program.link_program();
+        shader.delete();
+        if !program.link_success() {
+            return Err(format!("program link error: {}", program.info_log()));
         }
+
+        Ok(Self {
+            program,
+            // the pointers are non-null and were loaded by their GL names
+            dispatch_compute: unsafe {
+                mem::transmute::<*const c_void, DispatchComputeFn>(dispatch_ptr)
+            },
+            memory_barrier: unsafe {
+                mem::transmute::<*const c_void, MemoryBarrierFn>(barrier_ptr)
+            },
+        })
+    }
+
+    /// Runs the compute shader.
+    /// # Arguements
+    /// - `x`: the amount of work groups in the x-axis
+    /// - `y`: the amount of work groups in the y-axis
+    /// - `z`: the amount of work groups in the z-axis
+    /// # Note
+    /// Uses the program, the previous program is not restored.
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        self.program.use_program();
+        unsafe { (self.dispatch_compute)(x, y, z) };
+    }
+
+    /// Waits for the writes of the compute shader to be visible.
+    /// # Arguements
+    /// - `barriers`: the accesses that wait, such as `GL_SHADER_STORAGE_BARRIER_BIT` or
+    ///   `GL_ALL_BARRIER_BITS`
+    pub fn memory_barrier(&self, barriers: GLbitfield) {
+        unsafe { (self.memory_barrier)(barriers) };
+    }
+}
+
+impl Deref for ComputeProgram {
+    type Target = ShaderProgram;
+
+    fn deref(&self) -> &Self::Target {
+        &self.program
     }
 }
 
@@ -353,6 +786,148 @@ pub fn polygon_mode(mode: PolygonMode) {
     unsafe { glPolygonMode(GL_FRONT_AND_BACK, mode as GLenum) };
 }
 
+/// Which faces are culled (not drawn), see `cull_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CullMode {
+    /// GL_NONE: disables face culling
+    #[default]
+    None = 0,
+    /// GL_BACK
+    Back = GL_BACK as isize,
+    /// GL_FRONT
+    Front = GL_FRONT as isize,
+}
+
+/// Sets the `CullMode`.
+/// # Arguements
+/// - `mode`: the faces being culled, `CullMode::None` disables culling
+pub fn cull_mode(mode: CullMode) {
+    unsafe {
+        if mode == CullMode::None {
+            glDisable(GL_CULL_FACE);
+        } else {
+            glEnable(GL_CULL_FACE);
+            glCullFace(mode as GLenum);
+        }
+    }
+}
+
+/// The winding order of a front face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrontFace {
+    /// GL_CCW: counter-clockwise, the GL default
+    #[default]
+    Ccw = GL_CCW as isize,
+    /// GL_CW: clockwise
+    Cw = GL_CW as isize,
+}
+
+/// Sets the `FrontFace`.
+/// # Arguements
+/// - `face`: the winding order of front faces
+pub fn front_face(face: FrontFace) {
+    unsafe { glFrontFace(face as GLenum) };
+}
+
+/// A factor of the source or destination color, used in blending (see `blend_func`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendFactor {
+    /// GL_ZERO
+    Zero = GL_ZERO as isize,
+    /// GL_ONE
+    One = GL_ONE as isize,
+    /// GL_SRC_COLOR
+    SrcColor = GL_SRC_COLOR as isize,
+    /// GL_ONE_MINUS_SRC_COLOR
+    OneMinusSrcColor = GL_ONE_MINUS_SRC_COLOR as isize,
+    /// GL_SRC_ALPHA
+    SrcAlpha = GL_SRC_ALPHA as isize,
+    /// GL_ONE_MINUS_SRC_ALPHA
+    OneMinusSrcAlpha = GL_ONE_MINUS_SRC_ALPHA as isize,
+    /// GL_DST_ALPHA
+    DstAlpha = GL_DST_ALPHA as isize,
+    /// GL_ONE_MINUS_DST_ALPHA
+    OneMinusDstAlpha = GL_ONE_MINUS_DST_ALPHA as isize,
+}
+
+/// Enables or disables blending.
+/// # Arguements
+/// - `enabled`: if fragments are blended with the color buffer
+pub fn blend(enabled: bool) {
+    unsafe {
+        if enabled {
+            glEnable(GL_BLEND);
+        } else {
+            glDisable(GL_BLEND);
+        }
+    }
+}
+
+/// Sets how fragments are blended with the color buffer.
+/// # Arguements
+/// - `src`: the factor of the fragment's color
+/// - `dst`: the factor of the color buffer's color
+pub fn blend_func(src: BlendFactor, dst: BlendFactor) {
+    unsafe { glBlendFunc(src as GLenum, dst as GLenum) };
+}
+
+/// The comparison used in depth testing, a fragment passes if it's depth compares to the stored
+/// depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthFunc {
+    /// GL_NEVER
+    Never = GL_NEVER as isize,
+    /// GL_LESS
+    Less = GL_LESS as isize,
+    /// GL_EQUAL
+    Equal = GL_EQUAL as isize,
+    /// GL_LEQUAL
+    LessEqual = GL_LEQUAL as isize,
+    /// GL_GREATER
+    Greater = GL_GREATER as isize,
+    /// GL_NOTEQUAL
+    NotEqual = GL_NOTEQUAL as isize,
+    /// GL_GEQUAL
+    GreaterEqual = GL_GEQUAL as isize,
+    /// GL_ALWAYS
+    Always = GL_ALWAYS as isize,
+}
+
+/// Enables or disables depth testing.
+/// # Arguements
+/// - `enabled`: if fragments are tested against the depth buffer
+pub fn depth_test(enabled: bool) {
+    unsafe {
+        if enabled {
+            glEnable(GL_DEPTH_TEST);
+        } else {
+            glDisable(GL_DEPTH_TEST);
+        }
+    }
+}
+
+/// Sets the `DepthFunc`.
+/// # Arguements
+/// - `func`: the depth comparison
+pub fn depth_func(func: DepthFunc) {
+    unsafe { glDepthFunc(func as GLenum) };
+}
+
+/// Sets if the depth buffer can be written to.
+/// # Arguements
+/// - `write`: if depth is written
+pub fn depth_mask(write: bool) {
+    unsafe { glDepthMask(if write { GL_TRUE } else { GL_FALSE }) };
+}
+
+/// Sets if the color buffer can be written to.
+/// # Arguements
+/// - `write`: if the red, green, blue and alpha components are written
+pub fn color_mask(write: bool) {
+    let write = if write { GL_TRUE } else { GL_FALSE };
+    unsafe { glColorMask(write, write, write, write) };
+}
+
 /// The type of `Buffer` object.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BufferType {
@@ -360,6 +935,8 @@ pub enum BufferType {
     Array = GL_ARRAY_BUFFER as isize,
     /// GL_ELEMENT_ARRAY_BUFFER
     ElementArray = GL_ELEMENT_ARRAY_BUFFER as isize,
+    /// GL_UNIFORM_BUFFER
+    Uniform = GL_UNIFORM_BUFFER as isize,
 }
 
 /// The buffer object used in GL rendering.
@@ -368,14 +945,20 @@ impl Buffer {
     /// Creates a new buffer object
     /// # Returns
     /// Either:
-    /// - `None` when creation was not successful,
-    /// - A new buffer object
-    pub fn new() -> Option<Self> {
+    /// - A new buffer object,
+    /// - An error message, usually when there is no current GL context.
+    pub fn new() -> Result<Self, String> {
         let mut vbo = 0;
         unsafe {
             glGenBuffers(1, &mut vbo);
         }
-        if vbo != 0 { Some(Self(vbo)) } else { None }
+        let vbo = Self(vbo);
+        check_gl_error("couldn't make a buffer")?;
+        if vbo.0 != 0 {
+            Ok(vbo)
+        } else {
+            Err(no_object_error("buffer"))
+        }
     }
 
     /// Binds the buffer
@@ -393,6 +976,208 @@ impl Buffer {
     }
 }
 
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        if self.0 != 0 && has_gl_context() {
+            unsafe { glDeleteBuffers(1, &self.0) };
+        }
+    }
+}
+
+/// A buffer of uniform values, shared between every program that binds it's block to the same
+/// binding point (see `ShaderProgram::bind_uniform_block`).
+///
+/// Used for values that are the same for every shader, like the camera's matrices, so they're
+/// uploaded once instead of per program.
+/// # Note
+/// The data must follow the `std140` layout of the block (`layout(std140) uniform Block`):
+/// - `float`, `int` and `bool` are aligned to 4 bytes.
+/// - `vec2` is aligned to 8 bytes, `vec3` and `vec4` to 16 bytes (a `vec3` takes 12 bytes, but
+///   the next member starts at the next 16 byte boundary unless it's a scalar).
+/// - `mat4` is four `vec4` columns (64 bytes), a `mat3` is three columns padded to `vec4`s (48
+///   bytes).
+/// - Array elements are padded to 16 bytes each.
+///
+/// e.g. `{ mat4 view; mat4 projection; }` is the two matrices' bytes back to back (128 bytes).
+pub struct UniformBuffer {
+    buffer: Buffer,
+    size: usize,
+}
+impl UniformBuffer {
+    /// Creates a new uniform buffer, with uninitilised data.
+    /// # Arguements
+    /// - `size`: the size of the block in bytes
+    /// # Returns
+    /// Either:
+    /// - A new uniform buffer,
+    /// - An error message, usually when there is no current GL context.
+    pub fn new(size: usize) -> Result<Self, String> {
+        let buffer = Buffer::new()?;
+        buffer.bind(BufferType::Uniform);
+        buffer_reserve(BufferType::Uniform, size, GL_DYNAMIC_DRAW);
+        Buffer::clear_binding(BufferType::Uniform);
+        check_gl_error("couldn't allocate a uniform buffer")?;
+
+        Ok(Self { buffer, size })
+    }
+
+    /// Gets the size of the buffer.
+    /// # Returns
+    /// The size in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Binds the buffer to a uniform binding point, so blocks bound to the point read it.
+    /// # Arguements
+    /// - `index`: the binding point, less than `GL_MAX_UNIFORM_BUFFER_BINDINGS` (at least 36)
+    pub fn bind_to_point(&self, index: GLuint) {
+        unsafe { glBindBufferBase(GL_UNIFORM_BUFFER, index, self.buffer.0) };
+    }
+
+    /// Updates the start of the buffer.
+    /// # Arguements
+    /// - `data`: the bytes, in the `std140` layout
+    /// # Note
+    /// The data must fit in the buffer's `size`, otherwise GL raises `GL_INVALID_VALUE` and
+    /// nothing is updated.
+    pub fn update(&self, data: &[u8]) {
+        self.buffer.bind(BufferType::Uniform);
+        buffer_sub_data(BufferType::Uniform, 0, data);
+        Buffer::clear_binding(BufferType::Uniform);
+    }
+}
+
+/// The type of each component of a vertex attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    /// GL_FLOAT: a 32-bit float, read as a `float`
+    Float = GL_FLOAT as isize,
+    /// GL_INT: a 32-bit signed integer, read as an `int`
+    Int = GL_INT as isize,
+    /// GL_UNSIGNED_INT: a 32-bit unsigned integer, read as an `uint`
+    UnsignedInt = GL_UNSIGNED_INT as isize,
+    /// GL_UNSIGNED_BYTE: an 8-bit unsigned integer, read as a normalised `float` (0.0 to 1.0)
+    UnsignedByte = GL_UNSIGNED_BYTE as isize,
+}
+impl AttributeType {
+    /// Gets the size of a component.
+    /// # Returns
+    /// The size in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Float | Self::Int | Self::UnsignedInt => 4,
+            Self::UnsignedByte => 1,
+        }
+    }
+}
+
+/// An attribute of a vertex, inside of a `VertexLayout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertexAttribute {
+    /// The location in the vertex shader
+    pub location: GLuint,
+    /// The amount of components (1 to 4)
+    pub size: GLint,
+    /// The type of each component
+    pub ty: AttributeType,
+    /// The offset from the start of the vertex, in bytes
+    pub offset: usize,
+}
+
+/// The layout of an interleaved vertex buffer, the offsets and stride are computed from the
+/// attributes in the order they are pushed.
+/// # Example
+/// ```
+/// let layout = VertexLayout::new()
+///     .push(0, 3, AttributeType::Float)
+///     .push(1, 2, AttributeType::Float);
+/// assert_eq!(layout.stride(), 5 * size_of::<f32>());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>,
+    stride: usize,
+}
+impl VertexLayout {
+    /// Creates an empty layout.
+    /// # Returns
+    /// A `VertexLayout`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an attribute, after the previous attributes.
+    /// # Arguements
+    /// - `location`: the location in the vertex shader
+    /// - `size`: the amount of components (1 to 4)
+    /// - `ty`: the type of each component
+    /// # Returns
+    /// The layout
+    pub fn push(mut self, location: GLuint, size: GLint, ty: AttributeType) -> Self {
+        self.attributes.push(VertexAttribute {
+            location,
+            size,
+            ty,
+            offset: self.stride,
+        });
+        self.stride += size as usize * ty.size();
+        self
+    }
+
+    /// Gets the size of a vertex.
+    /// # Returns
+    /// The stride in bytes
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Gets the attributes of the layout.
+    /// # Returns
+    /// The attributes, in the order they were pushed
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Sets and enables the attribute pointers.
+    /// # Note
+    /// The VAO and array buffer need to be bound first.
+    pub fn apply(&self) {
+        let stride: GLsizei = self.stride.try_into().unwrap();
+        for attribute in self.attributes.iter() {
+            let offset = attribute.offset as *const c_void;
+            unsafe {
+                match attribute.ty {
+                    AttributeType::Float => glVertexAttribPointer(
+                        attribute.location,
+                        attribute.size,
+                        attribute.ty as GLenum,
+                        GL_FALSE,
+                        stride,
+                        offset,
+                    ),
+                    AttributeType::UnsignedByte => glVertexAttribPointer(
+                        attribute.location,
+                        attribute.size,
+                        attribute.ty as GLenum,
+                        GL_TRUE,
+                        stride,
+                        offset,
+                    ),
+                    AttributeType::Int | AttributeType::UnsignedInt => glVertexAttribIPointer(
+                        attribute.location,
+                        attribute.size,
+                        attribute.ty as GLenum,
+                        stride,
+                        offset,
+                    ),
+                }
+                glEnableVertexAttribArray(attribute.location);
+            }
+        }
+    }
+}
+
 /// Sets data inside a buffer
 /// # Arguements
 /// - `ty`: the type of buffer
@@ -409,6 +1194,219 @@ pub fn buffer_data(ty: BufferType, data: &[u8], usage: GLenum) {
     }
 }
 
+/// Allocates the bound buffer, without setting any data.
+/// # Arguements
+/// - `ty`: the type of buffer
+/// - `size`: the size in bytes
+/// - `usage`: How the buffer will be modified
+/// # Note
+/// Used with `buffer_sub_data`, to allocate once and update the data every frame.
+pub fn buffer_reserve(ty: BufferType, size: usize, usage: GLenum) {
+    unsafe {
+        glBufferData(
+            ty as GLenum,
+            size.try_into().unwrap(),
+            std::ptr::null(),
+            usage,
+        );
+    }
+}
+
+/// Updates a region of the bound buffer, without reallocating it.
+/// # Arguements
+/// - `ty`: the type of buffer
+/// - `offset`: the start of the region in bytes
+/// - `data`: a byte array
+/// # Note
+/// The region must fit in the size given by `buffer_reserve` or `buffer_data`, otherwise GL
+/// raises `GL_INVALID_VALUE` and nothing is updated.
+pub fn buffer_sub_data(ty: BufferType, offset: usize, data: &[u8]) {
+    unsafe {
+        glBufferSubData(
+            ty as GLenum,
+            offset.try_into().unwrap(),
+            data.len().try_into().unwrap(),
+            data.as_ptr().cast(),
+        );
+    }
+}
+
+/// The pixel format of a `Framebuffer`'s color attachment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorFormat {
+    /// 8-bit unsigned normalised channels, for regular rendering
+    #[default]
+    Rgba8 = GL_RGBA8 as isize,
+    /// 16-bit floating-point channels, for HDR rendering
+    Rgba16F = GL_RGBA16F as isize,
+    /// 32-bit floating-point channels, for HDR rendering with higher precision
+    Rgba32F = GL_RGBA32F as isize,
+}
+impl ColorFormat {
+    /// Gets the type of the channels, used when allocating the texture.
+    /// # Returns
+    /// The GL type of the channels
+    pub fn pixel_type(&self) -> GLenum {
+        match self {
+            Self::Rgba8 => GL_UNSIGNED_BYTE,
+            Self::Rgba16F => GL_HALF_FLOAT,
+            Self::Rgba32F => GL_FLOAT,
+        }
+    }
+
+    /// Checks if the format can store values outside of 0.0 and 1.0.
+    /// # Returns
+    /// If the format is floating-point
+    pub fn is_hdr(&self) -> bool {
+        !matches!(self, Self::Rgba8)
+    }
+}
+
+/// A framebuffer object that is rendered into, instead of the window.
+/// Has a color texture and a depth-stencil renderbuffer.
+pub struct Framebuffer {
+    /// The framebuffer object
+    pub id: GLuint,
+    /// The texture of the color attachment
+    pub color_texture: GLuint,
+    /// The renderbuffer of the depth-stencil attachment
+    pub depth_renderbuffer: GLuint,
+    /// The width in pixels
+    pub width: i32,
+    /// The height in pixels
+    pub height: i32,
+    /// The pixel format of `color_texture`
+    pub format: ColorFormat,
+}
+impl Framebuffer {
+    /// Creates a new framebuffer.
+    /// # Arguements
+    /// - `width`: the width in pixels
+    /// - `height`: the height in pixels
+    /// - `format`: the pixel format of the color attachment
+    /// # Returns
+    /// Either:
+    /// - A complete framebuffer,
+    /// - An error message when the size is invalid, or the format is unsupported by the driver
+    /// # Note
+    /// The previous framebuffer binding is reset to the window.
+    pub fn new(width: i32, height: i32, format: ColorFormat) -> Result<Self, String> {
+        if width <= 0 || height <= 0 {
+            return Err(format!("invalid framebuffer size {}x{}", width, height));
+        }
+
+        let mut framebuffer = Self {
+            id: 0,
+            color_texture: 0,
+            depth_renderbuffer: 0,
+            width,
+            height,
+            format,
+        };
+
+        let status = unsafe {
+            glGenFramebuffers(1, &mut framebuffer.id);
+            glBindFramebuffer(GL_FRAMEBUFFER, framebuffer.id);
+
+            glGenTextures(1, &mut framebuffer.color_texture);
+            glBindTexture(GL_TEXTURE_2D, framebuffer.color_texture);
+            glTexImage2D(
+                GL_TEXTURE_2D,
+                0,
+                format as GLint,
+                width,
+                height,
+                0,
+                GL_RGBA,
+                format.pixel_type(),
+                std::ptr::null(),
+            );
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR as GLint);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR as GLint);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_CLAMP_TO_EDGE as GLint);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_CLAMP_TO_EDGE as GLint);
+            glBindTexture(GL_TEXTURE_2D, 0);
+            glFramebufferTexture2D(
+                GL_FRAMEBUFFER,
+                GL_COLOR_ATTACHMENT0,
+                GL_TEXTURE_2D,
+                framebuffer.color_texture,
+                0,
+            );
+
+            glGenRenderbuffers(1, &mut framebuffer.depth_renderbuffer);
+            glBindRenderbuffer(GL_RENDERBUFFER, framebuffer.depth_renderbuffer);
+            glRenderbufferStorage(GL_RENDERBUFFER, GL_DEPTH24_STENCIL8, width, height);
+            glBindRenderbuffer(GL_RENDERBUFFER, 0);
+            glFramebufferRenderbuffer(
+                GL_FRAMEBUFFER,
+                GL_DEPTH_STENCIL_ATTACHMENT,
+                GL_RENDERBUFFER,
+                framebuffer.depth_renderbuffer,
+            );
+
+            let status = glCheckFramebufferStatus(GL_FRAMEBUFFER);
+            glBindFramebuffer(GL_FRAMEBUFFER, 0);
+            status
+        };
+
+        if status != GL_FRAMEBUFFER_COMPLETE {
+            framebuffer.delete();
+            return Err(format!(
+                "framebuffer with the format {:?} is incomplete (status: {:#x})",
+                format, status
+            ));
+        }
+        Ok(framebuffer)
+    }
+
+    /// Binds the framebuffer, so it is rendered into.
+    /// # Note
+    /// The viewport isn't changed, use `viewport` to get a matching one.
+    pub fn bind(&self) {
+        unsafe { glBindFramebuffer(GL_FRAMEBUFFER, self.id) }
+    }
+
+    /// Binds the window's framebuffer.
+    pub fn clear_binding() {
+        unsafe { glBindFramebuffer(GL_FRAMEBUFFER, 0) }
+    }
+
+    /// Gets a viewport covering the whole framebuffer.
+    /// # Returns
+    /// A viewport
+    pub fn viewport(&self) -> Viewport {
+        Viewport::new(0, 0, self.width, self.height)
+    }
+
+    /// Deletes the framebuffer and it's attachments.
+    /// # Note
+    /// The same as dropping the framebuffer.
+    pub fn delete(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        if !has_gl_context() {
+            return;
+        }
+
+        unsafe {
+            if self.id != 0 {
+                glDeleteFramebuffers(1, &self.id);
+            }
+            if self.color_texture != 0 {
+                glDeleteTextures(1, &self.color_texture);
+            }
+            if self.depth_renderbuffer != 0 {
+                glDeleteRenderbuffers(1, &self.depth_renderbuffer);
+            }
+        }
+    }
+}
+
 /// Sets the clear color.
 /// # Arguements
 /// - `color`: the color
@@ -417,3 +1415,117 @@ pub fn clear_color(color: Color3) {
         glClearColor(color.r, color.g, color.b, 1.0);
     }
 }
+
+/// Which buffers are cleared at the start of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClearFlags {
+    /// Clear the color buffer, to the clear color
+    pub color: bool,
+    /// Clear the depth buffer
+    pub depth: bool,
+    /// Clear the stencil buffer
+    pub stencil: bool,
+}
+impl ClearFlags {
+    /// Converts the flags into the mask used by `glClear`.
+    /// # Returns
+    /// The buffer bits
+    pub fn to_bits(self) -> GLbitfield {
+        let mut bits = 0;
+        if self.color {
+            bits |= GL_COLOR_BUFFER_BIT;
+        }
+        if self.depth {
+            bits |= GL_DEPTH_BUFFER_BIT;
+        }
+        if self.stencil {
+            bits |= GL_STENCIL_BUFFER_BIT;
+        }
+        bits
+    }
+}
+impl Default for ClearFlags {
+    /// Clears the color and depth buffers.
+    fn default() -> Self {
+        Self {
+            color: true,
+            depth: true,
+            stencil: false,
+        }
+    }
+}
+
+/// A rectangle of the window that is rendered to, measured in pixels from the bottom-left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// The x-axis of the bottom-left corner
+    pub x: i32,
+    /// The y-axis of the bottom-left corner
+    pub y: i32,
+    /// The width of the viewport
+    pub width: i32,
+    /// The height of the viewport
+    pub height: i32,
+}
+impl Viewport {
+    /// Creates a new viewport.
+    /// # Arguements
+    /// - `x`: the x-axis of the bottom-left corner
+    /// - `y`: the y-axis of the bottom-left corner
+    /// - `width`: the width
+    /// - `height`: the height
+    /// # Returns
+    /// A viewport
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Gets the viewport currently used by GL.
+    /// # Returns
+    /// The current viewport
+    pub fn current() -> Self {
+        let mut rect = [0_i32; 4];
+        unsafe {
+            glGetIntegerv(GL_VIEWPORT, rect.as_mut_ptr());
+        }
+        Self::new(rect[0], rect[1], rect[2], rect[3])
+    }
+
+    /// Gets the aspect ratio of the viewport.
+    /// # Returns
+    /// The width divided by the height, or 1.0 if the height is 0
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.height == 0 {
+            return 1.0;
+        }
+        self.width as f32 / self.height as f32
+    }
+
+    /// Sets the GL viewport and scissor box to the viewport.
+    /// # Note
+    /// The scissor box is only used when `scissor_test` is enabled.
+    pub fn apply(&self) {
+        unsafe {
+            glViewport(self.x, self.y, self.width, self.height);
+            glScissor(self.x, self.y, self.width, self.height);
+        }
+    }
+}
+
+/// Enables or disables the scissor test.
+/// # Arguements
+/// - `enabled`: if rendering should be clipped to the scissor box
+pub fn scissor_test(enabled: bool) {
+    unsafe {
+        if enabled {
+            glEnable(GL_SCISSOR_TEST);
+        } else {
+            glDisable(GL_SCISSOR_TEST);
+        }
+    }
+}
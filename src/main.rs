@@ -6,19 +6,24 @@
 
 pub mod gl_helper;
 pub mod mesh;
+pub mod noise;
 pub mod texture;
 /// Contains common datatypes used inside the engine.
 pub mod datatypes {
     pub mod color;
+    pub mod frustum;
+    pub mod ray;
     pub mod vectors;
 }
 /// Contains types used in the entity heirarchry structure.
 pub mod entities {
     pub mod entity;
     pub mod entity_tree;
+    pub mod material;
     /// Contains all variants of entities
     pub mod types {
         pub mod camera_type;
+        pub mod fly_controller;
         pub mod game_type;
         pub mod io_service;
         pub mod part_type;
@@ -31,10 +36,11 @@ pub mod entities {
 }
 pub mod window;
 
-use beryllium::video::{CreateWinArgs, GlSwapInterval};
-use core::{convert::TryInto, mem::size_of};
+#[cfg(test)]
+use beryllium::events::{SDLK_a, SDLK_w};
+use beryllium::video::CreateWinArgs;
 use ogl33::*;
-use std::{cell::RefCell, ptr, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use crate::{
     datatypes::{color::Color3, vectors::Vector3},
@@ -43,10 +49,7 @@ use crate::{
         entity_tree::EntityTree,
         traits::object_3d::Object3D,
         types::{
-            camera_type::Camera,
-            game_type::{Game, GameGenre},
-            io_service::InputService,
-            part_type::Part,
+            camera_type::Camera, game_type::GameGenre, io_service::InputService, part_type::Part,
         },
     },
     gl_helper::*,
@@ -74,13 +77,35 @@ fn start_window() -> Window {
     };
 
     let mut win = Window::new(win_args).unwrap();
+    win.set_vsync_mode(VsyncMode::On);
+
+    let gl_window = &win.window;
+    unsafe {
+        load_gl_with(|f_name| gl_window.get_proc_address(f_name.cast()));
+    }
+
+    win.set_clear_color(Color3::new(0.2, 0.3, 0.3).unwrap());
+    win.init_objects(VERT_SHADER, FRAG_SHADER).unwrap();
+    win
+}
+
+#[cfg(test)]
+fn start_hidden_window() -> Window {
+    let win_args = CreateWinArgs {
+        title: WINDOW_TITLE,
+        width: 64,
+        height: 64,
+        allow_high_dpi: false,
+        borderless: true,
+        resizable: false,
+    };
+
+    let mut win = Window::new_hidden(win_args).unwrap();
     let gl_window = &win.window;
-    gl_window.set_swap_interval(GlSwapInterval::Vsync).unwrap();
     unsafe {
         load_gl_with(|f_name| gl_window.get_proc_address(f_name.cast()));
     }
 
-    clear_color(Color3::new(0.2, 0.3, 0.3).unwrap());
     win.init_objects(VERT_SHADER, FRAG_SHADER).unwrap();
     win
 }
@@ -92,15 +117,26 @@ fn create_tree() -> (Rc<RefCell<EntityTree>>, Rc<RefCell<Entity>>) {
     let tree_binding = tree_cell.clone();
     let mut tree_borrow = tree_binding.borrow_mut();
 
-    let game_type = Game::new(GameGenre::Adventure);
-    let head = tree_borrow.add_head(game_type);
+    let head = tree_borrow.add_head(GameGenre::Adventure);
 
     (tree_cell, head)
 }
 
+/// Adds the services required by `Window::render_loop`.
+fn add_services(entity_tree: &mut EntityTree, head: &mut Entity) {
+    let _ = entity_tree.add_entity_with_parent(
+        "InputService",
+        EntityType::InputService(InputService::default()),
+        head,
+    );
+}
+
+/// Passing this argument starts with an empty tree, instead of the demo scene
+const NO_DEMO_FLAG: &str = "--no-demo";
+
 fn init_test_tree(entity_tree: Rc<RefCell<EntityTree>>, head: Rc<RefCell<Entity>>) {
     let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
-    let bitmap = Texture::new(include_bytes!("../assets/awesomeface.png").to_vec());
+    let bitmap = Texture::new(include_bytes!("../assets/awesomeface.png").to_vec()).unwrap();
 
     let mut tree = entity_tree.borrow_mut();
 
@@ -110,9 +146,10 @@ fn init_test_tree(entity_tree: Rc<RefCell<EntityTree>>, head: Rc<RefCell<Entity>
 
     drop(head);
 
-    let mut camera_type = Camera::new(90.0, 0.1, 100.0);
+    let mut camera_type = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+    // in front of the plane's face (+Z), looking down -Z
     camera_type.set_rotation(Vector3::new(0.0, 10.0, 0.0));
-    camera_type.set_position(Vector3::forward() * -1.0);
+    camera_type.set_position(Vector3::new(0.0, 0.0, 1.0));
 
     let _ = tree.add_main_camera(camera_type).unwrap();
 
@@ -123,32 +160,7 @@ fn init_test_tree(entity_tree: Rc<RefCell<EntityTree>>, head: Rc<RefCell<Entity>
         .add_entity_with_parent("part-entity", EntityType::Part(part_type), &mut head_borrow)
         .unwrap();
 
-    let _ = tree.add_entity_with_parent(
-        "InputService",
-        EntityType::InputService(InputService::default()),
-        &mut head_borrow,
-    );
-}
-
-fn enable_vertex_arrays() {
-    unsafe {
-        let vertex_data_size = size_of::<VertexDataInternal>().try_into().unwrap();
-
-        // position
-        glVertexAttribPointer(0, 3, GL_FLOAT, GL_FALSE, vertex_data_size, ptr::null());
-        glEnableVertexAttribArray(0);
-
-        // texture
-        glVertexAttribPointer(
-            1,
-            2,
-            GL_FLOAT,
-            GL_FALSE,
-            vertex_data_size,
-            size_of::<[f32; 3]>() as *const _,
-        );
-        glEnableVertexAttribArray(1);
-    }
+    add_services(&mut tree, &mut head_borrow);
 }
 
 /// main function
@@ -156,28 +168,77 @@ fn main() {
     let (tree_cell, head) = create_tree();
 
     let win = start_window();
-    init_test_tree(tree_cell.clone(), head);
-
-    win.shader_program.use_program();
-
-    enable_vertex_arrays();
+    if std::env::args().any(|arg| arg == NO_DEMO_FLAG) {
+        add_services(&mut tree_cell.borrow_mut(), &mut head.borrow_mut());
+    } else {
+        init_test_tree(tree_cell.clone(), head);
+    }
 
     polygon_mode(gl_helper::PolygonMode::Fill);
     win.render_loop(tree_cell);
-    win.shader_program.delete();
+    win.delete();
 }
 
 // Test Section
 
+#[cfg(test)]
+fn solid_bmp(width: u32, height: u32, color: (u8, u8, u8)) -> Vec<u8> {
+    let row_size = (width * 3).div_ceil(4) * 4;
+    let pixel_size = row_size * height;
+
+    let mut data = Vec::with_capacity((54 + pixel_size) as usize);
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(54 + pixel_size).to_le_bytes());
+    data.extend_from_slice(&[0; 4]); // reserved
+    data.extend_from_slice(&54_u32.to_le_bytes()); // pixel offset
+    data.extend_from_slice(&40_u32.to_le_bytes()); // header size
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&1_u16.to_le_bytes()); // planes
+    data.extend_from_slice(&24_u16.to_le_bytes()); // bits per pixel
+    data.extend_from_slice(&[0; 24]); // compression, size, resolution and palette
+
+    for _ in 0..height {
+        for _ in 0..width {
+            data.extend_from_slice(&[color.2, color.1, color.0]);
+        }
+        data.resize(data.len() + (row_size - width * 3) as usize, 0);
+    }
+    data
+}
+
+/// Makes a 32 bit BMP of a single color, with an alpha channel.
+#[cfg(test)]
+fn translucent_bmp(width: u32, height: u32, color: (u8, u8, u8, u8)) -> Vec<u8> {
+    let pixel_size = width * 4 * height;
+
+    let mut data = Vec::with_capacity((54 + pixel_size) as usize);
+    data.extend_from_slice(b"BM");
+    data.extend_from_slice(&(54 + pixel_size).to_le_bytes());
+    data.extend_from_slice(&[0; 4]); // reserved
+    data.extend_from_slice(&54_u32.to_le_bytes()); // pixel offset
+    data.extend_from_slice(&40_u32.to_le_bytes()); // header size
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&1_u16.to_le_bytes()); // planes
+    data.extend_from_slice(&32_u16.to_le_bytes()); // bits per pixel
+    data.extend_from_slice(&[0; 24]); // compression, size, resolution and palette
+
+    for _ in 0..width * height {
+        data.extend_from_slice(&[color.2, color.1, color.0, color.3]);
+    }
+    data
+}
+
 #[test]
 fn test_to_hsv_color_pure() {
     // pure colors
-    let pure_white = Color3::from_hsv(0, 0.0, 1.0).unwrap();
-    let pure_black = Color3::from_hsv(0, 0.0, 0.0).unwrap();
+    let pure_white = Color3::from_hsv(0.0, 0.0, 1.0);
+    let pure_black = Color3::from_hsv(0.0, 0.0, 0.0);
 
-    let pure_red = Color3::from_hsv(0, 1.0, 1.0).unwrap();
-    let pure_green = Color3::from_hsv(120, 1.0, 1.0).unwrap();
-    let pure_blue = Color3::from_hsv(240, 1.0, 1.0).unwrap();
+    let pure_red = Color3::from_hsv(0.0, 1.0, 1.0);
+    let pure_green = Color3::from_hsv(120.0, 1.0, 1.0);
+    let pure_blue = Color3::from_hsv(240.0, 1.0, 1.0);
 
     assert_eq!(pure_white, Color3::white());
     assert_eq!(pure_black, Color3::black());
@@ -213,3 +274,3061 @@ fn test_add_entity() {
     assert_eq!(head.children_id[0], test_entity.get_uuid());
     assert_eq!(head.get_uuid(), test_entity.parent_id.unwrap());
 }
+
+#[test]
+fn test_entity_clone_shallow() {
+    let (tree_cell, head_binding) = create_tree();
+
+    let mut head = head_binding.borrow_mut();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mut part_type = Part::new(&Mesh::default());
+    part_type.set_position(Vector3::new(1.0, 2.0, 3.0));
+
+    let template_binding = tree
+        .add_entity_with_parent("template", EntityType::Part(part_type), &mut head)
+        .unwrap();
+    let template = template_binding.borrow();
+    let clone = template.clone_shallow();
+
+    assert_ne!(clone.get_uuid(), template.get_uuid());
+    assert_eq!(clone.parent_id, None);
+    assert!(clone.children_id.is_empty());
+    assert_eq!(clone.get_name(), "template");
+
+    let EntityType::Part(cloned_part) = clone.get_type() else {
+        panic!("clone isn't a part");
+    };
+    assert_eq!(cloned_part.get_position(), Vector3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn test_mesh_wireframe() {
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let wireframe = mesh.to_wireframe();
+
+    // 2 triangles sharing a diagonal has 5 unique edges
+    assert_eq!(wireframe.topology, MeshTopology::Lines);
+    assert_eq!(wireframe.indices.len(), 5 * 2);
+}
+
+#[test]
+fn test_input_service_reset() {
+    use beryllium::events::{Event, KMOD_LSHIFT};
+    use datatypes::vectors::Vector2;
+
+    let mut input_service = InputService::default();
+    input_service.provide_input(SDLK_w, true);
+    input_service.mark_cleanup();
+    input_service.handle_event(&Event::Key {
+        win_id: 0,
+        pressed: true,
+        repeat: 0,
+        scancode: Default::default(),
+        keycode: SDLK_a,
+        modifiers: KMOD_LSHIFT,
+    });
+    input_service.handle_event(&Event::MouseWheel {
+        win_id: 0,
+        mouse_id: 0,
+        x: 0,
+        y: 2,
+    });
+    assert_eq!(input_service.get_modifiers(), KMOD_LSHIFT);
+    assert_eq!(input_service.get_scroll_delta(), Vector2::new(0.0, 2.0));
+
+    assert!(input_service.handle_event(&Event::WindowLostKeyboardFocus { win_id: 0 }));
+
+    assert!(input_service.get_keys_active().is_empty());
+    assert_eq!(input_service.get_modifiers(), Default::default());
+    assert_eq!(input_service.get_scroll_delta(), Vector2::zero());
+    for keycode in [SDLK_w, SDLK_a] {
+        assert!(!input_service.is_key_pressed(keycode));
+        assert!(!input_service.is_key_down(keycode));
+        assert!(!input_service.is_key_released(keycode));
+        assert!(!input_service.is_key_active(keycode));
+    }
+}
+
+#[test]
+fn test_bake_texture_to_vertex_colors() {
+    let texture = Texture::new(solid_bmp(4, 4, (255, 0, 0))).unwrap();
+    let mut mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    mesh.vertices[0].set_tex_coord(datatypes::vectors::Vector2::new(2.5, -1.25)); // outside of 0.0 to 1.0
+
+    mesh.bake_texture_to_vertex_colors(&texture);
+
+    for vertex in mesh.vertices.iter() {
+        assert_eq!(vertex.get_color(), Color3::red());
+    }
+}
+
+#[test]
+fn test_genre_defaults() {
+    use entities::types::game_type::{DEFAULT_NEAR_VIEW, Game};
+
+    let camera_of = |genre: Option<GameGenre>| {
+        let mut tree = EntityTree::default();
+        match genre {
+            Some(genre) => tree.add_head(genre),
+            None => tree.add_head_default(),
+        };
+        let camera = tree.add_default_main_camera().unwrap();
+        let EntityType::Camera(camera) = camera.borrow().get_type().clone() else {
+            panic!("main camera isn't a camera");
+        };
+        (camera.fov, camera.near_view, camera.far_view)
+    };
+
+    let action = camera_of(Some(GameGenre::Action));
+    let adventure = camera_of(Some(GameGenre::Adventure));
+    let default = camera_of(None);
+    let undefined = camera_of(Some(GameGenre::Undefined));
+
+    assert_eq!(action, (90.0_f32.to_radians(), DEFAULT_NEAR_VIEW, 250.0));
+    assert_eq!(
+        adventure,
+        (100.0_f32.to_radians(), DEFAULT_NEAR_VIEW, 100.0)
+    );
+    assert_eq!(undefined, (90.0_f32.to_radians(), DEFAULT_NEAR_VIEW, 100.0));
+    assert_eq!(default, action);
+    assert!(action.2 > adventure.2);
+    assert!(adventure.0 > action.0);
+
+    let game = Game::new(GameGenre::Action);
+    assert_eq!(game.default_camera().far_view, 250.0);
+}
+
+#[test]
+fn test_update_all() {
+    let (tree_cell, head) = create_tree();
+    let part = tree_cell
+        .borrow_mut()
+        .add_entity_with_parent(
+            "Part",
+            EntityType::Part(Part::default()),
+            &mut head.borrow_mut(),
+        )
+        .unwrap();
+    let frame_count = || {
+        let EntityType::Game(game) = head.borrow().get_type().clone() else {
+            panic!("head isn't a game");
+        };
+        (game.frame_count, game.elapsed_time)
+    };
+
+    let mut tree = tree_cell.borrow_mut();
+    assert_eq!(frame_count(), (0, 0.0));
+    tree.update_all(0.5);
+    assert_eq!(frame_count(), (1, 0.5));
+    assert!(!part.borrow().newly_created);
+    tree.update_all(0.25);
+    assert_eq!(frame_count(), (2, 0.75));
+
+    // borrowed entities are skipped
+    let head_borrow = head.borrow();
+    tree.update_all(0.25);
+    drop(head_borrow);
+    assert_eq!(frame_count(), (2, 0.75));
+}
+
+#[test]
+fn test_entity_tree_with_capacity() {
+    let tree = EntityTree::with_capacity(50_000, 10_000);
+
+    assert!(tree.entity_map.capacity() >= 50_000);
+    assert!(tree.parts.capacity() >= 10_000);
+    assert!(tree.entity_map.is_empty());
+}
+
+#[test]
+fn test_component_iter_map() {
+    let vector = Vector3::new(1.0, -2.0, 3.0);
+    for (i, comp) in vector.iter().enumerate() {
+        assert_eq!(comp, vector[i]);
+    }
+    assert_eq!(vector.iter().collect::<Vector3>(), vector);
+    assert_eq!(vector.map(|c| c * 2.0), Vector3::new(2.0, -4.0, 6.0));
+    assert_eq!(
+        vector.map(|c| c * 2.0),
+        vector.iter().map(|c| c * 2.0).collect::<Vector3>()
+    );
+    assert_eq!(
+        [1.0].into_iter().collect::<Vector3>(),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+
+    let color = Color3::new(0.25, 0.5, 0.75).unwrap();
+    for (i, comp) in color.iter().enumerate() {
+        assert_eq!(comp, color[i]);
+    }
+    assert_eq!(color.iter().collect::<Color3>(), color);
+    assert_eq!(color.map(|c| c * 2.0), Color3::new(0.5, 1.0, 1.0).unwrap());
+    assert_eq!(
+        color.map(|c| c * 2.0),
+        color.iter().map(|c| c * 2.0).collect::<Color3>()
+    );
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_render_with_depth_prepass() {
+    use gl_helper::Viewport;
+
+    let mut win = start_window();
+    assert!(win.init_depth_prepass("not a shader").is_err());
+    assert!(win.depth_shader_program.is_none());
+    win.init_depth_prepass(VERT_SHADER).unwrap();
+
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let mut part = Part::new(&mesh);
+    part.set_texture(Texture::new(solid_bmp(2, 2, (255, 255, 255))).unwrap());
+    part.set_position(Vector3::new(0.0, 0.0, -3.0)); // in front of the camera
+    let _ = tree
+        .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+        .unwrap();
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+
+    clear_color(Color3::black());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT) };
+    win.render_tree_with_prepass(&tree, &camera);
+
+    // the shading pass passes the equal depth test, so the part isn't left black
+    let viewport = Viewport::current();
+    let mut pixel = [0_u8; 4];
+    unsafe {
+        glReadPixels(
+            viewport.width / 2,
+            viewport.height / 2,
+            1,
+            1,
+            GL_RGBA,
+            GL_UNSIGNED_BYTE,
+            pixel.as_mut_ptr().cast(),
+        );
+    }
+    assert_ne!(pixel, [0, 0, 0, 255]);
+
+    let mut func = 0;
+    unsafe { glGetIntegerv(GL_DEPTH_FUNC, &mut func) };
+    assert_eq!(func as GLenum, GL_LESS);
+}
+
+#[test]
+fn test_compute_tangents_fallback() {
+    let coord = datatypes::vectors::Vector2::new(0.5, 0.5);
+    let mut mesh = Mesh::with_set_data(
+        vec![
+            VertexData::new(Vector3::new(0.0, 0.0, 0.0), coord),
+            VertexData::new(Vector3::new(1.0, 0.0, 0.0), coord),
+            VertexData::new(Vector3::new(0.0, 1.0, 0.0), coord),
+        ],
+        vec![0, 1, 2],
+    );
+
+    // every vertex has the same texture coordinate, so the UV area is zero
+    let fallback_count = mesh.compute_tangents();
+
+    assert_eq!(fallback_count, 3);
+    for tangent in mesh.tangents.iter() {
+        assert!(tangent.iter().all(f32::is_finite));
+        assert!((tangent.get_magnitude() - 1.0).abs() < 1e-4);
+        assert!(tangent.dot(Vector3::forward()).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_mesh_fullscreen_triangle() {
+    let triangle = Mesh::fullscreen_triangle();
+    assert_eq!(triangle.indices, vec![0, 1, 2]);
+    let [a, b, c] = [0, 1, 2].map(|i| triangle.vertices[i]);
+    let [pa, pb, pc] = [a, b, c].map(|v| v.get_position());
+    let [ta, tb, tc] = [a, b, c].map(|v| v.get_tex_coord());
+
+    // every corner of the screen ([-1, 1]²) is inside, at the matching corner of [0, 1]²
+    let area = (pb.x - pa.x) * (pc.y - pa.y) - (pc.x - pa.x) * (pb.y - pa.y);
+    for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+        let wb = ((x - pa.x) * (pc.y - pa.y) - (pc.x - pa.x) * (y - pa.y)) / area;
+        let wc = ((pb.x - pa.x) * (y - pa.y) - (x - pa.x) * (pb.y - pa.y)) / area;
+        let wa = 1.0 - wb - wc;
+        assert!(wa >= -1e-6 && wb >= -1e-6 && wc >= -1e-6, "({x}, {y})");
+
+        let u = ta.x * wa + tb.x * wb + tc.x * wc;
+        let v = ta.y * wa + tb.y * wb + tc.y * wc;
+        assert!((u - (x + 1.0) / 2.0).abs() < 1e-6, "({x}, {y})");
+        assert!((v - (y + 1.0) / 2.0).abs() < 1e-6, "({x}, {y})");
+    }
+}
+
+#[test]
+fn test_perlin_noise_seeded() {
+    let noise = noise::PerlinNoise::new(42);
+    let other_noise = noise::PerlinNoise::new(42);
+
+    for i in 0..256 {
+        let (x, y, z) = (i as f32 * 0.37, i as f32 * 0.11 - 20.0, i as f32 * 0.53);
+
+        let value_2d = noise.get_2d(x, y);
+        let value_3d = noise.get_3d(x, y, z);
+
+        assert_eq!(value_2d, other_noise.get_2d(x, y));
+        assert_eq!(value_3d, noise::perlin_3d(x, y, z, 42));
+        assert!((-1.0..=1.0).contains(&value_2d));
+        assert!((-1.0..=1.0).contains(&value_3d));
+    }
+}
+
+#[test]
+fn test_render_order_skips_hidden_parts() {
+    let (tree_cell, head_binding) = create_tree();
+
+    let mut head = head_binding.borrow_mut();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mesh = Mesh::default();
+    let mut hidden_part = Part::new(&mesh);
+    hidden_part.visable = false;
+
+    let visible_binding = tree
+        .add_entity_with_parent("visible", EntityType::Part(Part::new(&mesh)), &mut head)
+        .unwrap();
+    let hidden_binding = tree
+        .add_entity_with_parent("hidden", EntityType::Part(hidden_part), &mut head)
+        .unwrap();
+    let _ = tree
+        .add_entity_with_parent(
+            "hidden child",
+            EntityType::Part(Part::new(&mesh)),
+            &mut hidden_binding.borrow_mut(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        tree.render_order(),
+        vec![visible_binding.borrow().get_uuid()]
+    );
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_render_skips_hidden_parts() {
+    let win = start_window();
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let textured_part = || {
+        let mut part = Part::new(&mesh);
+        part.set_texture(Texture::new(solid_bmp(2, 2, (255, 255, 255))).unwrap());
+        part.set_position(Vector3::new(0.0, 0.0, -3.0));
+        part
+    };
+
+    let mut hidden_part = textured_part();
+    hidden_part.visable = false;
+    let hidden_binding = tree
+        .add_entity_with_parent(
+            "hidden",
+            EntityType::Part(hidden_part),
+            &mut head.borrow_mut(),
+        )
+        .unwrap();
+    let _ = tree
+        .add_entity_with_parent(
+            "hidden child",
+            EntityType::Part(textured_part()),
+            &mut hidden_binding.borrow_mut(),
+        )
+        .unwrap();
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+
+    win.reset_render_stats();
+    win.render_tree(&tree, &camera);
+    assert_eq!(win.render_stats().draw_calls, 0);
+
+    let _ = tree
+        .add_entity_with_parent(
+            "visible",
+            EntityType::Part(textured_part()),
+            &mut head.borrow_mut(),
+        )
+        .unwrap();
+    win.reset_render_stats();
+    win.render_tree(&tree, &camera);
+    assert_eq!(win.render_stats().draw_calls, 1);
+}
+
+#[test]
+fn test_visible_parts() {
+    let (tree_cell, head_binding) = create_tree();
+
+    let mut head = head_binding.borrow_mut();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mesh = Mesh::default();
+    let folder_binding = tree
+        .add_entity_with_parent(
+            "folder",
+            EntityType::Base(entities::entity::Base),
+            &mut head,
+        )
+        .unwrap();
+    let child_binding = tree
+        .add_entity_with_parent(
+            "child",
+            EntityType::Part(Part::new(&mesh)),
+            &mut folder_binding.borrow_mut(),
+        )
+        .unwrap();
+    let sibling_binding = tree
+        .add_entity_with_parent("sibling", EntityType::Part(Part::new(&mesh)), &mut head)
+        .unwrap();
+
+    let visible_ids = |tree: &EntityTree| -> Vec<_> {
+        tree.visible_parts()
+            .map(|entity| entity.borrow().get_uuid())
+            .collect()
+    };
+    let (child_id, sibling_id) = (
+        child_binding.borrow().get_uuid(),
+        sibling_binding.borrow().get_uuid(),
+    );
+    assert_eq!(visible_ids(&tree), vec![child_id, sibling_id]);
+
+    folder_binding.borrow_mut().set_visible(false);
+    assert!(!folder_binding.borrow().is_visible());
+    assert!(child_binding.borrow().is_visible());
+    assert!(!tree.is_visible(&child_binding.borrow()));
+    assert_eq!(visible_ids(&tree), vec![sibling_id]);
+
+    sibling_binding.borrow_mut().set_visible(false);
+    assert!(visible_ids(&tree).is_empty());
+    drop(head);
+    let loaded = EntityTree::from_scene_string(&tree.to_scene_string().unwrap()).unwrap();
+    assert!(visible_ids(&loaded).is_empty());
+
+    folder_binding.borrow_mut().set_visible(true);
+    assert_eq!(visible_ids(&tree), vec![child_id]);
+}
+
+#[test]
+fn test_print_tree() {
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let camera = tree.add_main_camera(Camera::default()).unwrap();
+    let level = tree
+        .add_entity_with_parent(
+            "Level",
+            EntityType::Base(entities::entity::Base),
+            &mut head.borrow_mut(),
+        )
+        .unwrap();
+    let floor = tree
+        .add_entity_with_parent(
+            "Floor",
+            EntityType::Part(Part::default()),
+            &mut level.borrow_mut(),
+        )
+        .unwrap();
+
+    let short_id = |entity: &Rc<RefCell<Entity>>| {
+        entity.borrow().get_uuid().simple().to_string()[..8].to_string()
+    };
+    let expected = format!(
+        "Game [{}] Game\n|-- Camera [{}] Camera (main camera)\n`-- Level [{}] Base\n    `-- Floor [{}] Part\n",
+        short_id(&head),
+        short_id(&camera),
+        short_id(&level),
+        short_id(&floor),
+    );
+    assert_eq!(tree.print_tree(), expected);
+
+    let floor_borrow = floor.borrow_mut();
+    assert!(tree.print_tree().ends_with(&format!(
+        "    `-- <borrowed> [{}]\n",
+        &floor_borrow.get_uuid().simple().to_string()[..8]
+    )));
+    drop(floor_borrow);
+
+    assert!(EntityTree::default().print_tree().is_empty());
+}
+
+#[test]
+fn test_get_siblings() {
+    let (tree_cell, head_binding) = create_tree();
+
+    let mut head = head_binding.borrow_mut();
+    let mut tree = tree_cell.borrow_mut();
+
+    let first_binding = tree
+        .add_entity_with_parent("first", EntityType::Part(Part::default()), &mut head)
+        .unwrap();
+    let second_binding = tree
+        .add_entity_with_parent("second", EntityType::Part(Part::default()), &mut head)
+        .unwrap();
+    let third_binding = tree
+        .add_entity_with_parent("third", EntityType::Part(Part::default()), &mut head)
+        .unwrap();
+    drop(head);
+
+    let first = first_binding.borrow();
+    let siblings = tree.get_siblings_id(&first);
+    assert_eq!(
+        siblings,
+        vec![
+            second_binding.borrow().get_uuid(),
+            third_binding.borrow().get_uuid()
+        ]
+    );
+    assert_eq!(tree.get_siblings(&first).len(), 2);
+
+    // a stale ID is skipped, instead of panicking
+    head_binding
+        .borrow_mut()
+        .children_id
+        .push(uuid::Uuid::new_v4());
+    assert_eq!(tree.get_siblings(&first).len(), 2);
+
+    let head = head_binding.borrow();
+    assert!(tree.get_siblings_id(&head).is_empty());
+}
+
+#[test]
+fn test_camera_fov_degrees() {
+    let mut camera = Camera::default();
+    camera.set_fov_degrees(90.0);
+
+    assert!((camera.fov_degrees() - 90.0).abs() < 1e-4);
+
+    let expected = ultraviolet::projection::perspective_gl(
+        std::f32::consts::FRAC_PI_2,
+        16.0 / 9.0,
+        camera.near_view,
+        camera.far_view,
+    );
+    let projection = camera.get_projection(16.0 / 9.0);
+    for (a, b) in expected.cols.iter().zip(projection.cols.iter()) {
+        assert!((*a - *b).mag() < 1e-5);
+    }
+
+    camera.set_fov_degrees(500.0);
+    assert!((camera.fov_degrees() - entities::types::camera_type::MAX_FOV_DEGREES).abs() < 1e-4);
+}
+
+#[test]
+fn test_mesh_simplify() {
+    use datatypes::vectors::Vector2;
+
+    const SIZE: u32 = 10;
+    let mut mesh = Mesh::default();
+    for z in 0..=SIZE {
+        for x in 0..=SIZE {
+            let (u, v) = (x as f32 / SIZE as f32, z as f32 / SIZE as f32);
+            mesh.add_vertex_data_pt(Vector3::new(u, 0.0, v), Vector2::new(u, v));
+        }
+    }
+    for z in 0..SIZE {
+        for x in 0..SIZE {
+            let i = z * (SIZE + 1) + x;
+            mesh.add_indices(&mut vec![
+                i,
+                i + SIZE + 1,
+                i + 1,
+                i + 1,
+                i + SIZE + 1,
+                i + SIZE + 2,
+            ]);
+        }
+    }
+    let original = mesh.indices.len() / 3;
+
+    let count = mesh.simplify(0.5);
+    assert!(count < original);
+    assert_eq!(mesh.indices.len(), count * 3);
+
+    for [a, b, c] in mesh.to_indices_tri() {
+        let vertex = |i: u32| mesh.vertices.get(i as usize).unwrap().get_position();
+        assert!(a != b && b != c && c != a);
+
+        // every triangle still faces up
+        let normal = (vertex(b) - vertex(a)).cross(vertex(c) - vertex(a));
+        assert!(normal.y > 0.0);
+    }
+}
+
+#[test]
+fn test_framebuffer_invalid_size() {
+    use gl_helper::{ColorFormat, Framebuffer};
+
+    // the size is checked before any GL call
+    let err = Framebuffer::new(0, 4, ColorFormat::Rgba8).err().unwrap();
+    assert_eq!(err, "invalid framebuffer size 0x4");
+    assert!(Framebuffer::new(4, -1, ColorFormat::Rgba16F).is_err());
+
+    assert!(!ColorFormat::Rgba8.is_hdr());
+    assert_eq!(ColorFormat::Rgba8.pixel_type(), GL_UNSIGNED_BYTE);
+    assert_eq!(ColorFormat::Rgba32F.pixel_type(), GL_FLOAT);
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_framebuffer_hdr() {
+    use gl_helper::{ColorFormat, Framebuffer, Viewport};
+
+    let _win = start_window();
+    let framebuffer = Framebuffer::new(16, 8, ColorFormat::Rgba16F).unwrap();
+    assert!(framebuffer.format.is_hdr());
+    assert_eq!(framebuffer.format.pixel_type(), GL_HALF_FLOAT);
+    assert_eq!(framebuffer.viewport(), Viewport::new(0, 0, 16, 8));
+
+    let mut internal_format = 0;
+    unsafe {
+        glBindTexture(GL_TEXTURE_2D, framebuffer.color_texture);
+        glGetTexLevelParameteriv(
+            GL_TEXTURE_2D,
+            0,
+            GL_TEXTURE_INTERNAL_FORMAT,
+            &mut internal_format,
+        );
+        glBindTexture(GL_TEXTURE_2D, 0);
+    }
+    assert_eq!(internal_format as GLenum, GL_RGBA16F);
+}
+
+#[test]
+fn test_vector3_arithmetic() {
+    let a = Vector3::new(1.0, -2.0, 3.5);
+    let b = Vector3::new(0.25, 4.0, -1.0);
+
+    assert_eq!(a + b, b + a);
+    assert_eq!(a * 2.0, a + a);
+    assert_eq!(a - a, Vector3::zero());
+    assert_eq!(-a + a, Vector3::zero());
+    assert_eq!(a * Vector3::one(), a);
+
+    let mut c = a;
+    c += b;
+    assert_eq!(c, a + b);
+    c -= b;
+    assert_eq!(c, a);
+    c *= 2.0;
+    assert_eq!(c, a * 2.0);
+
+    // float semantics, doesn't panic
+    let divided = a / 0.0;
+    assert!(divided.x.is_infinite());
+    assert!((Vector3::zero() / 0.0).x.is_nan());
+}
+
+#[test]
+fn test_vector3_products() {
+    assert_eq!(Vector3::right().cross(Vector3::up()), Vector3::forward());
+    assert_eq!(Vector3::right().dot(Vector3::up()), 0.0);
+
+    let v = Vector3::new(3.0, 0.0, 4.0);
+    assert_eq!(v.length(), 5.0);
+    assert_eq!(v.length_squared(), 25.0);
+    assert!((v.get_unit().length() - 1.0).abs() < 1e-6);
+
+    let unit = Vector3::zero().get_unit();
+    assert!(unit.iter().all(|c| !c.is_nan()));
+    assert_eq!(Vector3::new(1e-8, 0.0, 0.0).get_unit(), Vector3::zero());
+}
+
+#[test]
+fn test_vector3_basis_orthogonal() {
+    const BASIS: [Vector3; 3] = [Vector3::right(), Vector3::up(), Vector3::forward()];
+
+    for (i, a) in BASIS.iter().enumerate() {
+        assert_eq!(a.length(), 1.0);
+        for b in BASIS.iter().skip(i + 1) {
+            assert_eq!(a.dot(*b), 0.0);
+        }
+    }
+
+    assert_eq!(Vector3::up().cross(Vector3::forward()), Vector3::right());
+    assert_eq!(Vector3::forward().cross(Vector3::right()), Vector3::up());
+}
+
+#[test]
+fn test_vector3_lerp() {
+    let a = Vector3::new(1.0, 2.0, -4.0);
+    let b = Vector3::new(3.0, -2.0, 8.0);
+
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 0.5), (a + b) / 2.0);
+
+    assert_eq!(a.lerp(b, 2.0), b);
+    assert_eq!(a.lerp_unclamped(b, 2.0), Vector3::new(5.0, -6.0, 20.0));
+}
+
+#[test]
+fn test_approx_eq() {
+    use datatypes::vectors::{DEFAULT_EPSILON, Vector2};
+
+    // 0.1 + 0.2 rounds to exactly 0.3 as an f32, but 0.6 + 0.1 doesn't round to 0.7
+    let sum = 0.6_f32 + 0.1;
+    assert_ne!(sum, 0.7);
+
+    let computed = Vector3::new(sum, 1.0, -sum);
+    let expected = Vector3::new(0.7, 1.0, -0.7);
+    assert_ne!(computed, expected);
+    assert!(computed.approx_eq(expected, DEFAULT_EPSILON));
+    assert!(!computed.approx_eq(expected, 1e-9));
+    assert_approx_eq!(computed, expected);
+
+    assert!(Vector2::new(sum, 0.0).approx_eq(Vector2::new(0.7, 0.0), DEFAULT_EPSILON));
+    assert!(!Vector2::new(sum, 0.0).approx_eq(Vector2::new(0.7, 0.0), 1e-9));
+
+    let color = Color3::new(sum, 0.0, 0.5).unwrap();
+    let expected_color = Color3::new(0.7, 0.0, 0.5).unwrap();
+    assert!(color.approx_eq(expected_color, DEFAULT_EPSILON));
+    assert!(!color.approx_eq(expected_color, 1e-9));
+
+    // a larger epsilon can be given to the macro
+    assert_approx_eq!(Vector3::zero(), Vector3::new(0.01, 0.0, 0.0), 0.1);
+}
+
+#[test]
+fn test_vector3_clamp_min_max() {
+    let a = Vector3::new(1.0, -2.0, 3.0);
+    let b = Vector3::new(-1.0, 4.0, 3.0);
+
+    assert_eq!(a.min(b), Vector3::new(-1.0, -2.0, 3.0));
+    assert_eq!(a.max(b), Vector3::new(1.0, 4.0, 3.0));
+
+    let clamped = Vector3::new(5.0, -5.0, 0.0).clamp(-Vector3::one(), Vector3::one());
+    assert_eq!(clamped, Vector3::new(1.0, -1.0, 0.0));
+
+    // min > max on an axis doesn't panic, the bounds are used as-is
+    let swapped = Vector3::zero().clamp(Vector3::one(), -Vector3::one());
+    assert_eq!(swapped, -Vector3::one());
+}
+
+#[test]
+fn test_vector2_math() {
+    use datatypes::vectors::Vector2;
+
+    let a = Vector2::new(3.0, 4.0);
+    let b = Vector2::new(-1.0, 0.5);
+
+    assert_eq!(a + b, b + a);
+    assert_eq!(a * 2.0, a + a);
+    assert_eq!(a - b, Vector2::new(4.0, 3.5));
+    assert_eq!(a.dot(b), -1.0);
+    assert_eq!(a.length(), 5.0);
+    assert_eq!(Vector2::zero().get_unit(), Vector2::zero());
+}
+
+#[test]
+fn test_mesh_load_texcoords() {
+    use datatypes::vectors::Vector2;
+
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let tex_coords: Vec<Vector2> = mesh.vertices.iter().map(|v| v.get_tex_coord()).collect();
+
+    assert_eq!(
+        tex_coords,
+        vec![
+            Vector2::new(1.0, 1.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 1.0),
+        ]
+    );
+}
+
+#[test]
+fn test_vector3_distance_angle() {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(4.0, 6.0, 3.0);
+    assert_eq!(a.distance(b), 5.0);
+    assert_eq!(a.distance_squared(b), 25.0);
+
+    assert!((Vector3::right().angle_between(Vector3::up()) - FRAC_PI_2).abs() < 1e-6);
+    assert!((Vector3::right().angle_between(-Vector3::right()) - PI).abs() < 1e-6);
+    assert_eq!(Vector3::right().angle_between(Vector3::zero()), 0.0);
+}
+
+#[test]
+fn test_color3_lerp_scale() {
+    let grey = Color3::black().lerp(Color3::white(), 0.5);
+    assert_eq!(grey, Color3::new(0.5, 0.5, 0.5).unwrap());
+    assert_eq!(Color3::black().lerp(Color3::white(), 3.0), Color3::white());
+
+    let color = Color3::new(0.75, 0.25, 0.0).unwrap();
+    assert_eq!(color.scale(2.0), Color3::new(1.0, 0.5, 0.0).unwrap());
+    assert_eq!(color.scale(-1.0), Color3::black());
+}
+
+#[test]
+fn test_color4_conversion() {
+    use datatypes::color::Color4;
+
+    let color: Color4 = Color3::red().into();
+    assert_eq!(color, Color4::new(1.0, 0.0, 0.0, 1.0).unwrap());
+    assert_eq!(color.rgb(), Color3::red());
+    assert_eq!(color.to_string(), "color4(1, 0, 0, 1)");
+
+    assert_eq!(
+        Color4::from_rgba(255, 0, 255, 0),
+        Color4::new(1.0, 0.0, 1.0, 0.0).unwrap()
+    );
+    assert_eq!(
+        Color4::from_rgba(10, 20, 30, 40).to_rgba(),
+        (10, 20, 30, 40)
+    );
+    assert!(Color4::new(0.0, 0.0, 0.0, 1.5).is_none());
+}
+
+#[test]
+fn test_color3_hex_string() {
+    assert_eq!(Color3::from_hex_str("#ffffff").unwrap(), Color3::white());
+    assert_eq!(Color3::from_hex_str("#FF0000").unwrap(), Color3::red());
+    assert_eq!(Color3::from_hex_str("00ff00").unwrap(), Color3::green());
+
+    assert!(Color3::from_hex_str("#fff").is_err());
+    assert!(Color3::from_hex_str("#ff00zz").is_err());
+    assert!(Color3::from_hex_str("").is_err());
+
+    let color = Color3::from_hex_str("#1a2B3c").unwrap();
+    assert_eq!(color.to_hex_string(), "#1a2b3c");
+}
+
+#[test]
+fn test_color3_hsv_round_trip() {
+    assert_eq!(Color3::red().to_hsv(), (0.0, 1.0, 1.0));
+    assert_eq!(Color3::from_hsv(360.0, 1.0, 1.0), Color3::red());
+    assert_eq!(Color3::from_hsv(-120.0, 1.0, 1.0), Color3::blue());
+
+    let grey = Color3::new(0.5, 0.5, 0.5).unwrap().to_hsv();
+    assert_eq!(grey, (0.0, 0.0, 0.5));
+
+    for hex in [0x123456, 0xff8000, 0x00ffcc, 0x7f7f7f, 0xc0ffee, 0x000000] {
+        let color = Color3::from_hex(hex);
+        let (h, s, v) = color.to_hsv();
+        assert_approx_eq!(Color3::from_hsv(h, s, v), color);
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_datatypes_serde_round_trip() {
+    use datatypes::vectors::Vector2;
+
+    let vector3 = Vector3::new(1.5, -2.0, 3.25);
+    let json = serde_json::to_string(&vector3).unwrap();
+    assert_eq!(json, "[1.5,-2.0,3.25]");
+    assert_eq!(serde_json::from_str::<Vector3>(&json).unwrap(), vector3);
+
+    let vector2 = Vector2::new(-0.5, 4.0);
+    let json = serde_json::to_string(&vector2).unwrap();
+    assert_eq!(json, "[-0.5,4.0]");
+    assert_eq!(serde_json::from_str::<Vector2>(&json).unwrap(), vector2);
+
+    let color = Color3::new(0.25, 0.5, 1.0).unwrap();
+    let json = serde_json::to_string(&color).unwrap();
+    assert_eq!(json, "[0.25,0.5,1.0]");
+    assert_eq!(serde_json::from_str::<Color3>(&json).unwrap(), color);
+
+    // out of range components are clamped, and the wrong length is an error
+    let clamped = serde_json::from_str::<Color3>("[2.0,-1.0,0.5]").unwrap();
+    assert_eq!(clamped, Color3::new(1.0, 0.0, 0.5).unwrap());
+    assert!(serde_json::from_str::<Vector3>("[1.0,2.0]").is_err());
+}
+
+#[test]
+fn test_texture_drop() {
+    for _ in 0..256 {
+        let texture = Texture::new(solid_bmp(8, 8, (0, 0, 255))).unwrap();
+        assert!(!texture.pixels.is_null());
+    }
+
+    let mut texture = Texture::new(solid_bmp(2, 2, (0, 255, 0))).unwrap();
+    texture.free();
+    assert!(texture.pixels.is_null());
+    texture.free();
+    assert!(
+        texture
+            .sample_bilinear(datatypes::vectors::Vector2::zero())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_texture_decode_errors() {
+    assert!(Texture::from_file("assets/missing-texture.png").is_err());
+
+    let mut truncated = solid_bmp(4, 4, (255, 255, 255));
+    truncated.truncate(truncated.len() / 2);
+    let err = Texture::new(truncated).err().unwrap();
+    assert!(err.starts_with("truncated image"), "{err}");
+
+    // not a "BM" header, so no decoder accepts it
+    let mut bad_magic = solid_bmp(4, 4, (255, 255, 255));
+    bad_magic[..2].copy_from_slice(b"XX");
+    let err = Texture::new(bad_magic).err().unwrap();
+    assert!(err.starts_with("couldn't decode texture"), "{err}");
+    assert!(Texture::new(vec![]).is_err());
+
+    let texture = Texture::new(solid_bmp(4, 2, (255, 255, 255))).unwrap();
+    assert_eq!((texture.width, texture.height), (4, 2));
+}
+
+#[test]
+fn test_texture_params() {
+    assert_eq!(TextureWrap::Repeat as GLenum, GL_REPEAT);
+    assert_eq!(TextureWrap::MirroredRepeat as GLenum, GL_MIRRORED_REPEAT);
+    assert_eq!(TextureWrap::ClampToEdge as GLenum, GL_CLAMP_TO_EDGE);
+    assert_eq!(TextureWrap::ClampToBorder as GLenum, GL_CLAMP_TO_BORDER);
+    assert_eq!(TextureFilter::Nearest as GLenum, GL_NEAREST);
+    assert_eq!(TextureFilter::Linear as GLenum, GL_LINEAR);
+    assert_eq!(
+        TextureFilter::LinearMipmapLinear as GLenum,
+        GL_LINEAR_MIPMAP_LINEAR
+    );
+
+    let default = TextureParams::default();
+    assert_eq!(
+        default,
+        TextureParams::new(TextureWrap::Repeat, TextureFilter::Linear)
+    );
+
+    // the left edge is black and the right edge is white
+    let mut bmp = solid_bmp(2, 1, (0, 0, 0));
+    bmp[57..60].copy_from_slice(&[255, 255, 255]);
+    let mut texture = Texture::new(bmp).unwrap();
+    let edge = datatypes::vectors::Vector2::new(0.0, 0.5);
+
+    // repeating blends with the right edge
+    assert_eq!(texture.sample_bilinear(edge).unwrap().r, 0.5);
+
+    texture.set_params(TextureParams::new(
+        TextureWrap::ClampToEdge,
+        TextureFilter::Nearest,
+    ));
+    assert_eq!(texture.sample_bilinear(edge).unwrap(), Color3::black());
+}
+
+#[test]
+fn test_gl_object_drop_without_context() {
+    use gl_helper::{ContextGuard, has_gl_context};
+
+    assert!(!has_gl_context());
+    let guard = ContextGuard::acquire();
+    assert!(has_gl_context());
+    drop(guard);
+    assert!(!has_gl_context());
+
+    // GL isn't called without a context, which would panic as no GL function is loaded
+    drop(Buffer(7));
+    drop(ShaderProgram(7));
+}
+
+#[test]
+#[ignore = "requires a display (or a virtual display) and a GL context"]
+fn test_render_frame_once() {
+    let win = start_hidden_window();
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let mut part = Part::new(&mesh);
+    part.set_texture(Texture::new(solid_bmp(2, 2, (255, 255, 255))).unwrap());
+    part.set_position(Vector3::new(0.0, 0.0, -3.0)); // in front of the camera
+    let part_entity = tree
+        .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+        .unwrap();
+    let _ = tree.add_main_camera(Camera::new(90.0_f32.to_radians(), 0.1, 100.0));
+
+    let _ = win.poll_events();
+    win.render_frame(&tree);
+    assert_eq!(win.render_stats().draw_calls, 1);
+
+    assert!(part_entity.borrow().newly_created);
+    tree.update_all(1.0 / 60.0);
+    assert!(!part_entity.borrow().newly_created);
+}
+
+#[test]
+#[ignore = "requires a display (or a virtual display) and a GL context"]
+fn test_window_clear_color() {
+    let win = start_hidden_window();
+    let (tree_cell, _head) = create_tree();
+    let (width, height) = win.window.get_drawable_size();
+    let center = || win.read_pixels(width / 2, height / 2, 1, 1);
+
+    win.set_clear_color(Color3::new(0.0, 1.0, 0.0).unwrap());
+    win.render_frame(&tree_cell.borrow());
+    assert_eq!(center(), vec![0, 255, 0, 255]);
+
+    // without the color bit, the old frame stays
+    win.set_clear_color(Color3::new(1.0, 0.0, 0.0).unwrap());
+    win.set_clear_flags(ClearFlags {
+        color: false,
+        ..Default::default()
+    });
+    win.render_frame(&tree_cell.borrow());
+    assert_eq!(center(), vec![0, 255, 0, 255]);
+    assert_eq!(win.get_clear_color(), Color3::new(1.0, 0.0, 0.0).unwrap());
+}
+
+#[test]
+fn test_shader_watcher() {
+    use std::time::{Duration, SystemTime};
+
+    let path = std::env::temp_dir().join("akhiok_test_shader_watcher.glsl");
+    let path = path.to_str().unwrap();
+    std::fs::write(path, FRAG_SHADER).unwrap();
+
+    let mut watcher = ShaderWatcher::new(&[path, "missing/shader.glsl"]);
+    assert!(!watcher.needs_reload());
+
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    assert!(watcher.needs_reload());
+    assert!(!watcher.needs_reload());
+    std::fs::remove_file(path).unwrap();
+
+    // missing files are an error, instead of a panic
+    let error = ShaderProgram::from_vert_frag_file("missing/vert.glsl", "missing/frag.glsl");
+    assert!(error.unwrap_err().contains("missing/vert.glsl"));
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_shader_reload_keeps_program() {
+    let _win = start_hidden_window();
+    let dir = std::env::temp_dir();
+    let (vert_path, frag_path) = (
+        dir.join("akhiok_test_reload_vert.glsl"),
+        dir.join("akhiok_test_reload_frag.glsl"),
+    );
+    let (vert_path, frag_path) = (vert_path.to_str().unwrap(), frag_path.to_str().unwrap());
+    std::fs::write(vert_path, VERT_SHADER).unwrap();
+    std::fs::write(frag_path, FRAG_SHADER).unwrap();
+
+    let mut program = ShaderProgram::from_vert_frag_file(vert_path, frag_path).unwrap();
+    let old_id = program.0;
+
+    std::fs::write(frag_path, "#version 330 core\nvoid main() { typo }").unwrap();
+    assert!(program.reload_from_files(vert_path, frag_path).is_err());
+    assert_eq!(program.0, old_id);
+    assert!(program.link_success());
+
+    std::fs::write(frag_path, FRAG_SHADER).unwrap();
+    program.reload_from_files(vert_path, frag_path).unwrap();
+    assert_ne!(program.0, old_id);
+    assert!(program.link_success());
+
+    std::fs::remove_file(vert_path).unwrap();
+    std::fs::remove_file(frag_path).unwrap();
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_uniform_buffer() {
+    const CAMERA_VERT: &str = "#version 330 core
+layout(location = 0) in vec3 aPos;
+layout(std140) uniform Camera {
+  mat4 view;
+  mat4 projection;
+};
+void main() {
+  gl_Position = projection * view * vec4(aPos, 1.0);
+}";
+    const CAMERA_FRAG: &str = "#version 330 core
+out vec4 FragColor;
+void main() {
+  FragColor = vec4(1.0);
+}";
+
+    let _win = start_hidden_window();
+    let program = ShaderProgram::from_vert_frag(CAMERA_VERT, CAMERA_FRAG).unwrap();
+    assert!(program.bind_uniform_block("Camera", 0));
+    assert!(!program.bind_uniform_block("Missing", 0));
+
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+    let mut data = camera.get_view_matrix().as_byte_slice().to_vec();
+    data.extend_from_slice(camera.get_projection(1.0).as_byte_slice());
+
+    let buffer = UniformBuffer::new(data.len()).unwrap();
+    assert_eq!(buffer.size(), 128);
+    buffer.bind_to_point(0);
+    buffer.update(&data);
+    check_gl_error("uniform buffer").unwrap();
+
+    // read the matrices back from the buffer bound to the point
+    let mut bound = 0;
+    let mut uploaded = vec![0_u8; data.len()];
+    unsafe {
+        glGetIntegeri_v(GL_UNIFORM_BUFFER_BINDING, 0, &mut bound);
+        glBindBuffer(GL_UNIFORM_BUFFER, bound as GLuint);
+        glGetBufferSubData(
+            GL_UNIFORM_BUFFER,
+            0,
+            uploaded.len() as GLsizeiptr,
+            uploaded.as_mut_ptr().cast(),
+        );
+        glBindBuffer(GL_UNIFORM_BUFFER, 0);
+    }
+    assert_ne!(bound, 0);
+    assert_eq!(uploaded, data);
+}
+
+#[test]
+fn test_clear_flags_bits() {
+    assert_eq!(
+        ClearFlags::default().to_bits(),
+        GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT
+    );
+    let stencil_only = ClearFlags {
+        color: false,
+        depth: false,
+        stencil: true,
+    };
+    assert_eq!(stencil_only.to_bits(), GL_STENCIL_BUFFER_BIT);
+}
+
+#[test]
+#[ignore = "requires a display (or a virtual display) and a GL context"]
+fn test_draw_instanced() {
+    use ultraviolet::{Mat4, Vec3};
+
+    let mut win = start_hidden_window();
+    win.init_instancing(FRAG_SHADER).unwrap();
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+    win.set_camera_uniforms(win.instanced_shader_program.as_ref().unwrap(), &camera);
+
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let transforms: Vec<Mat4> = (0..100)
+        .map(|i| Mat4::from_translation(Vec3::new((i % 10) as f32, (i / 10) as f32, -5.0)))
+        .collect();
+
+    clear_color(Color3::black());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT) };
+    win.reset_render_stats();
+    win.draw_instanced(
+        &mesh,
+        &transforms,
+        Color3::new(1.0, 0.0, 0.0).unwrap(),
+        None,
+    );
+    assert_eq!(win.render_stats().draw_calls, 1);
+
+    // the first instance is in front of the camera, tinted by `obj_color`
+    let (width, height) = win.window.get_drawable_size();
+    let pixel = win.read_pixels(width / 2, height / 2, 1, 1);
+    assert!(pixel[0] > 0);
+    assert_eq!((pixel[1], pixel[2]), (0, 0));
+
+    let mut size = 0;
+    win.instance_buffer.bind(BufferType::Array);
+    unsafe { glGetBufferParameteriv(GL_ARRAY_BUFFER, GL_BUFFER_SIZE, &mut size) };
+    win.vbo.bind(BufferType::Array);
+    assert_eq!(size as usize, 100 * size_of::<Mat4>());
+}
+
+#[test]
+#[ignore = "requires a display (or a virtual display) and a GL context"]
+fn test_hidden_window_read_pixels() {
+    let win = start_hidden_window();
+    clear_color(Color3::new(1.0, 0.0, 1.0).unwrap());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT) };
+
+    let (width, height) = win.window.get_drawable_size();
+    let pixel = win.read_pixels(width / 2, height / 2, 1, 1);
+    assert_eq!(pixel, vec![255, 0, 255, 255]);
+    assert!(win.read_pixels(0, 0, -1, 1).is_empty());
+}
+
+#[test]
+#[ignore = "requires a display (or a virtual display) and a GL context"]
+fn test_capture_png() {
+    let win = start_hidden_window();
+    clear_color(Color3::new(0.0, 1.0, 1.0).unwrap());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT) };
+
+    let path = std::env::temp_dir().join("akhiok_test_capture.png");
+    let path = path.to_str().unwrap();
+    win.capture_png(path).unwrap();
+    let texture = Texture::from_file(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    let (width, height) = win.window.get_drawable_size();
+    assert_eq!((texture.width, texture.height), (width, height));
+    let corner = texture.sample_bilinear(datatypes::vectors::Vector2::zero());
+    assert_eq!(corner, Some(Color3::new(0.0, 1.0, 1.0).unwrap()));
+}
+
+#[test]
+fn test_encode_png() {
+    // top row: red, green; bottom row: blue, translucent white
+    let rgba = [
+        255, 0, 0, 255, 0, 255, 0, 255, //
+        0, 0, 255, 255, 255, 255, 255, 128,
+    ];
+    let png = texture::encode_png(2, 2, &rgba).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+
+    let texture = Texture::new(png).unwrap();
+    assert_eq!((texture.width, texture.height), (2, 2));
+    assert!(texture.is_translucent());
+    // textures are flipped, so (0, 0) is the bottom-left
+    let bottom_left = texture.sample_bilinear(datatypes::vectors::Vector2::new(0.25, 0.25));
+    assert_eq!(bottom_left, Some(Color3::blue()));
+
+    assert!(texture::encode_png(3, 2, &rgba).is_err());
+    assert!(texture::encode_png(0, 0, &[]).is_err());
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_window_teardown() {
+    let win = start_window();
+    assert_ne!(win.vao.0, 0);
+    assert_ne!(win.vbo.0, 0);
+    assert_ne!(win.ebo.0, 0);
+
+    win.delete();
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_window_depth_test() {
+    let win = start_window();
+    assert_eq!(unsafe { glIsEnabled(GL_DEPTH_TEST) }, GL_TRUE);
+
+    win.set_depth_test(false);
+    assert_eq!(unsafe { glIsEnabled(GL_DEPTH_TEST) }, GL_FALSE);
+
+    win.set_depth_func(gl_helper::DepthFunc::LessEqual);
+    let mut func = 0;
+    unsafe { glGetIntegerv(GL_DEPTH_FUNC, &mut func) };
+    assert_eq!(func as GLenum, GL_LEQUAL);
+}
+
+#[test]
+fn test_blend_factor_values() {
+    use gl_helper::BlendFactor;
+
+    assert_eq!(BlendFactor::Zero as GLenum, GL_ZERO);
+    assert_eq!(BlendFactor::One as GLenum, GL_ONE);
+    assert_eq!(BlendFactor::SrcAlpha as GLenum, GL_SRC_ALPHA);
+    assert_eq!(
+        BlendFactor::OneMinusSrcAlpha as GLenum,
+        GL_ONE_MINUS_SRC_ALPHA
+    );
+
+    // 24 bit images are opaque
+    let texture = Texture::new(solid_bmp(2, 2, (0, 255, 0))).unwrap();
+    assert!(!texture.is_translucent());
+    assert!(!Part::default().is_translucent());
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_window_blend() {
+    let win = start_window();
+    win.set_blend(true);
+    assert_eq!(unsafe { glIsEnabled(GL_BLEND) }, GL_TRUE);
+    let mut src = 0;
+    unsafe { glGetIntegerv(GL_BLEND_SRC_ALPHA, &mut src) };
+    assert_eq!(src as GLenum, GL_SRC_ALPHA);
+
+    win.set_blend(false);
+    assert_eq!(unsafe { glIsEnabled(GL_BLEND) }, GL_FALSE);
+}
+
+#[test]
+fn test_translucent_texture() {
+    let texture = Texture::new(translucent_bmp(2, 2, (255, 255, 255, 128))).unwrap();
+    assert!(texture.is_translucent());
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_render_translucent_after_prepass() {
+    use gl_helper::Viewport;
+
+    let mut win = start_window();
+    win.init_depth_prepass(VERT_SHADER).unwrap();
+
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+
+    let mut opaque = Part::new(&mesh);
+    opaque.color = Color3::red();
+    opaque.set_position(Vector3::new(0.0, 0.0, -3.0));
+    // nearer to the camera, so it's blended over the opaque part
+    let mut translucent = Part::new(&mesh);
+    translucent.color = Color3::green();
+    translucent.set_texture(Texture::new(translucent_bmp(2, 2, (255, 255, 255, 128))).unwrap());
+    translucent.set_position(Vector3::new(0.0, 0.0, -2.0));
+    for part in [opaque, translucent] {
+        let _ = tree
+            .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+            .unwrap();
+    }
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+
+    clear_color(Color3::black());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT) };
+    win.render_tree_with_prepass(&tree, &camera);
+
+    // both parts show through, the translucent part isn't rejected by the equal depth test
+    let viewport = Viewport::current();
+    let mut pixel = [0_u8; 4];
+    unsafe {
+        glReadPixels(
+            viewport.width / 2,
+            viewport.height / 2,
+            1,
+            1,
+            GL_RGBA,
+            GL_UNSIGNED_BYTE,
+            pixel.as_mut_ptr().cast(),
+        );
+    }
+    assert!(pixel[0] > 0);
+    assert!(pixel[1] > 0);
+
+    // the state is restored
+    let (mut func, mut depth_write) = (0, 0);
+    unsafe {
+        glGetIntegerv(GL_DEPTH_FUNC, &mut func);
+        glGetIntegerv(GL_DEPTH_WRITEMASK, &mut depth_write);
+    }
+    assert_eq!(func as GLenum, GL_LESS);
+    assert_eq!(depth_write, GL_TRUE as GLint);
+    assert_eq!(unsafe { glIsEnabled(GL_BLEND) }, GL_FALSE);
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_part_texture_slots() {
+    use entities::types::part_type::DIFFUSE_TEXTURE_SLOT;
+
+    const SLOTS_FRAG: &str = "#version 330 core
+uniform sampler2D texture0;
+uniform sampler2D normal_map;
+out vec4 FragColor;
+void main() {
+  FragColor = texture(texture0, vec2(0.0)) + texture(normal_map, vec2(0.0));
+}";
+
+    let _win = start_hidden_window();
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let mut part = Part::new(&mesh);
+    part.set_texture(Texture::new(solid_bmp(2, 2, (255, 0, 0))).unwrap());
+    part.set_texture_slot(
+        "normal_map",
+        Texture::new(solid_bmp(2, 2, (0, 0, 255))).unwrap(),
+    );
+
+    let diffuse_id = part.get_texture().unwrap().get_texture_id();
+    let normal_id = part
+        .get_texture_slot("normal_map")
+        .unwrap()
+        .get_texture_id();
+    assert_eq!(
+        part.get_texture_slot(DIFFUSE_TEXTURE_SLOT)
+            .unwrap()
+            .get_texture_id(),
+        diffuse_id
+    );
+    assert!(part.get_texture_slot("specular_map").is_none());
+
+    let program = ShaderProgram::from_vert_frag(VERT_SHADER, SLOTS_FRAG).unwrap();
+    program.use_program();
+    part.texture_slots().bind(&program);
+
+    for (unit, texture_id, sampler) in [(0, diffuse_id, "texture0"), (1, normal_id, "normal_map")] {
+        let (mut bound, mut value) = (0, -1);
+        unsafe {
+            glActiveTexture(GL_TEXTURE0 + unit);
+            glGetIntegerv(GL_TEXTURE_BINDING_2D, &mut bound);
+            glGetUniformiv(program.0, program.uniform_location(sampler), &mut value);
+        }
+        assert_eq!(bound as GLuint, texture_id);
+        assert_eq!(value, unit as i32);
+    }
+}
+
+#[test]
+fn test_material_uniforms() {
+    use entities::material::Material;
+
+    let mut material = Material::new(Rc::new(ShaderProgram(0)), Color3::red());
+    assert!(material.get_uniform("shininess").is_none());
+
+    material.set_uniform("shininess", UniformValue::Float(8.0));
+    material.set_uniform("shininess", UniformValue::Float(32.0));
+    material.set_uniform("lit", UniformValue::Bool(true));
+    assert_eq!(
+        material.get_uniform("shininess"),
+        Some(UniformValue::Float(32.0))
+    );
+    assert_eq!(material.get_uniform("lit"), Some(UniformValue::Bool(true)));
+    assert!(material.get_texture("texture0").is_none());
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_material_apply() {
+    use entities::material::Material;
+
+    const MATERIAL_FRAG: &str = "#version 330 core
+uniform sampler2D texture0;
+uniform vec3 obj_color;
+uniform float shininess;
+out vec4 FragColor;
+void main() {
+  FragColor = texture(texture0, vec2(0.0)) * vec4(obj_color * shininess, 1.0);
+}";
+
+    let _win = start_hidden_window();
+    let program = Rc::new(ShaderProgram::from_vert_frag(VERT_SHADER, MATERIAL_FRAG).unwrap());
+    let mut material = Material::new(program.clone(), Color3::new(0.25, 0.5, 1.0).unwrap());
+    material.set_texture(
+        "texture0",
+        Texture::new(solid_bmp(2, 2, (0, 255, 0))).unwrap(),
+    );
+    material.set_uniform("shininess", UniformValue::Float(16.0));
+
+    material.apply();
+
+    let (mut current, mut bound, mut sampler) = (0, 0, -1);
+    let (mut color, mut shininess) = ([0.0_f32; 3], 0.0_f32);
+    unsafe {
+        glGetIntegerv(GL_CURRENT_PROGRAM, &mut current);
+        glGetIntegerv(GL_TEXTURE_BINDING_2D, &mut bound);
+        glGetUniformiv(
+            program.0,
+            program.uniform_location("texture0"),
+            &mut sampler,
+        );
+        glGetUniformfv(
+            program.0,
+            program.uniform_location("obj_color"),
+            color.as_mut_ptr(),
+        );
+        glGetUniformfv(
+            program.0,
+            program.uniform_location("shininess"),
+            &mut shininess,
+        );
+    }
+
+    assert_eq!(current as GLuint, program.0);
+    assert_eq!(
+        bound as GLuint,
+        material.get_texture("texture0").unwrap().get_texture_id()
+    );
+    assert_eq!(sampler, 0);
+    assert_eq!(color, [0.25, 0.5, 1.0]);
+    assert_eq!(shininess, 16.0);
+
+    // a part with a material is translucent by the material's textures, not it's own
+    let mut part = Part::default();
+    part.material = Some(material.clone());
+    assert!(!part.is_translucent());
+    material.set_texture(
+        "texture0",
+        Texture::new(translucent_bmp(2, 2, (0, 255, 0, 128))).unwrap(),
+    );
+    part.material = Some(material);
+    assert!(part.is_translucent());
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_part_release_gpu() {
+    let _win = start_hidden_window();
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+
+    let baseline = live_texture_count();
+    for _ in 0..64 {
+        let mut part = Part::new(&mesh);
+        part.set_texture(Texture::new(solid_bmp(4, 4, (255, 255, 0))).unwrap());
+        part.set_texture_slot(
+            "normal_map",
+            Texture::new(solid_bmp(4, 4, (0, 0, 255))).unwrap(),
+        );
+
+        let part_entity = tree
+            .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+            .unwrap();
+        assert_eq!(live_texture_count(), baseline + 2);
+
+        let id = part_entity.borrow().get_uuid();
+        assert_eq!(tree.destroy_entity(id), 1);
+        let destroyed = part_entity.borrow();
+        let EntityType::Part(destroyed_part) = destroyed.get_type() else {
+            panic!("destroyed entity isn't a part");
+        };
+        assert!(destroyed_part.get_texture().is_none());
+        assert_eq!(live_texture_count(), baseline);
+    }
+
+    // removing keeps the textures, so the part can be added again
+    let mut part = Part::new(&mesh);
+    part.set_texture(Texture::new(solid_bmp(4, 4, (255, 255, 0))).unwrap());
+    let part_entity = tree
+        .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+        .unwrap();
+    let id = part_entity.borrow().get_uuid();
+    let removed = tree.remove_entity(id).unwrap();
+    assert_eq!(live_texture_count(), baseline + 1);
+    drop((removed, part_entity));
+    assert_eq!(live_texture_count(), baseline);
+
+    let mut part = Part::new(&mesh);
+    part.set_texture(Texture::new(solid_bmp(4, 4, (255, 255, 0))).unwrap());
+    let clone = part.clone();
+    part.release_gpu();
+    assert_eq!(live_texture_count(), baseline + 1); // still used by the clone
+    drop(clone);
+    assert_eq!(live_texture_count(), baseline);
+}
+
+#[test]
+fn test_cull_mode_values() {
+    use gl_helper::{CullMode, FrontFace};
+
+    assert_eq!(CullMode::None as GLenum, 0);
+    assert_eq!(CullMode::Back as GLenum, GL_BACK);
+    assert_eq!(CullMode::Front as GLenum, GL_FRONT);
+    assert_eq!(FrontFace::Ccw as GLenum, GL_CCW);
+    assert_eq!(FrontFace::Cw as GLenum, GL_CW);
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_window_cull_mode() {
+    use gl_helper::CullMode;
+
+    let win = start_window();
+    win.set_cull_mode(CullMode::None);
+    assert_eq!(unsafe { glIsEnabled(GL_CULL_FACE) }, GL_FALSE);
+
+    win.set_cull_mode(CullMode::Back);
+    assert_eq!(unsafe { glIsEnabled(GL_CULL_FACE) }, GL_TRUE);
+    let mut face = 0;
+    unsafe { glGetIntegerv(GL_CULL_FACE_MODE, &mut face) };
+    assert_eq!(face as GLenum, GL_BACK);
+}
+
+#[test]
+fn test_primitive_meshes_are_ccw() {
+    // every triangle faces +Z, so it's counter-clockwise when viewed from +Z
+    for mesh in [
+        Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap(),
+        Mesh::load_mesh(include_str!("../assets/meshs/triangle.mesh")).unwrap(),
+        Mesh::fullscreen_triangle(),
+    ] {
+        for [a, b, c] in mesh.to_indices_tri() {
+            let p = |i: u32| mesh.vertices[i as usize].get_position();
+            assert!((p(b) - p(a)).cross(p(c) - p(a)).z > 0.0);
+        }
+    }
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_shader_program_uniforms() {
+    let win = start_window();
+    let program = &win.shader_program;
+    program.use_program();
+
+    assert_ne!(program.uniform_location("model"), -1);
+    assert_eq!(
+        program.uniform_location("model"),
+        program.uniform_location("model\0")
+    );
+
+    program.set_vector2("uv_offset", datatypes::vectors::Vector2::one());
+    program.set_vector3("light_position", Vector3::up());
+    program.set_color3("obj_color", Color3::white());
+    program.set_matrix3("normal_matrix", ultraviolet::Mat3::identity());
+    assert_eq!(unsafe { glGetError() }, GL_NO_ERROR);
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_geometry_shader_compiles() {
+    const PASS_THROUGH_GEO: &str = "#version 330 core
+layout (triangles) in;
+layout (triangle_strip, max_vertices = 3) out;
+
+in vec3 vertColor[];
+in vec2 coord[];
+out vec3 geoColor;
+out vec2 geoCoord;
+
+void main() {
+    for (int i = 0; i < 3; i++) {
+        gl_Position = gl_in[i].gl_Position;
+        geoColor = vertColor[i];
+        geoCoord = coord[i];
+        EmitVertex();
+    }
+    EndPrimitive();
+}";
+
+    const EMPTY_FRAG: &str = include_str!("shaders/depth_frag.glsl");
+
+    let _win = start_window();
+    let program = ShaderProgram::from_vert_geo_frag(VERT_SHADER, PASS_THROUGH_GEO, EMPTY_FRAG);
+    assert!(program.is_ok(), "{}", program.err().unwrap_or_default());
+
+    let invalid = ShaderProgram::from_vert_geo_frag(VERT_SHADER, "not glsl", EMPTY_FRAG);
+    assert!(invalid.is_err_and(|e| e.starts_with("geometry compile error")));
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_shader_info_log() {
+    let _win = start_window();
+
+    let shader = Shader::new(ShaderType::Fragment).unwrap();
+    shader.set_source("#version 330 core\nvoid main() { broken }");
+    shader.compile();
+    assert!(!shader.compile_success());
+    assert!(!shader.info_log().is_empty());
+
+    let compiled = ShaderProgram::from_vert_frag(VERT_SHADER, "#version 330 core\nbroken");
+    assert!(compiled.is_err_and(|e| e.len() > "fragment compile error: ".len()));
+}
+
+#[test]
+#[ignore = "requires a display and a GL 4.3 context"]
+fn test_compute_dispatch() {
+    const NO_OP_COMPUTE: &str = "#version 430 core
+layout (local_size_x = 1) in;
+
+void main() {
+}";
+
+    let win = start_window();
+    let program = match win.create_compute_program(NO_OP_COMPUTE) {
+        Ok(program) => program,
+        Err(e) if gl_helper::gl_version() < (4, 3) => {
+            println!("skipped: {}", e);
+            return;
+        }
+        Err(e) => panic!("{}", e),
+    };
+
+    program.dispatch(1, 1, 1);
+    program.memory_barrier(gl_helper::GL_ALL_BARRIER_BITS);
+    assert_eq!(unsafe { glGetError() }, GL_NO_ERROR);
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_buffer_sub_data() {
+    let _win = start_window();
+
+    let buffer = Buffer::new().unwrap();
+    buffer.bind(BufferType::Array);
+    buffer_reserve(BufferType::Array, 8, GL_DYNAMIC_DRAW);
+    buffer_sub_data(BufferType::Array, 0, &[0; 8]);
+    buffer_sub_data(BufferType::Array, 2, &[1, 2, 3]);
+
+    let mut read = [0_u8; 8];
+    unsafe {
+        glGetBufferSubData(GL_ARRAY_BUFFER, 0, 8, read.as_mut_ptr().cast());
+    }
+    assert_eq!(read, [0, 0, 1, 2, 3, 0, 0, 0]);
+    assert_eq!(unsafe { glGetError() }, GL_NO_ERROR);
+}
+
+#[test]
+fn test_gl_error_name() {
+    assert_eq!(gl_error_name(GL_NO_ERROR), "GL_NO_ERROR");
+    assert_eq!(gl_error_name(GL_INVALID_OPERATION), "GL_INVALID_OPERATION");
+    assert_eq!(gl_error_name(GL_OUT_OF_MEMORY), "GL_OUT_OF_MEMORY");
+    assert_eq!(gl_error_name(0xdead), "unknown GL error");
+}
+
+#[test]
+#[ignore = "requires GL to be loaded, without a current context"]
+fn test_buffer_new_without_context() {
+    let error = Buffer::new().err().unwrap();
+    assert!(error.starts_with("couldn't make a buffer"));
+}
+
+#[test]
+fn test_vertex_layout_stride() {
+    let layout = VertexData::layout();
+    assert_eq!(layout.stride(), 11 * size_of::<f32>());
+    assert_eq!(layout.stride(), size_of::<VertexDataInternal>());
+
+    let offsets: Vec<usize> = layout.attributes().iter().map(|a| a.offset).collect();
+    assert_eq!(
+        offsets,
+        vec![
+            0,
+            3 * size_of::<f32>(),
+            6 * size_of::<f32>(),
+            8 * size_of::<f32>()
+        ]
+    );
+}
+
+#[test]
+#[ignore = "requires a display and a GL context"]
+fn test_render_untextured_part() {
+    use gl_helper::Viewport;
+
+    let win = start_window();
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    let mut part = Part::new(&mesh);
+    part.color = Color3::red();
+    part.set_position(Vector3::new(0.0, 0.0, -3.0));
+    let _ = tree
+        .add_entity_with_parent("Part", EntityType::Part(part), &mut head.borrow_mut())
+        .unwrap();
+    let camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+
+    clear_color(Color3::black());
+    unsafe { glClear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT) };
+    win.reset_render_stats();
+    win.render_tree(&tree, &camera);
+    assert_eq!(win.render_stats().draw_calls, 1);
+
+    // drawn with only it's color (and the vertex colors)
+    let viewport = Viewport::current();
+    let mut pixel = [0_u8; 4];
+    unsafe {
+        glReadPixels(
+            viewport.width / 2,
+            viewport.height / 2,
+            1,
+            1,
+            GL_RGBA,
+            GL_UNSIGNED_BYTE,
+            pixel.as_mut_ptr().cast(),
+        );
+    }
+    assert!(pixel[0] > 0);
+    assert_eq!(&pixel[1..], &[0, 0, 255]);
+}
+
+#[test]
+fn test_frame_timer_accumulates() {
+    use std::time::{Duration, Instant};
+    use window::{FrameTimer, MAX_FRAME_DELTA};
+
+    let start = Instant::now();
+    let mut timer = FrameTimer::starting_at(start);
+    let mut accumulated = 0.0;
+    for frame in 1..=10 {
+        let delta = timer.tick_at(start + Duration::from_millis(5 * frame));
+        assert!(delta <= MAX_FRAME_DELTA);
+        accumulated += delta;
+    }
+
+    assert!((accumulated - 0.05).abs() < 1e-4);
+}
+
+#[test]
+fn test_frame_timer_clamps() {
+    use std::time::{Duration, Instant};
+    use window::{FrameTimer, MAX_FRAME_DELTA};
+
+    let start = Instant::now();
+    let mut timer = FrameTimer::starting_at(start);
+    let now = start + Duration::from_secs_f32(MAX_FRAME_DELTA + 0.05);
+    assert_eq!(timer.tick_at(now), MAX_FRAME_DELTA);
+    assert!(timer.last_frame_time() > MAX_FRAME_DELTA);
+
+    // real time is still measured by tick
+    assert!(FrameTimer::new().tick() < MAX_FRAME_DELTA);
+}
+
+#[test]
+fn test_frame_stats_average() {
+    use window::{FRAME_STATS_WINDOW, FrameStats};
+
+    let mut stats = FrameStats::default();
+    assert_eq!(stats.fps(), 0.0);
+
+    for frame_time in [0.01, 0.02, 0.03, 0.04] {
+        stats.record(frame_time);
+    }
+    assert!((stats.frame_time_ms() - 25.0).abs() < 1e-3);
+    assert!((stats.fps() - 40.0).abs() < 1e-2);
+
+    // only the latest frames are averaged
+    for _ in 0..FRAME_STATS_WINDOW {
+        stats.record(0.1);
+    }
+    assert!((stats.frame_time_ms() - 100.0).abs() < 1e-3);
+    assert!((stats.fps() - 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_input_service_key_event() {
+    use beryllium::events::Event;
+    use entities::types::io_service::PressedStatus;
+
+    let mut input_service = InputService::default();
+    let key_down = Event::Key {
+        win_id: 0,
+        pressed: true,
+        repeat: 0,
+        scancode: Default::default(),
+        keycode: SDLK_w,
+        modifiers: Default::default(),
+    };
+
+    assert!(input_service.handle_event(&key_down));
+    assert_eq!(input_service.get_key_status(SDLK_w), PressedStatus::Pressed);
+
+    // frame boundary
+    input_service.mark_cleanup();
+    assert_eq!(input_service.get_key_status(SDLK_w), PressedStatus::Down);
+    assert!(input_service.is_key_down(SDLK_w));
+
+    assert!(!input_service.handle_event(&Event::Quit));
+}
+
+#[test]
+fn test_input_service_press_hold_release() {
+    use entities::types::io_service::PressedStatus;
+
+    let mut input_service = InputService::default();
+
+    // press
+    input_service.provide_input(SDLK_w, true);
+    assert!(input_service.is_key_pressed(SDLK_w));
+    input_service.mark_cleanup();
+    assert!(input_service.is_key_down(SDLK_w));
+
+    // hold, auto-repeats are ignored
+    for _ in 0..3 {
+        input_service.provide_input(SDLK_w, true);
+        assert!(input_service.is_key_down(SDLK_w));
+        input_service.mark_cleanup();
+    }
+    assert!(input_service.is_key_down(SDLK_w));
+
+    // release
+    input_service.provide_input(SDLK_w, false);
+    assert!(input_service.is_key_released(SDLK_w));
+    input_service.mark_cleanup();
+    assert_eq!(input_service.get_key_status(SDLK_w), PressedStatus::None);
+
+    // releasing an inactive key is ignored
+    input_service.provide_input(SDLK_a, false);
+    assert!(!input_service.is_key_active(SDLK_a));
+}
+
+#[test]
+fn test_input_service_repress_after_release() {
+    let mut input_service = InputService::default();
+    input_service.provide_input(SDLK_w, true);
+    input_service.provide_input(SDLK_w, true); // repeat before the frame ends
+    assert!(input_service.is_key_pressed(SDLK_w));
+
+    input_service.mark_cleanup();
+    input_service.provide_input(SDLK_w, false);
+    input_service.provide_input(SDLK_w, true);
+    assert!(input_service.is_key_pressed(SDLK_w));
+
+    input_service.mark_cleanup();
+    assert!(input_service.is_key_down(SDLK_w));
+}
+
+#[test]
+fn test_camera_look_at() {
+    let mut camera = Camera::default();
+    camera.set_position(Vector3::new(1.0, 2.0, 3.0));
+    let target = Vector3::new(-4.0, 0.0, 7.0);
+
+    camera.look_at(target, Vector3::up());
+
+    let expected = (target - camera.get_position()).get_unit();
+    assert!((camera.get_front() - expected).length() < 1e-5);
+    assert!(camera.get_right().dot(camera.get_front()).abs() < 1e-5);
+    assert!(camera.get_up().dot(camera.get_front()).abs() < 1e-5);
+    assert!(camera.get_up().y > 0.0);
+
+    // the rotation produces the same front
+    let mut rotated = camera.clone();
+    rotated.update_vectors();
+    assert!((rotated.get_front() - expected).length() < 1e-5);
+
+    // the target is rendered in the middle of the view, and `right` is right on the screen
+    let view = camera.get_view_matrix();
+    let viewed = view.transform_point3(target.into());
+    assert!(viewed.x.abs() < 1e-4 && viewed.y.abs() < 1e-4 && viewed.z < 0.0);
+    let right = view.transform_point3((camera.get_position() + camera.get_right()).into());
+    assert!(right.x > 0.99);
+
+    // looking straight up, or at itself
+    camera.look_at(camera.get_position() + Vector3::up(), Vector3::up());
+    assert!((camera.get_front() - Vector3::up()).length() < 1e-5);
+    assert!(camera.get_right().iter().all(f32::is_finite));
+
+    let front = camera.get_front();
+    camera.look_at(camera.get_position(), Vector3::up());
+    assert_eq!(camera.get_front(), front);
+}
+
+#[test]
+fn test_camera_orthographic_projection() {
+    use ultraviolet::Vec4;
+
+    let camera = Camera::new_orthographic(10.0, 0.1, 100.0);
+    let projection = camera.get_projection(2.0);
+
+    // 2 points on a line parallel to the view direction, at different depths
+    let near = projection * Vec4::new(2.0, 1.0, -1.0, 1.0);
+    let far = projection * Vec4::new(2.0, 1.0, -50.0, 1.0);
+
+    for clip in [near, far] {
+        assert!((clip.w - 1.0).abs() < 1e-6);
+        assert!((clip.x - 0.2).abs() < 1e-6); // the view is 20 units wide
+        assert!((clip.y - 0.2).abs() < 1e-6); // the view is 10 units high
+    }
+    assert!(near.z < far.z);
+
+    let perspective = Camera::default();
+    assert_eq!(
+        perspective.projection,
+        entities::types::camera_type::ProjectionKind::Perspective
+    );
+    let clip = perspective.get_projection(1.0) * Vec4::new(0.0, 0.0, -5.0, 1.0);
+    assert!((clip.w - 5.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_camera_view_matrix() {
+    use ultraviolet::Vec4;
+
+    let mut camera = Camera::default();
+    let view = camera.get_view_matrix();
+    for (a, b) in view
+        .cols
+        .iter()
+        .zip(ultraviolet::Mat4::identity().cols.iter())
+    {
+        assert!((*a - *b).mag() < 1e-6);
+    }
+
+    // a point in front of the camera, moving the camera back moves the point away
+    camera.set_position(Vector3::new(0.0, 0.0, 5.0));
+    let point = camera.get_view_matrix() * Vec4::new(0.0, 0.0, -1.0, 1.0);
+    assert!((point.z + 6.0).abs() < 1e-5);
+
+    // set_rotation recalculates the transform
+    camera.set_rotation(Vector3::new(0.0, 90.0, 0.0));
+    let rotated = camera.get_view_matrix();
+    let changed = (rotated.cols.iter().zip(view.cols.iter())).any(|(a, b)| (*a - *b).mag() > 0.5);
+    assert!(changed);
+    assert_eq!(camera.transform, camera.calculate_transform());
+
+    let expected = camera.get_projection(1.5) * rotated;
+    assert_eq!(camera.get_view_projection(1.5), expected);
+}
+
+#[test]
+fn test_camera_frustum() {
+    let mut camera = Camera::new(90.0_f32.to_radians(), 0.1, 100.0);
+    let frustum = camera.get_frustum(1.0);
+    let half = Vector3::new(0.5, 0.5, 0.5);
+
+    // the camera looks down -Z
+    let ahead = Vector3::new(0.0, 0.0, -5.0);
+    assert!(frustum.contains_aabb(ahead - half, ahead + half));
+
+    let behind = Vector3::new(0.0, 0.0, 5.0);
+    assert!(!frustum.contains_aabb(behind - half, behind + half));
+
+    let too_far = Vector3::new(0.0, 0.0, -150.0);
+    assert!(!frustum.contains_aabb(too_far - half, too_far + half));
+
+    let to_the_side = Vector3::new(20.0, 0.0, -5.0);
+    assert!(!frustum.contains_aabb(to_the_side - half, to_the_side + half));
+
+    // a box crossing the edge of the view is kept
+    let edge = Vector3::new(5.0, 0.0, -5.0);
+    assert!(frustum.contains_aabb(edge - half, edge + half));
+
+    // moving the camera moves the frustum
+    camera.set_position(Vector3::new(0.0, 0.0, 10.0));
+    let frustum = camera.get_frustum(1.0);
+    assert!(frustum.contains_aabb(behind - half, behind + half));
+}
+
+#[test]
+fn test_fly_controller() {
+    use datatypes::vectors::Vector2;
+    use entities::types::fly_controller::{FlyController, MAX_PITCH_DEGREES};
+
+    let controller = FlyController::new(3.0, 0.5);
+    let mut camera = Camera::default();
+    let mut input_service = InputService::default();
+    input_service.provide_input(SDLK_w, true);
+    input_service.mark_cleanup();
+
+    let start = camera.get_position();
+    let front = camera.get_front();
+    for _ in 0..10 {
+        controller.update(&mut camera, &input_service, 0.1);
+        input_service.mark_cleanup();
+    }
+
+    let expected = start + front * controller.speed;
+    assert!((camera.get_position() - expected).length() < 1e-4);
+
+    // moving the mouse up and right pitches up and turns right, the pitch is clamped
+    input_service.provide_mouse_motion(Vector2::new(10.0, -1000.0));
+    controller.update(&mut camera, &input_service, 0.0);
+    assert_eq!(camera.get_rotation().x, MAX_PITCH_DEGREES);
+    assert_eq!(camera.get_rotation().y, -5.0);
+    assert!(camera.get_front().y > 0.99);
+    assert!(camera.get_front().x > 0.0);
+
+    input_service.mark_cleanup();
+    assert_eq!(input_service.get_mouse_delta(), Vector2::zero());
+}
+
+#[test]
+fn test_tree_callbacks() {
+    let mut tree = EntityTree::default();
+    let added = Rc::new(RefCell::new(Vec::new()));
+    let removed = Rc::new(RefCell::new(Vec::new()));
+    let reparented = Rc::new(RefCell::new(Vec::new()));
+
+    let added_log = added.clone();
+    tree.on_added(Box::new(move |id| added_log.borrow_mut().push(id)));
+    let removed_log = removed.clone();
+    tree.on_removed(Box::new(move |id| removed_log.borrow_mut().push(id)));
+    let reparented_log = reparented.clone();
+    tree.on_reparented(Box::new(move |id, parent_id| {
+        reparented_log.borrow_mut().push((id, parent_id))
+    }));
+
+    let first = tree.add_entity("first", EntityType::Base(entities::entity::Base));
+    let second = tree
+        .add_entity_with_parent(
+            "second",
+            EntityType::Part(Part::default()),
+            &mut first.borrow_mut(),
+        )
+        .unwrap();
+    let (first_id, second_id) = (first.borrow().get_uuid(), second.borrow().get_uuid());
+    assert_eq!(*added.borrow(), vec![first_id, second_id]);
+    assert_eq!(*reparented.borrow(), vec![(second_id, Some(first_id))]);
+
+    let _ = tree.remove_entity(first_id).unwrap();
+    assert_eq!(*removed.borrow(), vec![first_id]);
+    assert_eq!(reparented.borrow().last(), Some(&(second_id, None)));
+}
+
+#[test]
+fn test_remove_entity() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    // head -> middle -> (leaf, other)
+    let middle_binding = tree
+        .add_entity_with_parent(
+            "middle",
+            EntityType::Part(Part::default()),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+    let middle_id = middle_binding.borrow().get_uuid();
+    let mut middle = middle_binding.borrow_mut();
+    let leaf_id = tree
+        .add_entity_with_parent("leaf", EntityType::Part(Part::default()), &mut middle)
+        .unwrap()
+        .borrow()
+        .get_uuid();
+    let other_id = tree
+        .add_entity_with_parent("other", EntityType::Part(Part::default()), &mut middle)
+        .unwrap()
+        .borrow()
+        .get_uuid();
+    drop(middle);
+
+    let removed = tree.remove_entity(middle_id).unwrap();
+    assert_eq!(removed.borrow().get_uuid(), middle_id);
+    assert!(removed.borrow().parent_id.is_none());
+    assert!(removed.borrow().children_id.is_empty());
+
+    assert!(!tree.entity_map.contains_key(&middle_id));
+    assert!(!tree.parts.contains(&middle_id));
+    let head = head_binding.borrow();
+    assert!(!head.children_id.contains(&middle_id));
+
+    // the children are re-parented to the head
+    assert_eq!(head.children_id, vec![leaf_id, other_id]);
+    assert_eq!(
+        tree.get_entity(leaf_id).unwrap().parent_id,
+        Some(head.get_uuid())
+    );
+    drop(head);
+
+    assert!(tree.remove_entity(middle_id).is_none());
+
+    let head_id = head_binding.borrow().get_uuid();
+    let removed = tree.remove_subtree(head_id);
+    assert_eq!(removed.len(), 3);
+    assert!(tree.entity_map.is_empty());
+    assert!(tree.parts.is_empty());
+    assert!(tree.head.is_none());
+}
+
+#[test]
+fn test_entity_tree_iterators() {
+    use std::cell::Cell;
+
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+    let mut add = |name: &str, parent: &Rc<RefCell<Entity>>| {
+        let entity = tree
+            .add_entity_with_parent(
+                name,
+                EntityType::Part(Part::default()),
+                &mut parent.borrow_mut(),
+            )
+            .unwrap();
+        let id = entity.borrow().get_uuid();
+        (entity, id)
+    };
+
+    // head -> a -> (a1 -> a1x, a2), head -> b
+    let (a, a_id) = add("a", &head_binding);
+    let (a1, a1_id) = add("a1", &a);
+    let (a1x, a1x_id) = add("a1x", &a1);
+    let (_, a2_id) = add("a2", &a);
+    let (_, b_id) = add("b", &head_binding);
+    drop(tree);
+    let tree = tree_cell.borrow();
+
+    let head = head_binding.borrow();
+    let head_id = head.get_uuid();
+    assert_eq!(
+        tree.children_iter(&head).collect::<Vec<_>>(),
+        vec![a_id, b_id]
+    );
+    assert_eq!(
+        tree.descendants_iter(&head).collect::<Vec<_>>(),
+        vec![a_id, a1_id, a1x_id, a2_id, b_id]
+    );
+    assert_eq!(
+        tree.ancestors_iter(&a1x.borrow()).collect::<Vec<_>>(),
+        vec![a1_id, a_id, head_id]
+    );
+    assert_eq!(tree.ancestors_iter(&head).count(), 0);
+
+    // find stops as soon as the entity is found
+    let visited = Cell::new(0);
+    let found = tree
+        .descendants_iter(&head)
+        .inspect(|_| visited.set(visited.get() + 1))
+        .find(|id| *id == a1_id);
+    assert_eq!(found, Some(a1_id));
+    assert_eq!(visited.get(), 2);
+}
+
+#[test]
+fn test_get_descendents_id_unique() {
+    use std::collections::HashSet;
+
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    // 3 levels: 2 children, each with 2 children, each with 1 child
+    let mut expected = 0;
+    let mut level = vec![head_binding.clone()];
+    for child_count in [2, 2, 1] {
+        let mut next_level = vec![];
+        for parent in level.iter() {
+            for _ in 0..child_count {
+                let child = tree
+                    .add_entity_with_parent(
+                        "child",
+                        EntityType::Part(Part::default()),
+                        &mut parent.borrow_mut(),
+                    )
+                    .unwrap();
+                next_level.push(child);
+                expected += 1;
+            }
+        }
+        level = next_level;
+    }
+
+    let descendents = tree.get_descendents_id(&head_binding.borrow());
+    assert_eq!(expected, 2 + 4 + 4);
+    assert_eq!(descendents.len(), expected);
+    assert_eq!(descendents.iter().collect::<HashSet<_>>().len(), expected);
+    assert_eq!(tree.get_descendents(&head_binding.borrow()).len(), expected);
+}
+
+#[test]
+fn test_get_ancestors_id_chain() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    // head -> first -> second -> leaf
+    let mut chain = vec![head_binding.clone()];
+    for name in ["first", "second", "leaf"] {
+        let parent = chain.last().unwrap().clone();
+        let child = tree
+            .add_entity_with_parent(
+                name,
+                EntityType::Part(Part::default()),
+                &mut parent.borrow_mut(),
+            )
+            .unwrap();
+        chain.push(child);
+    }
+
+    let ids: Vec<_> = chain.iter().map(|e| e.borrow().get_uuid()).collect();
+    let ancestors = tree.get_ancestors_id(&chain[3].borrow());
+    assert_eq!(ancestors, vec![ids[2], ids[1], ids[0]]);
+
+    let names: Vec<String> = tree
+        .get_ancestors(&chain[3].borrow())
+        .iter()
+        .map(|e| e.get_name().to_string())
+        .collect();
+    assert_eq!(names, vec!["second", "first", "Game"]);
+
+    // a cycle doesn't loop forever
+    chain[1].borrow_mut().parent_id = Some(ids[2]);
+    assert_eq!(
+        tree.get_ancestors_id(&chain[3].borrow()),
+        vec![ids[2], ids[1]]
+    );
+}
+
+#[test]
+fn test_entity_new() {
+    let entity = Entity::new("part", Box::new(EntityType::Part(Part::default())));
+    let other = Entity::new("part", Box::new(EntityType::Part(Part::default())));
+
+    assert_eq!(entity.get_name(), "part");
+    assert_ne!(entity.get_uuid(), other.get_uuid());
+    assert!(matches!(entity.get_type(), EntityType::Part(_)));
+    assert!(entity.parent_id.is_none());
+    assert!(entity.children_id.is_empty());
+
+    let copy = entity.clone_shallow();
+    assert_ne!(copy.get_uuid(), entity.get_uuid());
+    assert!(matches!(copy.get_type(), EntityType::Part(_)));
+}
+
+#[test]
+fn test_find_by_tag() {
+    let (tree_cell, head) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mut ids = vec![];
+    for name in ["Goblin", "Coin", "Orc"] {
+        let entity = tree
+            .add_entity_with_parent(
+                name,
+                EntityType::Part(Part::default()),
+                &mut head.borrow_mut(),
+            )
+            .unwrap();
+        ids.push(entity.borrow().get_uuid());
+    }
+    for id in [ids[0], ids[2]] {
+        assert!(tree.get_entity_mut(id).unwrap().add_tag("enemy"));
+    }
+    assert!(!tree.get_entity_mut(ids[0]).unwrap().add_tag("enemy"));
+    tree.get_entity_mut(ids[1]).unwrap().add_tag("pickup");
+
+    let mut enemies = tree.find_by_tag("enemy");
+    enemies.sort();
+    let mut expected = vec![ids[0], ids[2]];
+    expected.sort();
+    assert_eq!(enemies, expected);
+    assert_eq!(tree.find_by_tag("pickup"), vec![ids[1]]);
+    assert!(tree.find_by_tag("boss").is_empty());
+
+    // tags are saved in scenes
+    let loaded = EntityTree::from_scene_string(&tree.to_scene_string().unwrap()).unwrap();
+    assert!(loaded.get_entity(ids[1]).unwrap().has_tag("pickup"));
+
+    assert!(tree.get_entity_mut(ids[2]).unwrap().remove_tag("enemy"));
+    assert!(!tree.get_entity(ids[2]).unwrap().has_tag("enemy"));
+    assert_eq!(tree.find_by_tag("enemy"), vec![ids[0]]);
+}
+
+#[test]
+fn test_find_by_path() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let level = tree
+        .add_entity_with_parent(
+            "Level",
+            EntityType::Part(Part::default()),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+    let player = tree
+        .add_entity_with_parent(
+            "Player",
+            EntityType::Part(Part::default()),
+            &mut level.borrow_mut(),
+        )
+        .unwrap();
+    let _ = tree
+        .add_entity_with_parent(
+            "Player",
+            EntityType::Part(Part::default()),
+            &mut level.borrow_mut(),
+        )
+        .unwrap();
+    let player_id = player.borrow().get_uuid();
+
+    assert_eq!(tree.find_by_path("Game/Level/Player"), Some(player_id)); // the first match
+    assert_eq!(
+        tree.find_by_path("Game/Level"),
+        Some(level.borrow().get_uuid())
+    );
+    assert_eq!(tree.find_by_path("Game/Level/Enemy"), None);
+    assert_eq!(tree.find_by_path("Game/Player"), None);
+    assert_eq!(tree.find_by_path("Level/Player"), None);
+    assert_eq!(tree.find_by_path(""), None);
+
+    assert_eq!(
+        tree.get_entity_by_name("Level").unwrap().get_uuid(),
+        level.borrow().get_uuid()
+    );
+    assert!(tree.get_entity_by_name("Enemy").is_none());
+}
+
+#[test]
+fn test_clone_subtree() {
+    use std::collections::HashSet;
+    use uuid::Uuid;
+
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    // head -> root -> (left, right)
+    let root = tree
+        .add_entity_with_parent(
+            "root",
+            EntityType::Part(Part::default()),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+    for name in ["left", "right"] {
+        let _ = tree
+            .add_entity_with_parent(
+                name,
+                EntityType::Part(Part::default()),
+                &mut root.borrow_mut(),
+            )
+            .unwrap();
+    }
+    let root_id = root.borrow().get_uuid();
+    let original: Vec<Uuid> = tree.descendants_iter(&root.borrow()).collect();
+
+    let copy_id = tree.clone_subtree(root_id).unwrap();
+    assert_ne!(copy_id, root_id);
+    assert_eq!(tree.entity_map.len(), 1 + 3 + 3);
+    assert_eq!(tree.parts.len(), 6);
+
+    let head = head_binding.borrow();
+    assert_eq!(head.children_id, vec![root_id, copy_id]);
+
+    let copy = tree.get_entity(copy_id).unwrap();
+    assert_eq!(copy.get_name(), "root");
+    assert_eq!(copy.parent_id, Some(head.get_uuid()));
+
+    let copied: Vec<Uuid> = tree.descendants_iter(&copy).collect();
+    let names: Vec<String> = copied
+        .iter()
+        .map(|id| tree.get_entity(*id).unwrap().get_name().to_string())
+        .collect();
+    assert_eq!(names, vec!["left", "right"]);
+    for id in copied.iter() {
+        assert_eq!(tree.get_entity(*id).unwrap().parent_id, Some(copy_id));
+    }
+
+    let unique: HashSet<Uuid> = original.iter().chain(copied.iter()).copied().collect();
+    assert_eq!(unique.len(), 4);
+
+    drop(copy);
+    assert!(tree.clone_subtree(Uuid::new_v4()).is_none());
+}
+
+#[test]
+fn test_set_parent_rejects_cycles() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    // head -> a -> b -> c
+    let a = tree
+        .add_entity_with_parent(
+            "a",
+            EntityType::Part(Part::default()),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+    let b = tree
+        .add_entity_with_parent("b", EntityType::Part(Part::default()), &mut a.borrow_mut())
+        .unwrap();
+    let c = tree
+        .add_entity_with_parent("c", EntityType::Part(Part::default()), &mut b.borrow_mut())
+        .unwrap();
+
+    let result = tree.set_parent(&mut a.borrow_mut(), Some(&mut c.borrow_mut()));
+    assert_eq!(result, Err("can't parent to descendent"));
+    let result = tree.set_parent(&mut a.borrow_mut(), Some(&mut b.borrow_mut()));
+    assert_eq!(result, Err("can't parent to descendent"));
+    assert_eq!(a.borrow().parent_id, Some(head_binding.borrow().get_uuid()));
+
+    // the former parent doesn't list the child, reparenting doesn't panic
+    let c_id = c.borrow().get_uuid();
+    b.borrow_mut().children_id.clear();
+    assert!(
+        tree.set_parent(&mut c.borrow_mut(), Some(&mut a.borrow_mut()))
+            .is_ok()
+    );
+    assert_eq!(c.borrow().parent_id, Some(a.borrow().get_uuid()));
+    assert!(a.borrow().children_id.contains(&c_id));
+
+    // unparenting removes the entity from it's parent
+    assert!(tree.set_parent(&mut c.borrow_mut(), None).is_ok());
+    assert!(c.borrow().parent_id.is_none());
+    assert!(!a.borrow().children_id.contains(&c_id));
+}
+
+#[test]
+fn test_try_get_entity() {
+    use entities::entity_tree::EntityAccessError;
+
+    let (tree_cell, head_binding) = create_tree();
+    let tree = tree_cell.borrow();
+    let head_id = head_binding.borrow().get_uuid();
+
+    {
+        let _held = tree.try_get_entity_mut(head_id).unwrap();
+        assert_eq!(
+            tree.try_get_entity(head_id).err(),
+            Some(EntityAccessError::Borrowed(head_id))
+        );
+        assert!(tree.try_get_entity_mut(head_id).is_err());
+    }
+
+    {
+        let _first = tree.try_get_entity(head_id).unwrap();
+        assert!(tree.try_get_entity(head_id).is_ok());
+        assert!(tree.try_get_entity_mut(head_id).is_err());
+    }
+
+    let missing = uuid::Uuid::new_v4();
+    assert_eq!(
+        tree.try_get_entity(missing).err(),
+        Some(EntityAccessError::NotFound(missing))
+    );
+}
+
+#[test]
+fn test_scene_round_trip() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mut part_type = Part::default();
+    part_type
+        .load_mesh_from_file("assets/meshs/plane.mesh")
+        .unwrap();
+    part_type.set_position(Vector3::new(1.0, -2.5, 0.1));
+    part_type.set_rotation(Vector3::new(0.0, 45.0, 0.0));
+    part_type.color = Color3::from_hex(0x336699);
+
+    let base = tree
+        .add_entity_with_parent(
+            "Level",
+            EntityType::Part(part_type),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+    let mut player_type = Part::default();
+    let player_orientation = ultraviolet::Rotor3::from_rotation_xz(0.5);
+    player_type.set_orientation(player_orientation);
+    let _ = tree
+        .add_entity_with_parent(
+            "Player",
+            EntityType::Part(player_type),
+            &mut base.borrow_mut(),
+        )
+        .unwrap();
+    let _ = tree.add_main_camera(Camera::new_orthographic(8.0, 0.5, 50.0));
+
+    let scene = tree.to_scene_string().unwrap();
+    let loaded = EntityTree::from_scene_string(&scene).unwrap();
+    assert_eq!(loaded.to_scene_string().unwrap(), scene);
+    assert_eq!(loaded.head, tree.head);
+    assert_eq!(loaded.main_camera, tree.main_camera);
+    assert_eq!(loaded.entity_map.len(), 4);
+    assert_eq!(loaded.parts.len(), 2);
+
+    let level_id = loaded.find_by_path("Game/Level").unwrap();
+    assert_eq!(level_id, base.borrow().get_uuid());
+    let player = loaded
+        .get_entity(loaded.find_by_path("Game/Level/Player").unwrap())
+        .unwrap();
+    let EntityType::Part(player_part) = player.get_type() else {
+        panic!("Player isn't a part");
+    };
+    assert_eq!(
+        player_part.get_orientation(),
+        player_orientation.normalized()
+    );
+
+    let level = loaded.get_entity(level_id).unwrap();
+    let EntityType::Part(part) = level.get_type() else {
+        panic!("Level isn't a part");
+    };
+    assert_eq!(part.get_position(), Vector3::new(1.0, -2.5, 0.1));
+    assert_eq!(part.get_rotation(), Vector3::new(0.0, 45.0, 0.0));
+    assert_eq!(part.color, Color3::from_hex(0x336699));
+    assert_eq!(part.get_mesh().indices.len(), 6);
+    assert_eq!(part.get_mesh_path(), Some("assets/meshs/plane.mesh"));
+
+    // regenerating the IDs keeps the hierarchy
+    let copy = EntityTree::from_scene_string_with(&scene, true).unwrap();
+    assert_ne!(copy.head, tree.head);
+    assert!(!copy.entity_map.contains_key(&level_id));
+    let copy_head = copy.get_head().unwrap();
+    assert_eq!(copy.get_descendents_id(&copy_head.borrow()).len(), 3);
+    assert!(copy.find_by_path("Game/Level/Player").is_some());
+
+    assert!(EntityTree::from_scene_string(":Entity\nname missing-uuid").is_err());
+    assert!(EntityTree::from_scene_string(":Mesh\n").is_err());
+
+    // a borrowed entity isn't silently left out
+    let _borrow = base.borrow_mut();
+    assert_eq!(
+        tree.to_scene_string().err(),
+        Some(entities::entity_tree::EntityAccessError::Borrowed(level_id))
+    );
+}
+
+#[test]
+fn test_mesh_load_obj() {
+    use datatypes::vectors::Vector2;
+
+    let triangle =
+        Mesh::load_obj("# a triangle\no triangle\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1 2 3\n")
+            .unwrap();
+    assert_eq!(triangle.vertices.len(), 3);
+    assert_eq!(triangle.indices, vec![0, 1, 2]);
+    assert_eq!(
+        triangle.vertices[1].get_position(),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+
+    // a quad with texture coordinates, triangulated into 2 triangles
+    let quad = Mesh::load_obj(
+        "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+         vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+         g quad\nf 1/1/1 2/2/1 3/3/1 4/4/1\n",
+    )
+    .unwrap();
+    assert_eq!(quad.vertices.len(), 4);
+    assert_eq!(quad.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(quad.vertices[2].get_tex_coord(), Vector2::new(1.0, 1.0));
+
+    // negative indices, and the same position with 2 texture coordinates
+    let relative = Mesh::load_obj(
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 1\nf -3/-2 -2/-2 -1/-2\nf -3/-1 -1//1 -2\n",
+    )
+    .unwrap();
+    assert_eq!(relative.vertices.len(), 6);
+    assert_eq!(relative.indices, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(relative.vertices[0].get_position(), Vector3::zero());
+    assert_eq!(relative.vertices[3].get_tex_coord(), Vector2::new(1.0, 1.0));
+
+    assert!(Mesh::load_obj("v 0 0 0\nf 1 2 3\n").is_err());
+    assert!(Mesh::load_obj("v 0 0 0\nv 1 0 0\nf 1 2\n").is_err());
+    assert!(Mesh::load_obj("v 0 zero 0\n").is_err());
+}
+
+#[test]
+fn test_mesh_load_mismatched_sections() {
+    let mesh = Mesh::load_mesh(include_str!("../assets/meshs/plane.mesh")).unwrap();
+    assert_eq!(mesh.vertices[0].get_color(), Color3::red());
+    assert_eq!(mesh.vertices[2].get_color(), Color3::blue());
+
+    let vertices = ":Vertices\n0 0 0\n1 0 0\n0 1 0\n\n:Indices\n0 1 2\n";
+    let mesh = Mesh::load_mesh(vertices).unwrap();
+    assert_eq!(mesh.vertices[1].get_color(), Color3::white());
+
+    let too_few_coords = format!("{vertices}\n:TexCoord\n0.0 0.0\n1.0 0.0\n");
+    let err = Mesh::load_mesh(&too_few_coords).unwrap_err();
+    assert!(matches!(
+        err,
+        MeshParseError::MismatchedLength {
+            section: MeshSectionType::TexCoord,
+            expected: 3,
+            got: 2
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Region TexCoord has 2 values, expected 3 (one per vertex) or none"
+    );
+
+    let too_many_colors = format!("{vertices}\n:Color\n0 0 0\n0 0 0\n0 0 0\n0 0 0\n");
+    assert!(matches!(
+        Mesh::load_mesh(&too_many_colors),
+        Err(MeshParseError::MismatchedLength {
+            section: MeshSectionType::Color,
+            expected: 3,
+            got: 4
+        })
+    ));
+
+    // each face has it's own vertices, so the texcoords and colors match the vertex count
+    let cube = Mesh::load_mesh(include_str!("../assets/meshs/cube.mesh")).unwrap();
+    assert_eq!(cube.vertices.len(), 24);
+    assert!(Mesh::load_mesh(include_str!("../assets/meshs/triangle.mesh")).is_ok());
+}
+
+#[test]
+fn test_mesh_load_trailing_data() {
+    let with_whitespace =
+        ":Vertices\n0 0 0\n1 0 0\n0 1 0\n:Indices\n0 1 2\n:TexCoord\n0 0\n1 0\n0 1\n";
+    let without_whitespace =
+        ":Vertices\n0 0 0\n1 0 0\n0 1 0\n:Indices\n0 1 2\n:TexCoord\n0 0\n1 0\n0 1";
+    let blank_lines = ":Vertices\n\n0 0 0\n\n1 0 0\n0 1 0\n\n:Indices\n0 1\n\n2";
+
+    for data in [with_whitespace, without_whitespace, blank_lines] {
+        let mesh = Mesh::load_mesh(data).unwrap();
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+    }
+    let mesh = Mesh::load_mesh(without_whitespace).unwrap();
+    assert_eq!(
+        mesh.vertices[2].get_tex_coord(),
+        datatypes::vectors::Vector2::new(0.0, 1.0)
+    );
+
+    let incomplete = Mesh::load_mesh(":Vertices\n0 0 0\n1 0");
+    assert!(matches!(
+        incomplete,
+        Err(MeshParseError::IncompleteValue {
+            section: MeshSectionType::Vertices,
+            expected: 3,
+            got: 2
+        })
+    ));
+}
+
+#[test]
+fn test_mesh_compute_normals() {
+    let coord = datatypes::vectors::Vector2::zero();
+    // counter-clockwise, when looking down -Z
+    let mut quad = Mesh::with_set_data(
+        vec![
+            VertexData::new(Vector3::new(-0.5, -0.5, 0.0), coord),
+            VertexData::new(Vector3::new(0.5, -0.5, 0.0), coord),
+            VertexData::new(Vector3::new(0.5, 0.5, 0.0), coord),
+            VertexData::new(Vector3::new(-0.5, 0.5, 0.0), coord),
+        ],
+        vec![0, 1, 2, 0, 2, 3],
+    );
+    quad.compute_normals();
+    for vertex in quad.vertices.iter() {
+        assert!((vertex.get_normal() - Vector3::forward()).length() < 1e-6);
+    }
+
+    quad.compute_flat_normals();
+    assert_eq!(quad.vertices.len(), 6);
+    assert_eq!(quad.indices, vec![0, 1, 2, 3, 4, 5]);
+    for vertex in quad.vertices.iter() {
+        assert!((vertex.get_normal() - Vector3::forward()).length() < 1e-6);
+    }
+
+    // a corner of a cube, the smooth normal points away from the corner
+    let mut corner = Mesh::with_set_data(
+        vec![
+            VertexData::new(Vector3::zero(), coord),
+            VertexData::new(Vector3::right(), coord),
+            VertexData::new(Vector3::up(), coord),
+            VertexData::new(Vector3::forward(), coord),
+        ],
+        vec![0, 2, 1, 0, 1, 3, 0, 3, 2],
+    );
+    corner.compute_normals();
+    let expected = Vector3::new(-1.0, -1.0, -1.0).get_unit();
+    assert!((corner.vertices[0].get_normal() - expected).length() < 1e-5);
+}
+
+#[test]
+fn test_mesh_flat_vertices() {
+    let mut cube = Mesh::load_mesh(include_str!("../assets/meshs/cube.mesh")).unwrap();
+    // the faces share texcoords along the seams, the normals keep them split
+    cube.compute_normals();
+    assert_eq!(cube.vertices.len(), 24);
+    let flat = cube.to_flat_vertices();
+    assert_eq!(flat.len(), cube.indices.len());
+
+    let indexed = Mesh::from_flat_vertices(flat.clone());
+    assert_eq!(indexed.vertices.len(), cube.vertices.len());
+    assert_eq!(indexed.indices.len(), cube.indices.len());
+
+    let round_trip = indexed.to_flat_vertices();
+    assert_eq!(round_trip.len(), flat.len());
+    for (a, b) in round_trip.iter().zip(flat.iter()) {
+        assert_eq!(a.to_internal(), b.to_internal());
+    }
+
+    // vertices closer than the epsilon are merged
+    let mut nudged = flat[0];
+    nudged.set_position(flat[0].get_position() + Vector3::one() * (Mesh::DEDUP_EPSILON * 0.1));
+    let merged = Mesh::from_flat_vertices(vec![flat[0], nudged, flat[1]]);
+    assert_eq!(merged.vertices.len(), 2);
+    assert_eq!(merged.indices, vec![0, 0, 1]);
+}
+
+#[test]
+fn test_mesh_validate() {
+    let out_of_range = ":Vertices\n0 0 0 1 0 0 0 1 0\n:Indices\n0 1 3\n";
+    let err = Mesh::load_mesh(out_of_range).unwrap_err().to_string();
+    assert!(err.contains("index 3 at position 2"), "{err}");
+
+    let not_triangles = ":Vertices\n0 0 0 1 0 0 0 1 0\n:Indices\n0 1 2 0\n";
+    let err = Mesh::load_mesh(not_triangles).unwrap_err().to_string();
+    assert!(err.contains("multiple of 3"), "{err}");
+
+    let cube = Mesh::load_mesh(include_str!("../assets/meshs/cube.mesh")).unwrap();
+    assert!(cube.validate().is_ok());
+    assert!(cube.to_wireframe().validate().is_ok());
+}
+
+#[test]
+fn test_mesh_string_round_trip() {
+    use datatypes::{color::Color3, vectors::Vector2};
+    use mesh::VertexData;
+
+    let mut mesh = Mesh::load_mesh(include_str!("../assets/meshs/cube.mesh")).unwrap();
+    mesh.vertices[0] = VertexData::with_color(
+        Vector3::new(-1.25, 0.1, 3.0),
+        Color3 {
+            r: 1.0,
+            g: 51.0 / 255.0,
+            b: 0.0,
+        },
+        Vector2::new(0.75, 0.3),
+    );
+
+    let loaded = Mesh::load_mesh(&mesh.to_mesh_string()).unwrap();
+    assert_eq!(loaded.indices, mesh.indices);
+    assert_eq!(loaded.vertices.len(), mesh.vertices.len());
+    for (a, b) in loaded.vertices.iter().zip(mesh.vertices.iter()) {
+        assert_eq!(a.get_position(), b.get_position());
+        assert_eq!(a.get_tex_coord(), b.get_tex_coord());
+        let (ca, cb) = (a.get_color(), b.get_color());
+        assert!(
+            (ca.r - cb.r).abs() < 1e-6 && (ca.g - cb.g).abs() < 1e-6 && (ca.b - cb.b).abs() < 1e-6
+        );
+    }
+
+    let path = std::env::temp_dir().join("akhiok_test_mesh_string_round_trip.mesh");
+    let path = path.to_str().unwrap();
+    mesh.save_mesh_to_file(path).unwrap();
+    let from_file = Mesh::load_mesh_from_file(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(from_file.indices, mesh.indices);
+}
+
+#[test]
+fn test_mesh_transform_and_merge() {
+    use datatypes::vectors::Vector2;
+    use mesh::VertexData;
+
+    let triangle = Mesh::with_set_data(
+        vec![
+            VertexData::new(Vector3::new(0.0, 0.0, 0.0), Vector2::zero()),
+            VertexData::new(Vector3::new(1.0, 0.0, 0.0), Vector2::zero()),
+            VertexData::new(Vector3::new(0.0, 1.0, 0.0), Vector2::zero()),
+        ],
+        vec![0, 1, 2],
+    );
+
+    let mut moved = triangle.clone();
+    moved.compute_normals();
+    moved.transform(ultraviolet::Mat4::from_translation(ultraviolet::Vec3::new(
+        1.0, 0.0, 0.0,
+    )));
+    for (a, b) in moved.vertices.iter().zip(triangle.vertices.iter()) {
+        assert_eq!(a.get_position(), b.get_position() + Vector3::right());
+        // translating doesn't change the normals
+        assert_eq!(a.get_normal(), Vector3::forward());
+    }
+
+    // flattening the mesh can't be inverted, but doesn't produce NaN normals
+    let mut flattened = moved.clone();
+    flattened.transform(ultraviolet::Mat4::from_nonuniform_scale(
+        ultraviolet::Vec3::new(1.0, 1.0, 0.0),
+    ));
+    for vertex in flattened.vertices.iter() {
+        assert!(vertex.get_normal().iter().all(f32::is_finite));
+    }
+
+    let mut merged = triangle.clone();
+    merged.merge(&moved);
+    assert_eq!(merged.vertices.len(), 6);
+    assert_eq!(merged.indices, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(merged.vertices[3].get_position(), Vector3::right());
+    assert!(merged.validate().is_ok());
+}
+
+#[test]
+fn test_mesh_bounding_box() {
+    use entities::{
+        traits::object_3d::{Object3D, Object3DSize},
+        types::part_type::Part,
+    };
+
+    let half = Vector3::one() * 0.5;
+    let cube = Mesh::load_mesh(include_str!("../assets/meshs/cube.mesh")).unwrap();
+    assert_eq!(cube.bounding_box(), (-half, half));
+    assert_eq!(
+        Mesh::default().bounding_box(),
+        (Vector3::zero(), Vector3::zero())
+    );
+
+    let mut part = Part::new(&cube);
+    assert_eq!(part.world_bounding_box(), (-half, half));
+
+    part.set_size(Vector3::new(2.0, 4.0, 2.0));
+    part.set_position(Vector3::new(1.0, 0.0, 0.0));
+    let (min, max) = part.world_bounding_box();
+    assert!(
+        (min - Vector3::new(0.0, -2.0, -1.0)).length() < 1e-5,
+        "{min:?}"
+    );
+    assert!(
+        (max - Vector3::new(2.0, 2.0, 1.0)).length() < 1e-5,
+        "{max:?}"
+    );
+}
+
+#[test]
+fn test_mesh_raycast() {
+    use datatypes::{ray::Ray, vectors::Vector2};
+    use entities::{traits::object_3d::Object3D, types::part_type::Part};
+    use mesh::VertexData;
+
+    // a quad on the XY plane, facing +Z
+    let quad = Mesh::with_set_data(
+        vec![
+            VertexData::new(Vector3::new(-1.0, -1.0, 0.0), Vector2::zero()),
+            VertexData::new(Vector3::new(1.0, -1.0, 0.0), Vector2::zero()),
+            VertexData::new(Vector3::new(1.0, 1.0, 0.0), Vector2::zero()),
+            VertexData::new(Vector3::new(-1.0, 1.0, 0.0), Vector2::zero()),
+        ],
+        vec![0, 1, 2, 0, 2, 3],
+    );
+
+    let down_z = Ray::new(Vector3::new(0.25, 0.5, 5.0), -Vector3::forward());
+    let distance = quad.raycast(&down_z).unwrap();
+    assert!((distance - 5.0).abs() < 1e-5);
+    assert!((down_z.at(distance) - Vector3::new(0.25, 0.5, 0.0)).length() < 1e-5);
+
+    let away = Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::forward());
+    assert_eq!(quad.raycast(&away), None);
+    let beside = Ray::new(Vector3::new(3.0, 0.0, 5.0), -Vector3::forward());
+    assert_eq!(quad.raycast(&beside), None);
+
+    let mut part = Part::new(&quad);
+    part.set_position(Vector3::new(0.0, 0.0, -2.0));
+    let distance = part.raycast(&down_z).unwrap();
+    assert!((distance - 7.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_object_3d_orientation() {
+    use ultraviolet::{Rotor3, Vec3};
+
+    // a 90 degree yaw, turning +Z to +X
+    let mut euler = Part::default();
+    euler.set_rotation(Vector3::new(0.0, 90.0, 0.0));
+    let mut rotor = Part::default();
+    rotor.set_orientation(Rotor3::from_rotation_between(
+        Vec3::unit_z(),
+        Vec3::unit_x(),
+    ));
+
+    let front_of = |part: &Part| Vector3::from(part.get_orientation() * Vec3::unit_z());
+    assert!((front_of(&euler) - Vector3::right()).length() < 1e-5);
+    assert!((front_of(&rotor) - front_of(&euler)).length() < 1e-5);
+    for (a, b) in euler.transform.cols.iter().zip(rotor.transform.cols.iter()) {
+        assert!((*a - *b).mag() < 1e-5);
+    }
+
+    // the euler rotation is used again, after it's set
+    rotor.set_rotation(Vector3::zero());
+    assert_eq!(front_of(&rotor), Vector3::forward());
+}
+
+#[test]
+fn test_object_3d_vectors_match_transform() {
+    let mut part = Part::default();
+    part.set_rotation(Vector3::new(30.0, 45.0, 0.0));
+    part.update_vectors();
+
+    let transform = part.calculate_transform();
+    let column = |i: usize| Vector3::from(transform.cols[i].truncated());
+    assert!((part.get_right() - column(0)).length() < 1e-5);
+    assert!((part.get_up() - column(1)).length() < 1e-5);
+    assert!((part.get_front() + column(2)).length() < 1e-5);
+
+    // the front is -Z, a positive pitch looks up, a positive yaw turns towards -X
+    assert!(part.get_front().y > 0.0);
+    assert!(part.get_front().x < 0.0);
+    assert!(part.get_front().z < 0.0);
+}
+
+#[test]
+fn test_world_transform() {
+    let (tree_cell, head_binding) = create_tree();
+    let mut tree = tree_cell.borrow_mut();
+
+    let mut parent = Part::default();
+    parent.set_position(Vector3::new(0.0, 5.0, 0.0));
+    let parent_binding = tree
+        .add_entity_with_parent(
+            "parent",
+            EntityType::Part(parent),
+            &mut head_binding.borrow_mut(),
+        )
+        .unwrap();
+
+    let mut child = Part::default();
+    child.set_position(Vector3::new(1.0, 0.0, 0.0));
+    let child_id = tree
+        .add_entity_with_parent(
+            "child",
+            EntityType::Part(child),
+            &mut parent_binding.borrow_mut(),
+        )
+        .unwrap()
+        .borrow()
+        .get_uuid();
+
+    let world = tree.world_transform(child_id);
+    let position = Vector3::from(world.transform_point3(ultraviolet::Vec3::zero()));
+    assert!((position - Vector3::new(1.0, 5.0, 0.0)).length() < 1e-5);
+
+    // the head isn't a part, so it's the identity
+    let head_id = head_binding.borrow().get_uuid();
+    assert_eq!(tree.world_transform(head_id), ultraviolet::Mat4::identity());
+}
+
+#[test]
+fn test_object_3d_helpers() {
+    use entities::traits::object_3d::Object3DSize;
+
+    let mut part = Part::default();
+    part.translate(Vector3::new(1.0, 2.0, 0.0));
+    part.translate(Vector3::new(1.0, 0.0, -3.0));
+    assert_eq!(part.get_position(), Vector3::new(2.0, 2.0, -3.0));
+    assert_eq!(part.transform, part.calculate_transform());
+
+    // a yaw of 90 degrees turns the front to -X
+    part.rotate(Vector3::new(0.0, 90.0, 0.0));
+    part.move_forward(2.0);
+    assert!((part.get_position() - Vector3::new(0.0, 2.0, -3.0)).length() < 1e-5);
+    part.move_up(-2.0);
+    assert!((part.get_position() - Vector3::new(0.0, 0.0, -3.0)).length() < 1e-5);
+
+    part.scale_by(Vector3::new(2.0, 3.0, 1.0));
+    part.scale_by(Vector3::new(2.0, 1.0, 1.0));
+    assert_eq!(part.get_size(), Vector3::new(4.0, 3.0, 1.0));
+    assert_eq!(part.transform, part.calculate_transform());
+}
+
+#[test]
+fn test_normal_matrix() {
+    use entities::traits::object_3d::{Object3DSize, normal_matrix};
+    use ultraviolet::{Mat4, Vec3};
+
+    let mut part = Part::default();
+    part.set_size(Vector3::new(2.0, 1.0, 1.0));
+
+    // the normal of a 45 degree slope, which is stretched along X
+    let normal = Vec3::new(1.0, 1.0, 0.0).normalized();
+    let naive = part.transform.transform_vec3(normal).normalized();
+    let corrected = (part.get_normal_matrix() * normal).normalized();
+
+    // the stretched slope's tangent, the normal must be perpendicular to it
+    let tangent = part.transform.transform_vec3(Vec3::new(1.0, -1.0, 0.0));
+    assert!(naive.dot(tangent).abs() > 0.1);
+    assert!(corrected.dot(tangent).abs() < 1e-5);
+
+    // a size of zero isn't invertible
+    let flat = Mat4::from_nonuniform_scale(Vec3::new(1.0, 0.0, 1.0));
+    assert_eq!(normal_matrix(&flat).cols[1], Vec3::zero());
+}
+
+#[test]
+fn test_object_3d_derive() {
+    use derive_akhoik_ge::Object3D;
+    use entities::traits::object_3d::*;
+    use ultraviolet::{Mat4, Rotor3};
+
+    // the fields the derive macro expects
+    #[derive(Object3D)]
+    struct Marker {
+        transform: Mat4,
+        front: Vector3,
+        right: Vector3,
+        up: Vector3,
+        position: Vector3,
+        rotation: Vector3,
+        orientation: Option<Rotor3>,
+    }
+
+    let mut marker = Marker {
+        transform: Mat4::identity(),
+        front: -Vector3::forward(),
+        right: Vector3::right(),
+        up: Vector3::up(),
+        position: Vector3::zero(),
+        rotation: Vector3::zero(),
+        orientation: None,
+    };
+
+    // setting the rotation updates the direction vectors
+    marker.set_rotation(Vector3::new(0.0, 90.0, 0.0));
+    assert!((marker.get_front() + Vector3::right()).length() < 1e-5);
+    assert!((marker.get_right() + Vector3::forward()).length() < 1e-5);
+    assert_eq!(marker.transform, marker.calculate_transform());
+
+    marker.set_orientation(Rotor3::identity());
+    assert!((marker.get_front() + Vector3::forward()).length() < 1e-5);
+}
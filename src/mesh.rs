@@ -1,7 +1,8 @@
 //! Used for mesh creation and definition.
 
-use core::fmt;
+use core::fmt::{self, Write};
 use std::{
+    collections::{HashMap, HashSet},
     default::Default,
     error::Error,
     fs,
@@ -9,26 +10,60 @@ use std::{
     vec::*,
 };
 
-use crate::datatypes::vectors::*;
+use ogl33::{GL_LINES, GL_POINTS, GL_TRIANGLES};
+use ultraviolet::{Mat4, Vec3};
+
+use crate::{
+    datatypes::{color::Color3, ray::Ray, vectors::*},
+    entities::traits::object_3d::normal_matrix,
+    gl_helper::{AttributeType, VertexLayout},
+    texture::Texture,
+};
 
 /// An array of floats used in rendering vertices.
-pub type VertexDataInternal = [f32; 5];
+pub type VertexDataInternal = [f32; 11];
 
 /// `VertexData` used to construct points on meshes, containing:
 /// - `position` (the first 3 fields),
+/// - `color` (the next 3 fields)
 /// - `tex_coord` (the next 2 fields)
+/// - `normal` (the last 3 fields)
 #[derive(Clone, Copy, Debug, Default)]
-pub struct VertexData(f32, f32, f32, f32, f32);
+pub struct VertexData(f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32);
 impl VertexData {
-    /// Creates a new vertex.
+    /// Creates a new vertex, with a white vertex color.
     /// # Arguements:
     /// - `position`: the vertex's position
-    /// - `color` - the vertex color
     /// - `tex_coord` - the UV coordinates of the texture
     /// # Returns
     /// `VertexData`
     pub fn new(position: Vector3, tex_coord: Vector2) -> Self {
-        Self(position.x, position.y, position.z, tex_coord.x, tex_coord.y)
+        Self::with_color(position, Color3::white(), tex_coord)
+    }
+
+    /// Creates a new vertex with a vertex color.
+    /// # Arguements:
+    /// - `position`: the vertex's position
+    /// - `color` - the vertex color
+    /// - `tex_coord` - the UV coordinates of the texture
+    /// # Returns
+    /// `VertexData`
+    /// # Note
+    /// The normal is zero, see `Mesh::compute_normals`.
+    pub fn with_color(position: Vector3, color: Color3, tex_coord: Vector2) -> Self {
+        Self(
+            position.x,
+            position.y,
+            position.z,
+            color.r,
+            color.g,
+            color.b,
+            tex_coord.x,
+            tex_coord.y,
+            0.0,
+            0.0,
+            0.0,
+        )
     }
 
     /// Gets the position of the vertex.
@@ -47,28 +82,97 @@ impl VertexData {
         self.2 = pos.z;
     }
 
+    /// Gets the color of the vertex.
+    /// # Returns
+    /// The vertex's color
+    pub fn get_color(&self) -> Color3 {
+        Color3 {
+            r: self.3,
+            g: self.4,
+            b: self.5,
+        }
+    }
+
+    /// Sets the color of the vertex.
+    /// # Arguements
+    /// - `color`: the new color
+    pub fn set_color(&mut self, color: Color3) {
+        self.3 = color.r;
+        self.4 = color.g;
+        self.5 = color.b;
+    }
+
     /// Gets the texture coordinate of the vertex.
     /// # Returns
     /// The vertex's texture coordinate
     pub fn get_tex_coord(&self) -> Vector2 {
-        Vector2::new(self.3, self.4)
+        Vector2::new(self.6, self.7)
     }
 
     /// Sets the texture coordinate of the vertex.
     /// # Arguements
     /// - `coord`: The new texture coordinate
     pub fn set_tex_coord(&mut self, coord: Vector2) {
-        self.3 = coord.x;
-        self.4 = coord.y;
+        self.6 = coord.x;
+        self.7 = coord.y;
+    }
+
+    /// Gets the normal of the vertex.
+    /// # Returns
+    /// The vertex's normal
+    pub fn get_normal(&self) -> Vector3 {
+        Vector3::new(self.8, self.9, self.10)
+    }
+
+    /// Sets the normal of the vertex.
+    /// # Arguements
+    /// - `normal`: The new normal (normalised)
+    pub fn set_normal(&mut self, normal: Vector3) {
+        self.8 = normal.x;
+        self.9 = normal.y;
+        self.10 = normal.z;
     }
 
     /// Converts the vertex into an array of `f32`.
     /// # Returns
     /// A `f32` array with the following elements:
     /// - `position` (3),
+    /// - `color` (3),
     /// - `tex_coord` (2)
+    /// - `normal` (3)
     pub fn to_internal(&self) -> VertexDataInternal {
-        [self.0, self.1, self.2, self.3, self.4]
+        [
+            self.0, self.1, self.2, self.3, self.4, self.5, self.6, self.7, self.8, self.9, self.10,
+        ]
+    }
+
+    /// Gets the layout of `VertexDataInternal`, used by the vertex shader:
+    /// - location 0: `position`
+    /// - location 1: `color`
+    /// - location 2: `tex_coord`
+    /// - location 3: `normal`
+    /// # Returns
+    /// The vertex layout
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new()
+            .push(0, 3, AttributeType::Float)
+            .push(1, 3, AttributeType::Float)
+            .push(2, 2, AttributeType::Float)
+            .push(3, 3, AttributeType::Float)
+    }
+
+    /// Linearly interpolates every field of the vertex.
+    /// # Note
+    /// The normal isn't normalised afterwards.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let (a, b) = (self.to_internal(), other.to_internal());
+        let mut out = [0.0_f32; 11];
+        for (i, value) in out.iter_mut().enumerate() {
+            *value = a[i] + (b[i] - a[i]) * t;
+        }
+        Self(
+            out[0], out[1], out[2], out[3], out[4], out[5], out[6], out[7], out[8], out[9], out[10],
+        )
     }
 }
 
@@ -81,6 +185,8 @@ pub enum MeshSectionType {
     Indices,
     /// Texture Coordinates
     TexCoord,
+    /// Vertex Colors
+    Color,
     /// None
     None,
 }
@@ -98,23 +204,37 @@ impl MeshSectionType {
             Mesh::VERTICES_SECTION_NAME => MeshSectionType::Vertices,
             Mesh::INDICES_SECTION_NAME => MeshSectionType::Indices,
             Mesh::TEXCOORD_SECTION_NAME => MeshSectionType::TexCoord,
+            Mesh::COLOR_SECTION_NAME => MeshSectionType::Color,
             _ => MeshSectionType::None,
         }
     }
 }
 
 macro_rules! section_to_raw_fn {
-    ($current_section:expr, $section_name:expr, $data:expr, $pos_data:expr, $ind_data:expr, $texcoord_data:expr) => {{
+    ($current_section:expr, $section_name:expr, $data:expr, $pos_data:expr, $ind_data:expr, $texcoord_data:expr, $color_data:expr) => {{
         match $current_section {
             MeshSectionType::Vertices => Self::load_raw_vertices($data.as_str(), &mut $pos_data),
             MeshSectionType::Indices => Self::load_raw_indices($data.as_str(), &mut $ind_data),
             MeshSectionType::TexCoord => {
                 Self::load_raw_texcoord($data.as_str(), &mut $texcoord_data)
             }
+            MeshSectionType::Color => Self::load_raw_color($data.as_str(), &mut $color_data),
             _ => Err(MeshParseError::InvalidSectionType($section_name.clone())),
         }
     }};
 }
+/// The primitive that the indices of a mesh are grouped into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshTopology {
+    /// GL_TRIANGLES: every 3 indices is a triangle
+    #[default]
+    Triangles = GL_TRIANGLES as isize,
+    /// GL_LINES: every 2 indices is a line
+    Lines = GL_LINES as isize,
+    /// GL_POINTS: every index is a point
+    Points = GL_POINTS as isize,
+}
+
 /// A collection of veretices and indices that defines the shape of  a object's surface,
 #[derive(Clone, Debug, Default)]
 pub struct Mesh {
@@ -124,6 +244,12 @@ pub struct Mesh {
     /// # Example
     /// `[0, 1, 3, 1, 2, 3]`
     pub indices: Vec<u32>,
+    /// How the indices are grouped when rendering.
+    pub topology: MeshTopology,
+    /// The tangent of each vertex, used for normal mapping.
+    /// # Note
+    /// Empty until `compute_tangents` is called.
+    pub tangents: Vec<Vector3>,
 }
 impl Mesh {
     /// Creates a new `Mesh` with the `vertices` and `indices` preset.
@@ -133,7 +259,12 @@ impl Mesh {
     /// # Returns
     /// A mesh with the vertices and indices set.
     pub fn with_set_data(vertices: Vec<VertexData>, indices: Vec<u32>) -> Self {
-        Self { vertices, indices }
+        Self {
+            vertices,
+            indices,
+            topology: MeshTopology::Triangles,
+            tangents: vec![],
+        }
     }
 
     /// Create a new `Mesh` with the vertices and indices set.
@@ -146,21 +277,44 @@ impl Mesh {
         Self {
             vertices: Vec::with_capacity(v_size),
             indices: Vec::with_capacity(i_size),
+            topology: MeshTopology::Triangles,
+            tangents: vec![],
         }
     }
 
+    /// Creates a single triangle that covers the entire screen, in normalised device coordinates.
+    /// # Returns
+    /// A mesh with 3 vertices
+    /// # Note
+    /// - The triangle is oversized and GL clips the excess, this avoids the overdraw along the
+    ///   diagonal seam of a quad made of 2 triangles.
+    /// - The texture coordinates are (0, 0) at the bottom-left and (1, 1) at the top-right of the
+    ///   screen.
+    pub fn fullscreen_triangle() -> Self {
+        Self::with_set_data(
+            vec![
+                VertexData::new(Vector3::new(-1.0, -1.0, 0.0), Vector2::new(0.0, 0.0)),
+                VertexData::new(Vector3::new(3.0, -1.0, 0.0), Vector2::new(2.0, 0.0)),
+                VertexData::new(Vector3::new(-1.0, 3.0, 0.0), Vector2::new(0.0, 2.0)),
+            ],
+            vec![0, 1, 2],
+        )
+    }
+
     // Uses for parsing header in mesh files
     const SECTION_START_SYMBOL: char = ':';
     const VERTICES_SECTION_NAME: &str = "Vertices";
     const INDICES_SECTION_NAME: &str = "Indices";
     const TEXCOORD_SECTION_NAME: &str = "TexCoord";
+    const COLOR_SECTION_NAME: &str = "Color";
 
     fn load_raw_vertices(inp: &str, out: &mut Vec<Vector3>) -> Result<(), MeshParseError> {
         let mut swap: u8 = 0; // 0 is x, 1 is y and 2 is z
         let (mut x, mut y) = (0.0, 0.0); // z is not need
         let mut num_b = String::with_capacity(8);
 
-        for (i, c) in inp.chars().enumerate() {
+        // the trailing space flushes the last number
+        for (i, c) in inp.chars().chain([' ']).enumerate() {
             // only values allowed: numbers, '.', '-' and whitespace
             let is_whitespace = c.is_whitespace();
             let is_valid_num = c == '.' || c == '-' || c.is_numeric();
@@ -192,15 +346,16 @@ impl Mesh {
 
                 num_b.clear();
                 swap = (swap + 1) % 3;
-            } else {
+            } else if !is_whitespace {
                 num_b.push(c);
             }
         }
 
         if swap != 0 {
-            return Err(MeshParseError::ExcessValue {
-                max: 3,
-                data: num_b,
+            return Err(MeshParseError::IncompleteValue {
+                section: MeshSectionType::Vertices,
+                expected: 3,
+                got: swap as u32,
             });
         }
         Ok(())
@@ -211,7 +366,8 @@ impl Mesh {
         let mut u = 0.0; // v is not need
         let mut num_b = String::with_capacity(8);
 
-        for (i, c) in inp.chars().enumerate() {
+        // the trailing space flushes the last number
+        for (i, c) in inp.chars().chain([' ']).enumerate() {
             // only values allowed: numbers, '.' and whitespace
             let is_whitespace = c.is_whitespace();
             let is_valid_num = c == '.' || c.is_numeric();
@@ -241,25 +397,86 @@ impl Mesh {
 
                 num_b.clear();
                 swap = !swap;
-            } else {
+            } else if !is_whitespace {
                 num_b.push(c);
             }
         }
 
         if swap {
-            return Err(MeshParseError::ExcessValue {
-                max: 2,
-                data: num_b,
+            return Err(MeshParseError::IncompleteValue {
+                section: MeshSectionType::TexCoord,
+                expected: 2,
+                got: 1,
             });
         }
 
         Ok(())
     }
 
+    fn load_raw_color(inp: &str, out: &mut Vec<Color3>) -> Result<(), MeshParseError> {
+        let mut swap: u8 = 0; // 0 is r, 1 is g and 2 is b
+        let (mut r, mut g) = (0.0, 0.0); // b is not need
+        let mut num_b = String::with_capacity(8);
+
+        // the trailing space flushes the last number
+        for (i, c) in inp.chars().chain([' ']).enumerate() {
+            // only values allowed: numbers, '.' and whitespace
+            let is_whitespace = c.is_whitespace();
+            let is_valid_num = c == '.' || c.is_numeric();
+            if !is_whitespace && !is_valid_num {
+                return Err(MeshParseError::InvalidSymbol {
+                    at: i,
+                    section: MeshSectionType::Color,
+                });
+            }
+
+            if is_whitespace && !num_b.is_empty() {
+                // compute, each component is between 0 and 255
+                let v_ex = num_b.parse::<f32>();
+                let Ok(v) = v_ex else {
+                    return Err(MeshParseError::InparsableValue {
+                        at: i,
+                        got: num_b,
+                        inner: v_ex.unwrap_err().to_string(),
+                    });
+                };
+                if v > 255.0 {
+                    return Err(MeshParseError::InparsableValue {
+                        at: i,
+                        got: num_b,
+                        inner: "color component is greater than 255".to_string(),
+                    });
+                }
+                let v = v / 255.0;
+                match swap {
+                    0 => r = v,
+                    1 => g = v,
+                    2 => out.push(Color3 { r, g, b: v }),
+                    _ => panic!("internal error: swap not between 0 and 2"),
+                }
+
+                num_b.clear();
+                swap = (swap + 1) % 3;
+            } else if !is_whitespace {
+                num_b.push(c);
+            }
+        }
+
+        if swap != 0 {
+            return Err(MeshParseError::IncompleteValue {
+                section: MeshSectionType::Color,
+                expected: 3,
+                got: swap as u32,
+            });
+        }
+        Ok(())
+    }
+
     fn load_raw_indices(inp: &str, out: &mut Vec<u32>) -> Result<(), MeshParseError> {
         let mut num_b = String::with_capacity(8);
 
-        for (i, c) in inp.chars().enumerate() {
+        // the trailing space flushes the last number
+        for (i, c) in inp.chars().chain([' ']).enumerate() {
             // only values allowed: numbers and whitespace
             let is_whitespace = c.is_whitespace();
             let is_valid_num = c.is_numeric();
@@ -282,7 +499,7 @@ impl Mesh {
                 };
                 out.push(v);
                 num_b.clear();
-            } else {
+            } else if !is_whitespace {
                 num_b.push(c);
             }
         }
@@ -297,6 +514,10 @@ impl Mesh {
     /// Either:
     /// - `Ok`: A mesh based on the data
     /// - `Err`: An error message
+    /// # Note
+    /// The `TexCoord` and `Color` sections are optional, but when not empty they need one value
+    /// per vertex (colors are 3 components between 0 and 255). Vertices default to a (0, 0)
+    /// texture coordinate and a white color.
     pub fn load_mesh(b: &str) -> Result<Self, MeshParseError> {
         let mut current_section = MeshSectionType::None;
 
@@ -309,6 +530,7 @@ impl Mesh {
         let mut pos_data = Vec::<Vector3>::with_capacity(512);
         let mut ind_data = Vec::<u32>::with_capacity(128);
         let mut texcoord_data = Vec::<Vector2>::with_capacity(512);
+        let mut color_data = Vec::<Color3>::with_capacity(512);
 
         for c in b.chars() {
             if c == Self::SECTION_START_SYMBOL {
@@ -319,7 +541,8 @@ impl Mesh {
                         data,
                         pos_data,
                         ind_data,
-                        texcoord_data
+                        texcoord_data,
+                        color_data
                     )?
                 }
                 looking_at_sect_start = true;
@@ -352,17 +575,35 @@ impl Mesh {
                 data,
                 pos_data,
                 ind_data,
-                texcoord_data
+                texcoord_data,
+                color_data
             )?
         }
 
+        // the optional sections must have a value for every vertex
+        for (section, len) in [
+            (MeshSectionType::TexCoord, texcoord_data.len()),
+            (MeshSectionType::Color, color_data.len()),
+        ] {
+            if len != 0 && len != pos_data.len() {
+                return Err(MeshParseError::MismatchedLength {
+                    section,
+                    expected: pos_data.len(),
+                    got: len,
+                });
+            }
+        }
+
         let mut vertex_data = Vec::<VertexData>::with_capacity(pos_data.len());
         for (i, pos) in pos_data.into_iter().enumerate() {
             let coord = *texcoord_data.get(i).unwrap_or(&Vector2::zero());
-            vertex_data.push(VertexData::new(pos, coord));
+            let color = *color_data.get(i).unwrap_or(&Color3::white());
+            vertex_data.push(VertexData::with_color(pos, color, coord));
         }
 
-        Ok(Mesh::with_set_data(vertex_data, ind_data))
+        let mesh = Mesh::with_set_data(vertex_data, ind_data);
+        mesh.validate().map_err(MeshParseError::InvalidMesh)?;
+        Ok(mesh)
     }
 
     /// Creates a new from a file of mesh data.
@@ -386,6 +627,195 @@ impl Mesh {
         Self::load_mesh(&b)
     }
 
+    /// Writes the mesh as mesh data, that can be loaded with `load_mesh`.
+    /// # Returns
+    /// The mesh data, with every section
+    /// # Note
+    /// - Colors are written as 0 to 255 integers, so they are rounded to the nearest 255th.
+    /// - Normals, tangents and the topology aren't written.
+    /// - `load_mesh` doesn't accept negative texture coordinates.
+    pub fn to_mesh_string(&self) -> String {
+        let mut b = String::with_capacity(self.vertices.len() * 48 + self.indices.len() * 4);
+        let mut write_section = |name: &str, lines: &mut dyn Iterator<Item = String>| {
+            // writing to a string can't fail
+            writeln!(b, "{}{name}", Self::SECTION_START_SYMBOL).unwrap();
+            for line in lines {
+                writeln!(b, "{line}").unwrap();
+            }
+            b.push('\n');
+        };
+
+        write_section(
+            Self::VERTICES_SECTION_NAME,
+            &mut self.vertices.iter().map(|vertex| {
+                let pos = vertex.get_position();
+                format!("{} {} {}", pos.x, pos.y, pos.z)
+            }),
+        );
+        write_section(
+            Self::INDICES_SECTION_NAME,
+            &mut self.indices.chunks(3).map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+        );
+        write_section(
+            Self::TEXCOORD_SECTION_NAME,
+            &mut self.vertices.iter().map(|vertex| {
+                let coord = vertex.get_tex_coord();
+                format!("{} {}", coord.x, coord.y)
+            }),
+        );
+        write_section(
+            Self::COLOR_SECTION_NAME,
+            &mut self.vertices.iter().map(|vertex| {
+                let color = vertex.get_color();
+                let [r, g, b] = [color.r, color.g, color.b]
+                    .map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+                format!("{r} {g} {b}")
+            }),
+        );
+
+        b
+    }
+
+    /// Saves the mesh to a file, see `to_mesh_string`.
+    /// # Arguements
+    /// - `path`: the path of the file
+    /// # Returns
+    /// Either:
+    /// - `Ok`: The file was written
+    /// - `Err`: The file couldn't be written
+    pub fn save_mesh_to_file(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_mesh_string())
+    }
+
+    /// Creates a new mesh from Wavefront OBJ data.
+    /// # Arguements
+    /// - `obj`: the OBJ data
+    /// # Returns
+    /// Either:
+    /// - `Ok`: A mesh based on the data
+    /// - `Err`: An error message, containing the line number
+    /// # Note
+    /// - Only `v`, `vt` and `f` are used, other lines (e.g. `vn`, `o`, `g` and comments) are
+    ///   ignored.
+    /// - Faces with more than 3 vertices are triangulated as a fan, so should be convex.
+    /// - Each unique position and texture coordinate pair becomes one vertex, as the mesh uses a
+    ///   single index per vertex.
+    /// - Negative (relative) indices are supported.
+    pub fn load_obj(obj: &str) -> Result<Self, String> {
+        let mut positions = Vec::<Vector3>::with_capacity(512);
+        let mut tex_coords = Vec::<Vector2>::with_capacity(512);
+        let mut mesh = Self::with_capacity(512, 512);
+        let mut vertex_ids = HashMap::<(usize, Option<usize>), u32>::new();
+
+        for (i, line) in obj.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+
+            let parse_floats = |words: std::str::SplitWhitespace| {
+                words
+                    .map(|w| w.parse::<f32>())
+                    .collect::<Result<Vec<f32>, _>>()
+                    .map_err(|e| format!("line {line_number}: {e}"))
+            };
+
+            match keyword {
+                "v" => {
+                    let values = parse_floats(words)?;
+                    let [x, y, z, ..] = values[..] else {
+                        return Err(format!("line {line_number}: expected 3 coordinates"));
+                    };
+                    positions.push(Vector3::new(x, y, z));
+                }
+                "vt" => {
+                    let values = parse_floats(words)?;
+                    let [u, v, ..] = values[..] else {
+                        return Err(format!("line {line_number}: expected 2 coordinates"));
+                    };
+                    tex_coords.push(Vector2::new(u, v));
+                }
+                "f" => {
+                    let mut face = Vec::<u32>::with_capacity(4);
+                    for word in words {
+                        let mut parts = word.split('/');
+                        let position_index = parts
+                            .next()
+                            .map(|p| Self::resolve_obj_index(p, positions.len()))
+                            .unwrap_or(Err("missing position index".to_string()))
+                            .map_err(|e| format!("line {line_number}: {e}"))?;
+                        let tex_coord_index = match parts.next() {
+                            Some(part) if !part.is_empty() => Some(
+                                Self::resolve_obj_index(part, tex_coords.len())
+                                    .map_err(|e| format!("line {line_number}: {e}"))?,
+                            ),
+                            _ => None,
+                        };
+
+                        let key = (position_index, tex_coord_index);
+                        let id = *vertex_ids.entry(key).or_insert_with(|| {
+                            let coord = tex_coord_index.map_or(Vector2::zero(), |i| tex_coords[i]);
+                            mesh.add_vertex_data_pt(positions[position_index], coord);
+                            (mesh.vertices.len() - 1) as u32
+                        });
+                        face.push(id);
+                    }
+
+                    if face.len() < 3 {
+                        return Err(format!("line {line_number}: a face needs 3 vertices"));
+                    }
+                    for j in 1..face.len() - 1 {
+                        mesh.indices.extend([face[0], face[j], face[j + 1]]);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        mesh.vertices.shrink_to_fit();
+        mesh.indices.shrink_to_fit();
+        Ok(mesh)
+    }
+
+    /// Creates a new mesh from a Wavefront OBJ file.
+    /// # Arguements
+    /// - `path`: the path of the file
+    /// # Returns
+    /// Either:
+    /// - `Ok`: A mesh based on the data
+    /// - `Err`: An error message
+    /// # Note
+    /// See `load_obj`.
+    pub fn load_obj_from_file(path: &str) -> Result<Self, String> {
+        let obj = fs::read_to_string(path).map_err(|e| format!("couldn't read {path}: {e}"))?;
+        Self::load_obj(&obj)
+    }
+
+    /// Converts an OBJ index (1-based, or negative relative to the end) into a 0-based index.
+    fn resolve_obj_index(index: &str, len: usize) -> Result<usize, String> {
+        let value = index
+            .parse::<i64>()
+            .map_err(|e| format!("invalid index {index}: {e}"))?;
+
+        let resolved = if value < 0 {
+            len as i64 + value
+        } else {
+            value - 1
+        };
+        if resolved < 0 || resolved >= len as i64 {
+            return Err(format!("index {index} is out of range"));
+        }
+        Ok(resolved as usize)
+    }
+
     /// Adds a vertex to the mesh.
     /// # Arguements
     /// - `vd`: the vertex's data
@@ -422,6 +852,681 @@ impl Mesh {
     pub fn to_vertex_data_internal(&self) -> Vec<VertexDataInternal> {
         self.vertices.iter().map(|v| v.to_internal()).collect()
     }
+
+    /// Bakes the texture's colors into the vertex colors, by sampling the texture at each
+    /// vertex's texture coordinate.
+    /// # Arguements
+    /// - `texture`: the texture being sampled
+    /// # Note
+    /// Vertices are left unchanged if the texture has no pixel data.
+    pub fn bake_texture_to_vertex_colors(&mut self, texture: &Texture) {
+        for vertex in self.vertices.iter_mut() {
+            if let Some(color) = texture.sample_bilinear(vertex.get_tex_coord()) {
+                vertex.set_color(color);
+            }
+        }
+    }
+
+    /// Groups the indices into triangles.
+    /// # Returns
+    /// A collection of triangles, any indices left over are ignored
+    /// # Note
+    /// This assumes the mesh has the `Triangles` topology.
+    pub fn to_indices_tri(&self) -> Vec<[u32; 3]> {
+        self.indices
+            .chunks_exact(3)
+            .map(|tri| [tri[0], tri[1], tri[2]])
+            .collect()
+    }
+
+    /// Computes smooth normals, by averaging the normals of the triangles sharing each vertex.
+    /// # Note
+    /// - Larger triangles have more weight.
+    /// - Vertices not used by any triangle have a zero normal.
+    /// - Vertices are only shared if they have the same index, vertices split at a UV seam get
+    ///   different normals.
+    /// - This assumes the mesh has the `Triangles` topology.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vector3::zero(); self.vertices.len()];
+
+        for [a, b, c] in self.to_indices_tri() {
+            let (a, b, c) = (a as usize, b as usize, c as usize);
+            let (Some(v0), Some(v1), Some(v2)) = (
+                self.vertices.get(a),
+                self.vertices.get(b),
+                self.vertices.get(c),
+            ) else {
+                continue;
+            };
+
+            let p0 = v0.get_position();
+            // not normalised, so larger triangles have more weight
+            let face_normal = (v1.get_position() - p0).cross(v2.get_position() - p0);
+            for i in [a, b, c] {
+                normals[i] += face_normal;
+            }
+        }
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.set_normal(normal.get_unit());
+        }
+    }
+
+    /// Computes flat normals, so each triangle is shaded uniformly.
+    /// # Note
+    /// - Every triangle gets it's own 3 vertices (see `to_flat_vertices`), so the vertex count
+    ///   becomes the index count.
+    /// - Indices out of range are skipped, and `tangents` is cleared.
+    /// - This assumes the mesh has the `Triangles` topology.
+    pub fn compute_flat_normals(&mut self) {
+        let mut vertices = self.to_flat_vertices();
+
+        for corners in vertices.chunks_exact_mut(3) {
+            let p0 = corners[0].get_position();
+            let face_normal = (corners[1].get_position() - p0)
+                .cross(corners[2].get_position() - p0)
+                .get_unit();
+            for corner in corners.iter_mut() {
+                corner.set_normal(face_normal);
+            }
+        }
+
+        self.indices = (0..vertices.len() as u32).collect();
+        self.vertices = vertices;
+        self.tangents.clear();
+    }
+
+    /// The grid size used to compare vertices, in `from_flat_vertices`.
+    pub const DEDUP_EPSILON: f32 = 1e-5;
+
+    /// Expands the indices, so every triangle has it's own 3 vertices.
+    /// # Returns
+    /// The vertices of each triangle, in order
+    /// # Note
+    /// - Triangles with an index out of range are skipped.
+    /// - This assumes the mesh has the `Triangles` topology.
+    pub fn to_flat_vertices(&self) -> Vec<VertexData> {
+        self.to_indices_tri()
+            .into_iter()
+            .filter_map(|tri| {
+                let [a, b, c] = tri.map(|i| self.vertices.get(i as usize).copied());
+                Some([a?, b?, c?])
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Creates a mesh from vertices, where every 3 vertices is a triangle, by merging equal
+    /// vertices.
+    /// # Arguements
+    /// - `vertices`: the vertices of each triangle (see `to_flat_vertices`)
+    /// # Returns
+    /// An indexed mesh
+    /// # Note
+    /// Every field (position, color, texture coordinate and normal) is rounded to a multiple of
+    /// `DEDUP_EPSILON` before comparing, so vertices closer than the epsilon are merged, but 2
+    /// vertices either side of a multiple may not be. The first vertex of each group is kept.
+    pub fn from_flat_vertices(vertices: Vec<VertexData>) -> Self {
+        let mut mesh = Self::with_capacity(vertices.len(), vertices.len());
+        let mut vertex_ids = HashMap::<[i64; 11], u32>::with_capacity(vertices.len());
+
+        for vertex in vertices {
+            let key = vertex
+                .to_internal()
+                .map(|v| (v / Self::DEDUP_EPSILON).round() as i64);
+            let id = *vertex_ids.entry(key).or_insert_with(|| {
+                mesh.vertices.push(vertex);
+                (mesh.vertices.len() - 1) as u32
+            });
+            mesh.indices.push(id);
+        }
+
+        mesh.vertices.shrink_to_fit();
+        mesh
+    }
+
+    /// The smallest length, before a tangent is considered invalid.
+    const TANGENT_EPSILON: f32 = 1e-6;
+
+    /// Computes the tangent of each vertex, from the triangles and texture coordinates.
+    /// # Returns
+    /// The amount of vertices that needed a fallback tangent
+    /// # Note
+    /// - Degenerate texture coordinates or triangles produce invalid tangents (zero or `NaN`),
+    ///   these are replaced by a fallback that is orthogonal to the vertex's normal.
+    /// - The result is stored in `tangents`.
+    pub fn compute_tangents(&mut self) -> usize {
+        let mut tangents = vec![Vector3::zero(); self.vertices.len()];
+        let mut normals = vec![Vector3::zero(); self.vertices.len()];
+
+        for [a, b, c] in self.to_indices_tri() {
+            let (a, b, c) = (a as usize, b as usize, c as usize);
+            let (Some(v0), Some(v1), Some(v2)) = (
+                self.vertices.get(a),
+                self.vertices.get(b),
+                self.vertices.get(c),
+            ) else {
+                continue;
+            };
+
+            let p0 = v0.get_position();
+            let edge1 = v1.get_position() - p0;
+            let edge2 = v2.get_position() - p0;
+
+            let uv0 = v0.get_tex_coord();
+            let delta_uv1 = v1.get_tex_coord() - uv0;
+            let delta_uv2 = v2.get_tex_coord() - uv0;
+
+            // not normalised, so larger triangles have more weight
+            let face_normal = edge1.cross(edge2);
+            let det = delta_uv1.x * delta_uv2.y - delta_uv2.x * delta_uv1.y;
+            let face_tangent = if det.abs() > Self::TANGENT_EPSILON {
+                (edge1 * delta_uv2.y - edge2 * delta_uv1.y) / det
+            } else {
+                Vector3::zero()
+            };
+
+            for i in [a, b, c] {
+                tangents[i] += face_tangent;
+                normals[i] += face_normal;
+            }
+        }
+
+        let mut fallback_count = 0;
+        for (tangent, normal) in tangents.iter_mut().zip(normals) {
+            let normal = normal.get_unit();
+            // Gram-Schmidt, makes the tangent orthogonal to the normal
+            let orthogonal = (*tangent - normal * normal.dot(*tangent)).get_unit();
+
+            if orthogonal.iter().all(f32::is_finite)
+                && orthogonal.get_magnitude() > Self::TANGENT_EPSILON
+            {
+                *tangent = orthogonal;
+            } else {
+                *tangent = Self::fallback_tangent(normal);
+                fallback_count += 1;
+            }
+        }
+
+        self.tangents = tangents;
+        fallback_count
+    }
+
+    /// Creates a tangent orthogonal to the normal, using the world axis least aligned with it.
+    fn fallback_tangent(normal: Vector3) -> Vector3 {
+        if !normal.iter().all(f32::is_finite) || normal == Vector3::zero() {
+            return Vector3::right();
+        }
+
+        let axis = [Vector3::right(), Vector3::up(), Vector3::forward()]
+            .into_iter()
+            .min_by(|a, b| normal.dot(*a).abs().total_cmp(&normal.dot(*b).abs()))
+            .unwrap();
+
+        (axis - normal * normal.dot(axis)).get_unit()
+    }
+
+    /// The smallest area (doubled), before a triangle is considered degenerate.
+    const SIMPLIFY_EPSILON: f32 = 1e-10;
+
+    /// Reduces the amount of triangles, by collapsing edges using the quadric error metric.
+    /// # Arguements
+    /// - `target_ratio`: the fraction of triangles to keep, clamped between 0.0 and 1.0
+    /// # Returns
+    /// The amount of triangles after simplifying
+    /// # Note
+    /// - Boundary edges (used by one triangle) and non-manifold edges are never collapsed, this
+    ///   also preserves UV seams, since they are made of split vertices.
+    /// - A collapse is rejected if it would flip or degenerate a triangle, so the target may
+    ///   not be reached.
+    /// - Unused vertices are removed and `tangents` is cleared, call `compute_tangents` again if
+    ///   needed.
+    /// - Only meshes with the `Triangles` topology are simplified.
+    pub fn simplify(&mut self, target_ratio: f32) -> usize {
+        if self.topology != MeshTopology::Triangles {
+            return self.indices.len() / 3;
+        }
+
+        let mut triangles: Vec<[u32; 3]> = self
+            .to_indices_tri()
+            .into_iter()
+            .filter(|tri| tri.iter().all(|i| (*i as usize) < self.vertices.len()))
+            .collect();
+        let target = (triangles.len() as f32 * target_ratio.clamp(0.0, 1.0)).ceil() as usize;
+
+        let vertex_count = self.vertices.len();
+        let mut alive = vec![true; triangles.len()];
+        let mut alive_count = triangles.len();
+        let mut vertex_tris = vec![Vec::<usize>::new(); vertex_count];
+        let mut quadrics = vec![Quadric::default(); vertex_count];
+        let mut edge_uses = HashMap::<(u32, u32), u32>::new();
+
+        for (t, tri) in triangles.iter().enumerate() {
+            let [a, b, c] = tri.map(|i| self.vertices[i as usize].get_position());
+            let normal = (b - a).cross(c - a);
+            let area = normal.get_magnitude();
+            if area > Self::SIMPLIFY_EPSILON {
+                let quadric = Quadric::from_plane(normal / area, a, area);
+                for i in tri {
+                    quadrics[*i as usize].add(&quadric);
+                }
+            }
+
+            for i in tri {
+                vertex_tris[*i as usize].push(t);
+            }
+            for (start, end) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                *edge_uses
+                    .entry((start.min(end), start.max(end)))
+                    .or_default() += 1;
+            }
+        }
+
+        let mut locked = vec![false; vertex_count];
+        for ((start, end), uses) in edge_uses.iter() {
+            if *uses != 2 {
+                locked[*start as usize] = true;
+                locked[*end as usize] = true;
+            }
+        }
+
+        while alive_count > target {
+            let mut edges = HashSet::<(u32, u32)>::new();
+            for (tri, _) in triangles
+                .iter()
+                .zip(alive.iter())
+                .filter(|(_, alive)| **alive)
+            {
+                for (start, end) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                    edges.insert((start.min(end), start.max(end)));
+                }
+            }
+
+            // (cost, kept vertex, removed vertex, interpolation from kept to removed)
+            let mut candidates: Vec<(f64, usize, usize, f32)> = edges
+                .into_iter()
+                .map(|(a, b)| (a as usize, b as usize))
+                .filter(|(a, b)| !locked[*a] && !locked[*b])
+                .map(|(a, b)| {
+                    let mut quadric = quadrics[a];
+                    quadric.add(&quadrics[b]);
+
+                    let (pos_a, pos_b) = (
+                        self.vertices[a].get_position(),
+                        self.vertices[b].get_position(),
+                    );
+                    [0.0, 0.5, 1.0]
+                        .into_iter()
+                        .map(|t| (quadric.error(pos_a + (pos_b - pos_a) * t), a, b, t))
+                        .min_by(|x, y| x.0.total_cmp(&y.0))
+                        .unwrap()
+                })
+                .collect();
+            candidates.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+            let mut touched = vec![false; vertex_count];
+            let mut collapsed = 0;
+            for (_, keep, remove, t) in candidates {
+                if alive_count <= target {
+                    break;
+                }
+                if touched[keep] || touched[remove] {
+                    continue;
+                }
+
+                let merged = self.vertices[keep].lerp(&self.vertices[remove], t);
+                if !Self::can_collapse(
+                    &self.vertices,
+                    &triangles,
+                    &alive,
+                    &vertex_tris,
+                    (keep, remove),
+                    merged.get_position(),
+                ) {
+                    continue;
+                }
+
+                self.vertices[keep] = merged;
+                let removed_quadric = quadrics[remove];
+                quadrics[keep].add(&removed_quadric);
+
+                let removed_tris = std::mem::take(&mut vertex_tris[remove]);
+                for t in removed_tris {
+                    if !alive[t] {
+                        continue;
+                    }
+
+                    if triangles[t].contains(&(keep as u32)) {
+                        alive[t] = false;
+                        alive_count -= 1;
+                    } else {
+                        for i in triangles[t].iter_mut() {
+                            if *i == remove as u32 {
+                                *i = keep as u32;
+                            }
+                        }
+                        vertex_tris[keep].push(t);
+                    }
+                }
+                vertex_tris[keep].retain(|t| alive[*t]);
+
+                // neighbouring costs are now stale, so they wait for the next pass
+                for t in vertex_tris[keep].iter() {
+                    for i in triangles[*t] {
+                        touched[i as usize] = true;
+                    }
+                }
+                touched[remove] = true;
+                collapsed += 1;
+            }
+
+            if collapsed == 0 {
+                break;
+            }
+        }
+
+        // remove unused vertices
+        let mut remap = vec![u32::MAX; vertex_count];
+        let mut vertices = Vec::<VertexData>::with_capacity(vertex_count);
+        let mut indices = Vec::<u32>::with_capacity(alive_count * 3);
+        for (tri, _) in triangles
+            .iter()
+            .zip(alive.iter())
+            .filter(|(_, alive)| **alive)
+        {
+            for i in tri {
+                let i = *i as usize;
+                if remap[i] == u32::MAX {
+                    remap[i] = vertices.len() as u32;
+                    vertices.push(self.vertices[i]);
+                }
+                indices.push(remap[i]);
+            }
+        }
+
+        self.vertices = vertices;
+        self.indices = indices;
+        self.tangents.clear();
+        alive_count
+    }
+
+    /// Checks if collapsing an edge keeps the surface valid. The collapse is invalid when:
+    /// - the vertices share more than 2 neighbours (it would pinch the surface),
+    /// - or a remaining triangle would flip or degenerate.
+    fn can_collapse(
+        vertices: &[VertexData],
+        triangles: &[[u32; 3]],
+        alive: &[bool],
+        vertex_tris: &[Vec<usize>],
+        (keep, remove): (usize, usize),
+        position: Vector3,
+    ) -> bool {
+        let neighbours = |v: usize| -> HashSet<u32> {
+            vertex_tris[v]
+                .iter()
+                .filter(|t| alive[**t])
+                .flat_map(|t| triangles[*t])
+                .filter(|i| *i as usize != v)
+                .collect()
+        };
+        let shared = neighbours(keep).intersection(&neighbours(remove)).count();
+        if shared != 2 {
+            return false;
+        }
+
+        let affected = vertex_tris[keep].iter().chain(vertex_tris[remove].iter());
+        for t in affected.filter(|t| alive[**t]) {
+            let tri = triangles[*t];
+            if tri.contains(&(keep as u32)) && tri.contains(&(remove as u32)) {
+                continue; // removed by the collapse
+            }
+
+            let old = tri.map(|i| vertices[i as usize].get_position());
+            let new = tri.map(|i| {
+                if i as usize == keep || i as usize == remove {
+                    position
+                } else {
+                    vertices[i as usize].get_position()
+                }
+            });
+
+            let old_normal = (old[1] - old[0]).cross(old[2] - old[0]);
+            let new_normal = (new[1] - new[0]).cross(new[2] - new[0]);
+            if new_normal.get_magnitude() <= Self::SIMPLIFY_EPSILON
+                || old_normal.dot(new_normal) <= 0.0
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Gets the axis-aligned bounding box of the vertices.
+    /// # Returns
+    /// The minimum and maximum corners, or zero bounds if there are no vertices
+    pub fn bounding_box(&self) -> (Vector3, Vector3) {
+        let mut positions = self.vertices.iter().map(|vertex| vertex.get_position());
+        let Some(first) = positions.next() else {
+            return (Vector3::zero(), Vector3::zero());
+        };
+
+        positions.fold((first, first), |(min, max), pos| {
+            (min.min(pos), max.max(pos))
+        })
+    }
+
+    /// The smallest determinant or distance of a hit, used by `raycast`.
+    const RAYCAST_EPSILON: f32 = 1e-7;
+
+    /// Finds the nearest triangle that the ray hits, using the Möller–Trumbore algorithm.
+    /// # Arguements
+    /// - `ray`: the ray, in the mesh's space
+    /// # Returns
+    /// Either:
+    /// - `Some`: the distance along the ray (in lengths of it's direction) of the nearest hit
+    /// - `None`: the ray didn't hit any triangle
+    /// # Note
+    /// - Both sides of a triangle can be hit, hits behind the origin are ignored.
+    /// - Triangles with an index out of range are skipped.
+    /// - This assumes the mesh has the `Triangles` topology.
+    pub fn raycast(&self, ray: &Ray) -> Option<f32> {
+        let mut nearest: Option<f32> = None;
+
+        for tri in self.to_indices_tri() {
+            let [p0, p1, p2] = tri.map(|i| self.vertices.get(i as usize).map(|v| v.get_position()));
+            let (Some(p0), Some(p1), Some(p2)) = (p0, p1, p2) else {
+                continue;
+            };
+
+            let (edge1, edge2) = (p1 - p0, p2 - p0);
+            let p = ray.direction.cross(edge2);
+            let determinant = edge1.dot(p);
+            if determinant.abs() < Self::RAYCAST_EPSILON {
+                continue; // parallel with the triangle
+            }
+
+            let inv_determinant = 1.0 / determinant;
+            let s = ray.origin - p0;
+            let u = s.dot(p) * inv_determinant;
+            if !(0.0..=1.0).contains(&u) {
+                continue;
+            }
+
+            let q = s.cross(edge1);
+            let v = ray.direction.dot(q) * inv_determinant;
+            if v < 0.0 || u + v > 1.0 {
+                continue;
+            }
+
+            let distance = edge2.dot(q) * inv_determinant;
+            if distance > Self::RAYCAST_EPSILON && nearest.is_none_or(|n| distance < n) {
+                nearest = Some(distance);
+            }
+        }
+
+        nearest
+    }
+
+    /// Applies a transformation to every vertex, e.g. to bake a part's transform into it's mesh.
+    /// # Arguements
+    /// - `matrix`: the transformation
+    /// # Note
+    /// - Positions are transformed as points, normals by the `normal_matrix` of the matrix.
+    /// - Normals and tangents are normalised again, zero normals (not computed) are left as is.
+    pub fn transform(&mut self, matrix: Mat4) {
+        let normal_matrix = normal_matrix(&matrix);
+
+        for vertex in self.vertices.iter_mut() {
+            vertex.set_position(matrix.transform_point3(vertex.get_position().into()).into());
+
+            let normal = vertex.get_normal();
+            if normal != Vector3::zero() {
+                let normal: Vector3 = (normal_matrix * Vec3::from(normal)).into();
+                vertex.set_normal(normal.get_unit());
+            }
+        }
+
+        for tangent in self.tangents.iter_mut() {
+            let new_tangent: Vector3 = matrix.transform_vec3((*tangent).into()).into();
+            *tangent = new_tangent.get_unit();
+        }
+    }
+
+    /// Appends another mesh's vertices and indices, so both can be drawn at once.
+    /// # Arguements
+    /// - `other`: the mesh being appended
+    /// # Note
+    /// - The indices of `other` are offset by the current vertex count.
+    /// - `tangents` is kept only if both meshes have them, otherwise it's cleared.
+    /// - Both meshes should have the same topology.
+    pub fn merge(&mut self, other: &Mesh) {
+        let offset = self.vertices.len() as u32;
+        let keep_tangents = self.tangents.len() == self.vertices.len()
+            && other.tangents.len() == other.vertices.len();
+
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices
+            .extend(other.indices.iter().map(|index| index + offset));
+
+        if keep_tangents {
+            self.tangents.extend_from_slice(&other.tangents);
+        } else {
+            self.tangents.clear();
+        }
+    }
+
+    /// Checks the indices can be drawn with the mesh's topology.
+    /// # Returns
+    /// Either:
+    /// - `Ok`: every index is in range, and the index count fits the topology
+    /// - `Err`: An error message, with the offending index and it's position
+    /// # Note
+    /// The index count needs to be a multiple of 3 for `Triangles`, and 2 for `Lines`.
+    pub fn validate(&self) -> Result<(), String> {
+        let vertex_count = self.vertices.len();
+        if let Some((at, index)) = self
+            .indices
+            .iter()
+            .enumerate()
+            .find(|(_, index)| **index as usize >= vertex_count)
+        {
+            return Err(format!(
+                "index {index} at position {at} is out of range, there are {vertex_count} vertices"
+            ));
+        }
+
+        let per_primitive = match self.topology {
+            MeshTopology::Triangles => 3,
+            MeshTopology::Lines => 2,
+            MeshTopology::Points => 1,
+        };
+        if !self.indices.len().is_multiple_of(per_primitive) {
+            return Err(format!(
+                "{} indices isn't a multiple of {per_primitive} ({:?})",
+                self.indices.len(),
+                self.topology
+            ));
+        }
+        Ok(())
+    }
+
+    /// Creates a wireframe of the mesh, where every unique edge of a triangle is a line.
+    /// # Returns
+    /// A mesh with the `Lines` topology, sharing the same vertices
+    /// # Note
+    /// Edges shared between triangles are only included once.
+    pub fn to_wireframe(&self) -> Mesh {
+        let triangles = self.to_indices_tri();
+        let mut edges = HashSet::<(u32, u32)>::with_capacity(triangles.len() * 3);
+        let mut indices = Vec::<u32>::with_capacity(triangles.len() * 6);
+
+        for [a, b, c] in triangles {
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                let key = (start.min(end), start.max(end));
+                if edges.insert(key) {
+                    indices.push(start);
+                    indices.push(end);
+                }
+            }
+        }
+
+        Self {
+            vertices: self.vertices.clone(),
+            indices,
+            topology: MeshTopology::Lines,
+            tangents: vec![],
+        }
+    }
+}
+
+/// A symmetric 4x4 matrix, measuring the squared distance of a point to a set of planes.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+impl Quadric {
+    /// Creates a quadric of a plane.
+    /// # Arguements
+    /// - `normal`: the unit normal of the plane
+    /// - `point`: a point on the plane
+    /// - `weight`: how much the plane contributes to the error
+    fn from_plane(normal: Vector3, point: Vector3, weight: f32) -> Self {
+        let (a, b, c) = (normal.x as f64, normal.y as f64, normal.z as f64);
+        let d = -normal.dot(point) as f64;
+        let w = weight as f64;
+        Self([
+            a * a * w,
+            a * b * w,
+            a * c * w,
+            a * d * w,
+            b * b * w,
+            b * c * w,
+            b * d * w,
+            c * c * w,
+            c * d * w,
+            d * d * w,
+        ])
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (value, other) in self.0.iter_mut().zip(other.0) {
+            *value += other;
+        }
+    }
+
+    /// The error of moving a vertex to the position.
+    fn error(&self, position: Vector3) -> f64 {
+        let q = &self.0;
+        let (x, y, z) = (position.x as f64, position.y as f64, position.z as f64);
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
 }
 
 /// Errors relating to mesh parsing.
@@ -450,8 +1555,29 @@ pub enum MeshParseError {
         /// The excess value
         data: String,
     },
+    /// Thrown when the last value of a section is missing components, e.g. a vertex with only 2
+    /// coordinates.
+    IncompleteValue {
+        /// The mesh section
+        section: MeshSectionType,
+        /// The amount of components expected
+        expected: u32,
+        /// The amount of components given
+        got: u32,
+    },
     /// Thrown when there has been an invalid section type.
     InvalidSectionType(String),
+    /// Thrown when an optional section doesn't have a value for every vertex.
+    MismatchedLength {
+        /// The mesh section
+        section: MeshSectionType,
+        /// The amount of vertices
+        expected: usize,
+        /// The amount of values in the section
+        got: usize,
+    },
+    /// Thrown when the indices don't fit the vertices, see `Mesh::validate`.
+    InvalidMesh(String),
     /// Thrown when the mesh file couldn't be read.
     CouldntReadFile(io::Error),
     /// Thrown when the mesh file couldn't be opened.
@@ -473,7 +1599,24 @@ impl fmt::Display for MeshParseError {
             Self::ExcessValue { data, max } => {
                 write!(f, "Too many values with '{data}', maximum amount {max}")
             }
+            Self::IncompleteValue {
+                section,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Incomplete value in region {section:?}, got {got} of {expected} components"
+            ),
             Self::InvalidSectionType(section) => write!(f, "Invalid section name: {section}"),
+            Self::MismatchedLength {
+                section,
+                expected,
+                got,
+            } => write!(
+                f,
+                "Region {section:?} has {got} values, expected {expected} (one per vertex) or none"
+            ),
+            Self::InvalidMesh(err) => write!(f, "Invalid mesh: {err}"),
             Self::CouldntReadFile(err) => write!(f, "couldn't read file: {err}"),
             Self::CouldntOpenFile(err) => write!(f, "couldn't open file: {err}"),
         }
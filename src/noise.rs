@@ -0,0 +1,207 @@
+//! Seeded noise used for procedural content, such as terrain. Stores:
+//! - `PerlinNoise`: 2D and 3D perlin noise
+//!
+//! The noise is dependency-free and reproducible, the same seed always produces the same values.
+
+/// Perlin noise, with a permutation table shuffled by a seed.
+#[derive(Debug, Clone)]
+pub struct PerlinNoise {
+    /// The numbers 0 to 255 shuffled, then repeated twice to avoid wrapping indices
+    permutation: [u8; 512],
+}
+impl PerlinNoise {
+    /// Creates new perlin noise.
+    /// # Arguements
+    /// - `seed`: the seed used to shuffle the permutation table
+    /// # Returns
+    /// `PerlinNoise`
+    pub fn new(seed: u64) -> Self {
+        let mut table = [0_u8; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+
+        // Fisher-Yates shuffle
+        let mut state = seed;
+        for i in (1..table.len()).rev() {
+            let j = (Self::splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0_u8; 512];
+        permutation[..256].copy_from_slice(&table);
+        permutation[256..].copy_from_slice(&table);
+        Self { permutation }
+    }
+
+    /// Gets the next pseudo-random number.
+    fn splitmix64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// The smoothstep curve `6t^5 - 15t^4 + 10t^3`.
+    fn fade(t: f32) -> f32 {
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+    }
+
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        self.permutation[i] as usize
+    }
+
+    /// The dot product of a pseudo-random 2D gradient and the distance vector.
+    fn grad_2d(hash: usize, x: f32, y: f32) -> f32 {
+        match hash & 7 {
+            0 => x + y,
+            1 => -x + y,
+            2 => x - y,
+            3 => -x - y,
+            4 => x,
+            5 => -x,
+            6 => y,
+            _ => -y,
+        }
+    }
+
+    /// The dot product of a pseudo-random 3D gradient (an edge of a cube) and the distance vector.
+    fn grad_3d(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+        match hash & 15 {
+            0 | 12 => x + y,
+            1 | 14 => -x + y,
+            2 => x - y,
+            3 => -x - y,
+            4 => x + z,
+            5 => -x + z,
+            6 => x - z,
+            7 => -x - z,
+            8 => y + z,
+            9 | 13 => -y + z,
+            10 => y - z,
+            _ => -y - z,
+        }
+    }
+
+    /// Samples 2D noise.
+    /// # Arguements
+    /// - `x`: x axis
+    /// - `y`: y axis
+    /// # Returns
+    /// A value between -1.0 and 1.0
+    /// # Note
+    /// Integer coordinates always return 0.0, so scale the coordinates by a frequency.
+    pub fn get_2d(&self, x: f32, y: f32) -> f32 {
+        let (x_floor, y_floor) = (x.floor(), y.floor());
+        let (xi, yi) = (
+            (x_floor as i64 & 255) as usize,
+            (y_floor as i64 & 255) as usize,
+        );
+        let (xf, yf) = (x - x_floor, y - y_floor);
+        let (u, v) = (Self::fade(xf), Self::fade(yf));
+
+        let a = self.hash(xi) + yi;
+        let b = self.hash(xi + 1) + yi;
+
+        let bottom = Self::lerp(
+            Self::grad_2d(self.hash(a), xf, yf),
+            Self::grad_2d(self.hash(b), xf - 1.0, yf),
+            u,
+        );
+        let top = Self::lerp(
+            Self::grad_2d(self.hash(a + 1), xf, yf - 1.0),
+            Self::grad_2d(self.hash(b + 1), xf - 1.0, yf - 1.0),
+            u,
+        );
+
+        Self::lerp(bottom, top, v).clamp(-1.0, 1.0)
+    }
+
+    /// Samples 3D noise.
+    /// # Arguements
+    /// - `x`: x axis
+    /// - `y`: y axis
+    /// - `z`: z axis
+    /// # Returns
+    /// A value between -1.0 and 1.0
+    /// # Note
+    /// Integer coordinates always return 0.0, so scale the coordinates by a frequency.
+    pub fn get_3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (x_floor, y_floor, z_floor) = (x.floor(), y.floor(), z.floor());
+        let (xi, yi, zi) = (
+            (x_floor as i64 & 255) as usize,
+            (y_floor as i64 & 255) as usize,
+            (z_floor as i64 & 255) as usize,
+        );
+        let (xf, yf, zf) = (x - x_floor, y - y_floor, z - z_floor);
+        let (u, v, w) = (Self::fade(xf), Self::fade(yf), Self::fade(zf));
+
+        let a = self.hash(xi) + yi;
+        let aa = self.hash(a) + zi;
+        let ab = self.hash(a + 1) + zi;
+        let b = self.hash(xi + 1) + yi;
+        let ba = self.hash(b) + zi;
+        let bb = self.hash(b + 1) + zi;
+
+        let near = Self::lerp(
+            Self::lerp(
+                Self::grad_3d(self.hash(aa), xf, yf, zf),
+                Self::grad_3d(self.hash(ba), xf - 1.0, yf, zf),
+                u,
+            ),
+            Self::lerp(
+                Self::grad_3d(self.hash(ab), xf, yf - 1.0, zf),
+                Self::grad_3d(self.hash(bb), xf - 1.0, yf - 1.0, zf),
+                u,
+            ),
+            v,
+        );
+        let far = Self::lerp(
+            Self::lerp(
+                Self::grad_3d(self.hash(aa + 1), xf, yf, zf - 1.0),
+                Self::grad_3d(self.hash(ba + 1), xf - 1.0, yf, zf - 1.0),
+                u,
+            ),
+            Self::lerp(
+                Self::grad_3d(self.hash(ab + 1), xf, yf - 1.0, zf - 1.0),
+                Self::grad_3d(self.hash(bb + 1), xf - 1.0, yf - 1.0, zf - 1.0),
+                u,
+            ),
+            v,
+        );
+
+        Self::lerp(near, far, w).clamp(-1.0, 1.0)
+    }
+}
+
+/// Samples 2D perlin noise.
+/// # Arguements
+/// - `x`: x axis
+/// - `y`: y axis
+/// - `seed`: the seed of the noise
+/// # Returns
+/// A value between -1.0 and 1.0
+/// # Note
+/// This builds the permutation table every call, when sampling many points use `PerlinNoise`.
+pub fn perlin_2d(x: f32, y: f32, seed: u64) -> f32 {
+    PerlinNoise::new(seed).get_2d(x, y)
+}
+
+/// Samples 3D perlin noise.
+/// # Arguements
+/// - `x`: x axis
+/// - `y`: y axis
+/// - `z`: z axis
+/// - `seed`: the seed of the noise
+/// # Returns
+/// A value between -1.0 and 1.0
+/// # Note
+/// This builds the permutation table every call, when sampling many points use `PerlinNoise`.
+pub fn perlin_3d(x: f32, y: f32, z: f32, seed: u64) -> f32 {
+    PerlinNoise::new(seed).get_3d(x, y, z)
+}
@@ -0,0 +1,20 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+layout(location = 1) in vec3 aColor;
+layout(location = 2) in vec2 aCoord;
+layout(location = 3) in vec3 aNormal;
+layout(location = 4) in mat4 aModel; // per instance, uses locations 4 to 7
+
+out vec3 vertColor;
+out vec2 coord;
+out vec3 normal;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+void main() {
+    gl_Position = projection * view * aModel * vec4(aPos, 1.0);
+    vertColor = aColor;
+    coord = aCoord;
+    normal = transpose(inverse(mat3(aModel))) * aNormal;
+}
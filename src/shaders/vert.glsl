@@ -1,14 +1,21 @@
 #version 330 core
 layout(location = 0) in vec3 aPos;
-layout(location = 1) in vec2 aCoord;
+layout(location = 1) in vec3 aColor;
+layout(location = 2) in vec2 aCoord;
+layout(location = 3) in vec3 aNormal;
 
+out vec3 vertColor;
 out vec2 coord;
+out vec3 normal;
 
 uniform mat4 model;
+uniform mat3 normal_matrix;
 uniform mat4 view;
 uniform mat4 projection;
 
 void main() {
     gl_Position = projection * view * model * vec4(aPos, 1.0);
+    vertColor = aColor;
     coord = aCoord;
+    normal = normal_matrix * aNormal;
 }
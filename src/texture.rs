@@ -1,10 +1,129 @@
 //! Used for the creation and defination of textures. Used in rendering images on meshes.
-use std::{fs, io, ptr::null_mut};
+use std::{cell::Cell, fs, io, ptr::null_mut, rc::Rc, slice};
 
-use ogl33::glGenBuffers;
+use ogl33::*;
+
+use crate::{
+    datatypes::{color::Color3, vectors::Vector2},
+    gl_helper::{ShaderProgram, has_gl_context},
+};
+
+thread_local! {
+    /// The amount of GL textures created on this thread that haven't been deleted. GL contexts
+    /// are current to one thread, so the count is per thread.
+    static LIVE_TEXTURES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Gets the amount of textures uploaded to GL that are still alive, on the current thread.
+/// # Returns
+/// The amount of live GL textures
+/// # Note
+/// Useful for finding GPU memory leaks, e.g. textures kept alive by removed parts.
+pub fn live_texture_count() -> usize {
+    LIVE_TEXTURES.with(Cell::get)
+}
+
+/// How texture coordinates outside of 0.0 to 1.0 are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureWrap {
+    /// GL_REPEAT: the texture repeats
+    #[default]
+    Repeat = GL_REPEAT as isize,
+    /// GL_MIRRORED_REPEAT: the texture repeats, mirrored every other repeat
+    MirroredRepeat = GL_MIRRORED_REPEAT as isize,
+    /// GL_CLAMP_TO_EDGE: the edge pixels are stretched
+    ClampToEdge = GL_CLAMP_TO_EDGE as isize,
+    /// GL_CLAMP_TO_BORDER: the border color is used
+    ClampToBorder = GL_CLAMP_TO_BORDER as isize,
+}
+impl TextureWrap {
+    /// Wraps a pixel coordinate into the texture.
+    /// # Note
+    /// `ClampToBorder` is treated like `ClampToEdge`, since there is no border color.
+    fn wrap_pixel(&self, i: i32, size: i32) -> i32 {
+        match self {
+            Self::Repeat => i.rem_euclid(size),
+            Self::MirroredRepeat => {
+                let i = i.rem_euclid(size * 2);
+                if i >= size { size * 2 - 1 - i } else { i }
+            }
+            Self::ClampToEdge | Self::ClampToBorder => i.clamp(0, size - 1),
+        }
+    }
+}
+
+/// How the texture is sampled when it is magnified or minified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureFilter {
+    /// GL_NEAREST: the nearest pixel, used for pixel-art
+    Nearest = GL_NEAREST as isize,
+    /// GL_LINEAR: the weighted average of the nearest 4 pixels
+    #[default]
+    Linear = GL_LINEAR as isize,
+    /// GL_NEAREST_MIPMAP_NEAREST: the nearest pixel of the nearest mipmap (minifying only)
+    NearestMipmapNearest = GL_NEAREST_MIPMAP_NEAREST as isize,
+    /// GL_LINEAR_MIPMAP_NEAREST: linear filtering of the nearest mipmap (minifying only)
+    LinearMipmapNearest = GL_LINEAR_MIPMAP_NEAREST as isize,
+    /// GL_NEAREST_MIPMAP_LINEAR: the nearest pixel, blended between mipmaps (minifying only)
+    NearestMipmapLinear = GL_NEAREST_MIPMAP_LINEAR as isize,
+    /// GL_LINEAR_MIPMAP_LINEAR: linear filtering, blended between mipmaps (minifying only)
+    LinearMipmapLinear = GL_LINEAR_MIPMAP_LINEAR as isize,
+}
+
+/// The sampling parameters of a texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextureParams {
+    /// The wrapping of the horizontal axis
+    pub wrap_s: TextureWrap,
+    /// The wrapping of the vertical axis
+    pub wrap_t: TextureWrap,
+    /// The filter used when the texture is minified
+    pub min_filter: TextureFilter,
+    /// The filter used when the texture is magnified
+    /// # Note
+    /// Only `Nearest` and `Linear` are valid.
+    pub mag_filter: TextureFilter,
+}
+impl TextureParams {
+    /// Creates new texture parameters, using the same wrapping and filter for both axes.
+    /// # Arguements
+    /// - `wrap`: the wrapping of both axes
+    /// - `filter`: the filter used when minifying and magnifying
+    /// # Returns
+    /// `TextureParams`
+    pub fn new(wrap: TextureWrap, filter: TextureFilter) -> Self {
+        Self {
+            wrap_s: wrap,
+            wrap_t: wrap,
+            min_filter: filter,
+            mag_filter: filter,
+        }
+    }
+
+    /// Applies the parameters to the bound `GL_TEXTURE_2D`.
+    fn apply(&self) {
+        unsafe {
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, self.wrap_s as GLint);
+            glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, self.wrap_t as GLint);
+            glTexParameteri(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_MIN_FILTER,
+                self.min_filter as GLint,
+            );
+            glTexParameteri(
+                GL_TEXTURE_2D,
+                GL_TEXTURE_MAG_FILTER,
+                self.mag_filter as GLint,
+            );
+        }
+    }
+}
 
 /// A texture usable inside of the engine.
-#[derive(Debug, Clone)]
+/// # Note
+/// The pixel data is freed when the texture is dropped, so it can't be cloned. Share a texture
+/// with `Rc` instead.
+#[derive(Debug)]
 pub struct Texture {
     /// The images's width
     pub width: i32,
@@ -14,22 +133,32 @@ pub struct Texture {
     pub pixels: *mut u8,
     /// The colour space of the image
     pub comp: i32,
-    /// The gl buffer
-    pub texture_id: u32,
+    /// The GL texture object, 0 until uploaded with `load_to_gl`
+    texture_id: GLuint,
+    /// If any pixel isn't fully opaque
+    translucent: bool,
+    /// The sampling parameters
+    params: TextureParams,
 }
 impl Texture {
     /// Make a texture from a byte vector
     /// # Arguements
     /// - `data`: a byte vector representing a image
     /// # Returns
-    /// A new texture
-    pub fn new(mut data: Vec<u8>) -> Self {
+    /// Either:
+    /// - `Ok`: A new texture
+    /// - `Err`: An error message, when the image couldn't be decoded or is truncated
+    pub fn new(mut data: Vec<u8>) -> Result<Self, String> {
+        Self::check_truncated(&data)?;
+
         let mut texture = Self {
             width: 0,
             height: 0,
             pixels: null_mut(),
             comp: 0,
             texture_id: 0,
+            translucent: false,
+            params: TextureParams::default(),
         };
 
         unsafe {
@@ -44,48 +173,437 @@ impl Texture {
             );
         }
 
-        texture
+        if texture.pixels.is_null() {
+            return Err(format!(
+                "couldn't decode texture: {}",
+                Self::failure_reason()
+            ));
+        }
+
+        if texture.width <= 0 || texture.height <= 0 {
+            return Err(format!(
+                "invalid texture size {}x{}",
+                texture.width, texture.height
+            ));
+        }
+
+        texture.translucent = texture
+            .pixel_slice()
+            .chunks_exact(4)
+            .any(|pixel| pixel[3] < u8::MAX);
+        Ok(texture)
     }
 
-    /// Loads the texture to gl
+    /// Gets the reason the last image failed to decode.
+    fn failure_reason() -> String {
+        let reason = unsafe { stb_image_rust::stbi__g_failure_reason };
+        if reason.is_empty() {
+            return String::from("unknown reason");
+        }
+
+        reason.to_string()
+    }
+
+    /// The magic bytes at the start of a BMP file.
+    const BMP_MAGIC: &[u8] = b"BM";
+
+    /// Checks the image data isn't shorter than the file size in it's header.
+    /// # Note
+    /// stb decodes a truncated BMP without an error (the missing pixels are left black), so it's
+    /// rejected before decoding. Only BMP headers store the file size, other formats are left to
+    /// stb.
+    fn check_truncated(data: &[u8]) -> Result<(), String> {
+        let Some(size_bytes) = data.get(2..6) else {
+            return Ok(());
+        };
+        if !data.starts_with(Self::BMP_MAGIC) {
+            return Ok(());
+        }
+
+        let file_size = u32::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
+        if data.len() < file_size {
+            return Err(format!(
+                "truncated image: got {} of {} bytes",
+                data.len(),
+                file_size
+            ));
+        }
+        Ok(())
+    }
+
+    /// Uploads the texture to GL, using the texture's `TextureParams`.
+    /// # Note
+    /// - Requires a GL context.
+    /// - Only uploads once, calling it again does nothing.
+    /// - Textures without pixel data aren't uploaded.
     pub fn load_to_gl(&mut self) {
+        if self.texture_id != 0 || self.pixels.is_null() {
+            return;
+        }
+
         unsafe {
-            glGenBuffers(1, &mut self.texture_id);
+            glGenTextures(1, &mut self.texture_id);
+            glBindTexture(GL_TEXTURE_2D, self.texture_id);
+            self.params.apply();
+            glTexImage2D(
+                GL_TEXTURE_2D,
+                0,
+                GL_RGBA as GLint,
+                self.width as GLsizei,
+                self.height as GLsizei,
+                0,
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                self.pixels.cast(),
+            );
+            glGenerateMipmap(GL_TEXTURE_2D);
+            glBindTexture(GL_TEXTURE_2D, 0);
+        }
+        if self.texture_id != 0 {
+            LIVE_TEXTURES.with(|count| count.set(count.get() + 1));
         }
     }
 
+    /// Sets the sampling parameters of the texture.
+    /// # Arguements
+    /// - `params`: the new parameters
+    /// # Note
+    /// Should be called before `load_to_gl`, if the texture is already uploaded then the GL
+    /// texture is updated (requiring a GL context).
+    pub fn set_params(&mut self, params: TextureParams) {
+        self.params = params;
+        if self.texture_id == 0 {
+            return;
+        }
+
+        unsafe { glBindTexture(GL_TEXTURE_2D, self.texture_id) };
+        self.params.apply();
+        unsafe { glBindTexture(GL_TEXTURE_2D, 0) };
+    }
+
+    /// Gets the sampling parameters of the texture.
+    /// # Returns
+    /// The parameters
+    pub fn get_params(&self) -> TextureParams {
+        self.params
+    }
+
+    /// Gets the GL texture object.
+    /// # Returns
+    /// The texture object, or 0 if the texture hasn't been uploaded with `load_to_gl`
+    pub fn get_texture_id(&self) -> GLuint {
+        self.texture_id
+    }
+
+    /// Checks if the texture has been uploaded to GL.
+    /// # Returns
+    /// If `load_to_gl` has been successfully called
+    pub fn is_loaded(&self) -> bool {
+        self.texture_id != 0
+    }
+
     /// Reads the texture file to an texture that would be usable inside the engine.
     /// # Arguements
     /// - `path`: the file's path
     /// # Returns
     /// Either:
     /// - `Ok`: A new texture
-    /// - `Err`: An error message
-    pub fn from_file(path: &str) -> Result<Self, &'static str> {
-        let f_ex = fs::File::open(path);
-        let Ok(mut f) = f_ex else {
-            return Err("couldn't load texture");
-        };
+    /// - `Err`: An error message, when the file couldn't be read or decoded
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut f =
+            fs::File::open(path).map_err(|e| format!("couldn't load texture {}: {}", path, e))?;
 
         let mut data = vec![];
+        io::Read::read_to_end(&mut f, &mut data)
+            .map_err(|e| format!("couldn't read texture {}: {}", path, e))?;
+
+        Self::new(data)
+    }
+
+    /// Does the texture have any pixel that isn't fully opaque?
+    /// # Returns
+    /// If the texture needs blending
+    pub fn is_translucent(&self) -> bool {
+        self.translucent
+    }
 
-        if io::Read::read_to_end(&mut f, &mut data).is_err() {
-            return Err("couldn't read texture");
+    /// Gets the pixels of the texture, as RGBA bytes.
+    /// # Returns
+    /// The pixels, empty if the texture has no pixel data
+    fn pixel_slice(&self) -> &[u8] {
+        if self.pixels.is_null() || self.width <= 0 || self.height <= 0 {
+            return &[];
         }
 
-        Ok(Self::new(data))
+        let len = (self.width * self.height * 4) as usize;
+        unsafe { slice::from_raw_parts(self.pixels, len) }
+    }
+
+    /// Gets the color of a pixel, the coordinates are wrapped using the texture's `TextureParams`.
+    fn get_pixel(&self, x: i32, y: i32) -> [f32; 3] {
+        let (x, y) = (
+            self.params.wrap_s.wrap_pixel(x, self.width),
+            self.params.wrap_t.wrap_pixel(y, self.height),
+        );
+        let i = ((y * self.width + x) * 4) as usize;
+        let pixels = self.pixel_slice();
+        [
+            pixels[i] as f32 / 255.0,
+            pixels[i + 1] as f32 / 255.0,
+            pixels[i + 2] as f32 / 255.0,
+        ]
     }
 
-    /// Frees the texture.
-    fn free(&self) {
+    /// Samples the texture's color with bilinear filtering.
+    /// # Arguements
+    /// - `coord`: the UV coordinate, where (0, 0) is the bottom-left
+    /// # Returns
+    /// Either:
+    /// - `None`: when the texture has no pixel data
+    /// - The sampled color
+    /// # Note
+    /// Coordinates outside of 0.0 to 1.0 are wrapped by `wrap_s` and `wrap_t`, the filters are
+    /// ignored.
+    pub fn sample_bilinear(&self, coord: Vector2) -> Option<Color3> {
+        if self.pixel_slice().is_empty() {
+            return None;
+        }
+
+        let x = coord.x * self.width as f32 - 0.5;
+        let y = coord.y * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+
+        let (bottom_left, bottom_right) = (self.get_pixel(x0, y0), self.get_pixel(x0 + 1, y0));
+        let (top_left, top_right) = (self.get_pixel(x0, y0 + 1), self.get_pixel(x0 + 1, y0 + 1));
+
+        let mut rgb = [0.0; 3];
+        for (i, comp) in rgb.iter_mut().enumerate() {
+            let bottom = bottom_left[i] + (bottom_right[i] - bottom_left[i]) * tx;
+            let top = top_left[i] + (top_right[i] - top_left[i]) * tx;
+            *comp = (bottom + (top - bottom) * ty).clamp(0.0, 1.0);
+        }
+
+        Some(Color3 {
+            r: rgb[0],
+            g: rgb[1],
+            b: rgb[2],
+        })
+    }
+
+    /// Frees the pixel data of the texture.
+    /// # Note
+    /// Called automatically when dropped, freeing the texture multiple times is safe.
+    pub fn free(&mut self) {
+        if self.pixels.is_null() {
+            return;
+        }
+
         unsafe {
             stb_image_rust::c_runtime::free(self.pixels);
         }
+        self.pixels = null_mut();
     }
 }
 
 impl Drop for Texture {
     fn drop(&mut self) {
         self.free();
+        if self.texture_id != 0 && has_gl_context() {
+            unsafe { glDeleteTextures(1, &self.texture_id) };
+            LIVE_TEXTURES.with(|count| count.set(count.get().saturating_sub(1)));
+        }
+    }
+}
+
+/// Named textures, each bound to it's own texture unit. Used by parts and materials.
+///
+/// The name of a slot is the name of the sampler uniform it's bound to, e.g. `texture0` or
+/// `normal_map`.
+#[derive(Debug, Clone, Default)]
+pub struct TextureSlots {
+    /// The textures, shared between clones.
+    /// # Note
+    /// The index of a texture is the texture unit it's bound to.
+    slots: Vec<(String, Rc<Texture>)>,
+}
+impl TextureSlots {
+    /// Gets a texture by it's slot.
+    /// # Arguements
+    /// - `name`: the name of the slot
+    /// # Returns
+    /// Either:
+    /// - The borrowed texture
+    /// - `None`, if the slot is empty
+    pub fn get(&self, name: &str) -> Option<&Texture> {
+        self.slots
+            .iter()
+            .find(|(slot, _)| slot == name)
+            .map(|(_, texture)| texture.as_ref())
+    }
+
+    /// Sets the texture of a slot.
+    /// # Arguements
+    /// - `name`: the name of the slot
+    /// - `texture`: the new texture to be assigned
+    /// # Note
+    /// - A new slot is bound to the next texture unit, replacing a texture keeps it's unit.
+    /// - Uploads the texture to GL, so requires a GL context.
+    pub fn set(&mut self, name: &str, mut texture: Texture) {
+        texture.load_to_gl();
+        let texture = Rc::new(texture);
+
+        match self.slots.iter_mut().find(|(slot, _)| slot == name) {
+            Some((_, old)) => *old = texture,
+            None => self.slots.push((name.to_string(), texture)),
+        }
+    }
+
+    /// Removes every texture, the GL textures are deleted once no other slots share them.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+
+    /// Gets the amount of slots, which is also the first unused texture unit.
+    /// # Returns
+    /// The amount of slots
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Are there no slots?
+    /// # Returns
+    /// If there are no textures
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Iterates over the textures, with the texture unit each is bound to.
+    /// # Returns
+    /// An iterator of the texture unit, the slot's name and the texture
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str, &Texture)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(unit, (name, texture))| (unit as u32, name.as_str(), texture.as_ref()))
+    }
+
+    /// Does any texture have a pixel that isn't fully opaque?
+    /// # Returns
+    /// If the textures need blending
+    pub fn is_translucent(&self) -> bool {
+        self.slots
+            .iter()
+            .any(|(_, texture)| texture.is_translucent())
+    }
+
+    /// Binds every texture to it's texture unit, and points the slot's sampler uniform at the
+    /// unit.
+    /// # Arguements
+    /// - `program`: the program the sampler uniforms are set in, must be in use
+    /// # Note
+    /// The active texture unit is reset to `GL_TEXTURE0` afterwards.
+    pub fn bind(&self, program: &ShaderProgram) {
+        for (unit, name, texture) in self.iter() {
+            program.set_int(name, unit as i32);
+            unsafe {
+                glActiveTexture(GL_TEXTURE0 + unit);
+                glBindTexture(GL_TEXTURE_2D, texture.get_texture_id());
+            }
+        }
+
+        unsafe { glActiveTexture(GL_TEXTURE0) };
+    }
+}
+
+/// The largest amount of bytes in an uncompressed deflate block.
+const MAX_STORED_BLOCK: usize = 65535;
+
+/// Encodes RGBA pixels as a PNG image.
+/// # Arguements
+/// - `width`: the width of the image
+/// - `height`: the height of the image
+/// - `rgba`: the pixels as RGBA bytes, rows from the top to the bottom
+/// # Returns
+/// Either:
+/// - `Ok`: the PNG file's bytes
+/// - `Err`: An error message, when the size doesn't match the pixels
+/// # Note
+/// The image data isn't compressed (stored deflate blocks), so the file is about the same size
+/// as the pixels.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let row_len = width as usize * 4;
+    if width == 0 || height == 0 || rgba.len() != row_len * height as usize {
+        return Err(format!(
+            "{} bytes isn't a {width}x{height} RGBA image",
+            rgba.len()
+        ));
+    }
+
+    // each row starts with the filter type, 0 is none
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in rgba.chunks_exact(row_len) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // zlib header, stored blocks, then the adler-32 checksum
+    let mut zlib = Vec::with_capacity(raw.len() + raw.len() / MAX_STORED_BLOCK * 5 + 11);
+    zlib.extend_from_slice(&[0x78, 0x01]);
+    let block_count = raw.len().div_ceil(MAX_STORED_BLOCK);
+    for (i, block) in raw.chunks(MAX_STORED_BLOCK).enumerate() {
+        let len = block.len() as u16;
+        zlib.push((i + 1 == block_count) as u8);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bit depth, RGBA, deflate, no filter, no interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = Vec::with_capacity(zlib.len() + 64);
+    png.extend_from_slice(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']);
+    write_png_chunk(&mut png, b"IHDR", &header);
+    write_png_chunk(&mut png, b"IDAT", &zlib);
+    write_png_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+/// Writes a PNG chunk: the length, type, data and the CRC of the type and data.
+fn write_png_chunk(png: &mut Vec<u8>, ty: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(ty);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// The CRC-32 used by PNG (ISO 3309).
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// The Adler-32 checksum used by zlib.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
     }
+    (b << 16) | a
 }
@@ -1,18 +1,39 @@
 //! Used for the `Window` helper structure. Containing various GL objects.
 
-use std::{cell::RefCell, ptr, rc::Rc};
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::VecDeque,
+    ffi::c_void,
+    fs, ptr,
+    rc::Rc,
+    time::Instant,
+};
 
 use beryllium::{
     events::Event,
     init::InitFlags,
-    video::{CreateWinArgs, GlContextFlags, GlProfile, GlWindow},
+    video::{CreateWinArgs, GlContextFlags, GlProfile, GlSwapInterval, GlWindow},
     *,
 };
 use ogl33::*;
+use ultraviolet::Mat4;
+use uuid::Uuid;
 
 use crate::{
-    entities::{entity::EntityType, entity_tree::EntityTree, types::part_type::Part},
-    gl_helper::*,
+    datatypes::color::Color3,
+    entities::{
+        entity::EntityType,
+        entity_tree::EntityTree,
+        traits::object_3d::normal_matrix,
+        types::{
+            camera_type::Camera,
+            io_service::InputService,
+            part_type::{DIFFUSE_TEXTURE_SLOT, Part},
+        },
+    },
+    gl_helper::{DepthFunc, FrontFace, Viewport, *},
+    mesh::{Mesh, VertexData},
+    texture::{Texture, encode_png},
 };
 
 /// Takes a string literal and concatenates a null byte onto the end.
@@ -23,6 +44,162 @@ macro_rules! null_str {
         concat!($lit, "\0")
     }};
 }
+
+/// The fragment shader used in the depth prepass, it outputs nothing.
+const DEPTH_FRAG_SHADER: &str = include_str!("shaders/depth_frag.glsl");
+/// The vertex shader used when drawing instances, it reads the model matrix from
+/// `INSTANCE_ATTRIBUTE_LOCATION` instead of a uniform.
+const INSTANCED_VERT_SHADER: &str = include_str!("shaders/instanced_vert.glsl");
+
+/// The first location of the per-instance model matrix, a `mat4` uses 4 locations (one for
+/// each column).
+pub const INSTANCE_ATTRIBUTE_LOCATION: GLuint = 4;
+
+/// A 1x1 white bitmap, loaded as the white texture (see `Window::init_objects`).
+const WHITE_BMP: &[u8] = &[
+    b'B', b'M', 58, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0, // file header
+    40, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 24, 0, // info header
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, // unused
+    255, 255, 255, 0, // the pixel, padded to 4 bytes
+];
+
+/// How the window's buffer swaps are synchronised to the display's refresh rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VsyncMode {
+    /// Swaps happen immediately, which can cause tearing
+    Off,
+    /// Swaps wait for the display's refresh
+    On,
+    /// Swaps wait for the display's refresh, unless the frame is late
+    Adaptive,
+}
+impl VsyncMode {
+    /// Converts the mode into SDL's swap interval.
+    fn to_swap_interval(self) -> GlSwapInterval {
+        match self {
+            Self::Off => GlSwapInterval::Immediate,
+            Self::On => GlSwapInterval::Vsync,
+            Self::Adaptive => GlSwapInterval::AdaptiveVsync,
+        }
+    }
+}
+
+/// The largest delta returned by `FrameTimer::tick`, in seconds.
+///
+/// Longer frames (e.g. after a breakpoint or dragging the window) are clamped to this, so
+/// updates don't take one huge step.
+pub const MAX_FRAME_DELTA: f32 = 0.25;
+
+/// Measures the time between frames.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameTimer {
+    last_frame: Instant,
+    last_frame_time: f32,
+}
+impl FrameTimer {
+    /// Creates a new frame timer, starting now.
+    /// # Returns
+    /// `FrameTimer`
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    /// Creates a new frame timer, starting at `start`.
+    /// # Arguements
+    /// - `start`: when the first frame started
+    /// # Returns
+    /// `FrameTimer`
+    pub fn starting_at(start: Instant) -> Self {
+        Self {
+            last_frame: start,
+            last_frame_time: 0.0,
+        }
+    }
+
+    /// Starts a new frame.
+    /// # Returns
+    /// The seconds since the last frame, clamped to `MAX_FRAME_DELTA`
+    pub fn tick(&mut self) -> f32 {
+        self.tick_at(Instant::now())
+    }
+
+    /// Starts a new frame at `now`, instead of the current time.
+    /// # Arguements
+    /// - `now`: when the new frame started, not before the last frame
+    /// # Returns
+    /// The seconds since the last frame, clamped to `MAX_FRAME_DELTA`
+    pub fn tick_at(&mut self, now: Instant) -> f32 {
+        self.last_frame_time = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        self.last_frame_time.min(MAX_FRAME_DELTA)
+    }
+
+    /// Gets the length of the last frame, measured by `tick` (or `tick_at`).
+    /// # Returns
+    /// The seconds since the frame before, not clamped
+    pub fn last_frame_time(&self) -> f32 {
+        self.last_frame_time
+    }
+}
+impl Default for FrameTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The amount of frames averaged by `FrameStats`.
+pub const FRAME_STATS_WINDOW: usize = 60;
+
+/// A rolling average of frame times, used to measure performance.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    frame_times: VecDeque<f32>,
+    total: f32,
+}
+impl FrameStats {
+    /// Adds the length of a frame, forgetting the oldest once there are `FRAME_STATS_WINDOW`
+    /// frames.
+    /// # Arguements
+    /// - `frame_time`: the length of the frame, in seconds
+    pub fn record(&mut self, frame_time: f32) {
+        if self.frame_times.len() == FRAME_STATS_WINDOW
+            && let Some(oldest) = self.frame_times.pop_front()
+        {
+            self.total -= oldest;
+        }
+        self.frame_times.push_back(frame_time);
+        self.total += frame_time;
+    }
+
+    /// Gets the average length of a frame.
+    /// # Returns
+    /// The average in milliseconds, 0.0 if no frames were recorded
+    pub fn frame_time_ms(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        self.total / self.frame_times.len() as f32 * 1000.0
+    }
+
+    /// Gets the average frames per second.
+    /// # Returns
+    /// The frames per second, 0.0 if no frames were recorded
+    pub fn fps(&self) -> f32 {
+        let frame_time = self.frame_time_ms();
+        if frame_time <= 0.0 {
+            return 0.0;
+        }
+        1000.0 / frame_time
+    }
+}
+
+/// Statistics of what has been rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// The amount of draw calls issued
+    pub draw_calls: u32,
+}
+
 /// A wrapper for `GlWindow`, shader program and multiple GL objects:
 /// - `vao`,
 /// - `vbo` and
@@ -36,10 +213,35 @@ pub struct Window {
     pub ebo: Buffer,
     /// The shader program used in GL.
     pub shader_program: ShaderProgram,
-    /// Simple DirectMedia Layer
-    pub sdl: Sdl,
+    /// The shader program used in the depth prepass.
+    /// Can be `None`, see `init_depth_prepass`.
+    pub depth_shader_program: Option<ShaderProgram>,
+    /// The shader program used when drawing instances.
+    /// Can be `None`, see `init_instancing`.
+    pub instanced_shader_program: Option<ShaderProgram>,
+    /// The buffer of per-instance model matrices, see `draw_instanced`.
+    pub instance_buffer: Buffer,
+    /// The statistics of the current frame
+    render_stats: Cell<RenderStats>,
+    /// A 1x1 white texture, sampled by parts without a texture so they're drawn with their
+    /// `color`. Can be `None`, see `init_objects`.
+    white_texture: Option<Texture>,
+    /// The frame times measured by the render loop
+    frame_stats: RefCell<FrameStats>,
+    /// The color each frame is cleared to
+    clear_color: Cell<Color3>,
+    /// The buffers cleared each frame
+    clear_flags: Cell<ClearFlags>,
     /// The GL window
+    /// # Note
+    /// Declared after the GL objects, so they are dropped before the context.
     pub window: GlWindow,
+    /// Marks the context as alive, so GL objects outliving the window aren't deleted without it
+    _context: ContextGuard,
+    /// Simple DirectMedia Layer
+    /// # Note
+    /// Must be the last field, so SDL is torn down after the window.
+    pub sdl: Sdl,
 }
 impl Window {
     /// Creates a new window, with Gl objects uninitilised.
@@ -57,8 +259,17 @@ impl Window {
 
         let win_struct = Self {
             window: win,
+            _context: ContextGuard::acquire(),
             sdl,
             shader_program: ShaderProgram(0),
+            depth_shader_program: None,
+            instanced_shader_program: None,
+            instance_buffer: Buffer(0),
+            render_stats: Cell::new(RenderStats::default()),
+            white_texture: None,
+            frame_stats: RefCell::new(FrameStats::default()),
+            clear_color: Cell::new(Color3::black()),
+            clear_flags: Cell::new(ClearFlags::default()),
             vao: VertexArray(0),
             vbo: Buffer(0),
             ebo: Buffer(0),
@@ -67,64 +278,350 @@ impl Window {
         Ok(win_struct)
     }
 
-    /// Initilises the objects and program for the window
+    /// Creates a new window that isn't shown, with Gl objects uninitilised.
+    ///
+    /// Used for tests and offscreen rendering, the GL context works the same as a shown window.
+    /// # Arguements
+    /// - `args`: arguements to create the window
+    /// # Returns
+    /// The hidden window. However can throw an error when it could create a window and context.
+    /// # Note
+    /// - A display (or a virtual display, like Xvfb) is still needed to create a context.
+    /// - `beryllium` can't create a hidden window, so the window is hidden straight after it's
+    ///   created, through the context's current window. The window can briefly be visable
+    ///   before it's hidden.
+    pub fn new_hidden(args: CreateWinArgs) -> Result<Self, &'static str> {
+        let window = Self::new(args)?;
+        unsafe {
+            let sdl_window = fermium::video::SDL_GL_GetCurrentWindow();
+            if sdl_window.is_null() {
+                return Err("couldn't get the window of the GL context");
+            }
+            fermium::video::SDL_HideWindow(sdl_window);
+        }
+        Ok(window)
+    }
+
+    /// Reads the pixels of the color buffer.
+    /// # Arguements
+    /// - `x`: the left of the rectangle, in pixels
+    /// - `y`: the bottom of the rectangle, in pixels
+    /// - `width`: the width of the rectangle
+    /// - `height`: the height of the rectangle
+    /// # Returns
+    /// The pixels as RGBA bytes, rows from the bottom to the top
+    /// # Note
+    /// A negative size returns no pixels.
+    pub fn read_pixels(&self, x: i32, y: i32, width: i32, height: i32) -> Vec<u8> {
+        if width <= 0 || height <= 0 {
+            return vec![];
+        }
+
+        let mut pixels = vec![0_u8; width as usize * height as usize * 4];
+        unsafe {
+            glPixelStorei(GL_PACK_ALIGNMENT, 1);
+            glReadPixels(
+                x,
+                y,
+                width,
+                height,
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                pixels.as_mut_ptr().cast(),
+            );
+        }
+        pixels
+    }
+
+    /// Saves the color buffer of the window as a PNG image.
+    /// # Arguements
+    /// - `path`: the path of the image
+    /// # Returns
+    /// Either:
+    /// - `Ok`: the image was saved
+    /// - `Err`: An error message
+    /// # Note
+    /// The whole drawable area is captured, call it after rendering but before swapping the
+    /// window.
+    pub fn capture_png(&self, path: &str) -> Result<(), String> {
+        let (width, height) = self.window.get_drawable_size();
+        let pixels = self.read_pixels(0, 0, width, height);
+
+        // GL's rows start at the bottom, PNG's at the top
+        let row_len = width.max(0) as usize * 4;
+        let flipped: Vec<u8> = pixels
+            .chunks_exact(row_len.max(1))
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        let png = encode_png(width.max(0) as u32, height.max(0) as u32, &flipped)?;
+        fs::write(path, png).map_err(|e| format!("couldn't write {path}: {e}"))
+    }
+
+    /// Sets how buffer swaps are synchronised to the display.
+    /// # Arguements
+    /// - `mode`: the requested vsync mode
+    /// # Returns
+    /// The mode that was actually granted, `Adaptive` falls back to `On` when unsupported.
+    /// # Note
+    /// Returns `Off` if no mode could be set.
+    pub fn set_vsync_mode(&self, mode: VsyncMode) -> VsyncMode {
+        let mut requested = mode;
+        loop {
+            if self
+                .window
+                .set_swap_interval(requested.to_swap_interval())
+                .is_ok()
+            {
+                return requested;
+            }
+
+            requested = match requested {
+                VsyncMode::Adaptive => VsyncMode::On,
+                VsyncMode::On | VsyncMode::Off => return VsyncMode::Off,
+            };
+        }
+    }
+
+    /// Initilises the objects and program for the window.
+    ///
+    /// Binds the `vao`, `vbo` and `ebo`, applies the `VertexData` layout and uses the program,
+    /// so the window is ready for `render_tree`. Also makes the white texture, drawn on parts
+    /// without a texture. Depth testing is enabled, with `DepthFunc::Less`, and back faces are
+    /// culled, with counter-clockwise front faces.
+    /// # Arguements
+    /// - `vert`: the vertex shader source code
+    /// - `frag`: the fragment shader source code
     /// # Returns
     /// Nothing or an error message.
-    pub fn init_objects(&mut self, vert: &str, frag: &str) -> Result<(), &'static str> {
-        let vao_null = VertexArray::new();
-        let Some(vao) = vao_null else {
-            return Err("couldn't make a vao");
-        };
+    pub fn init_objects(&mut self, vert: &str, frag: &str) -> Result<(), String> {
+        let vao = VertexArray::new().map_err(|e| format!("couldn't make a vao: {}", e))?;
         vao.bind();
         self.vao = vao;
 
-        let vbo_null = Buffer::new();
-        let Some(vbo) = vbo_null else {
-            return Err("couldn't make a vbo");
-        };
+        let vbo = Buffer::new().map_err(|e| format!("couldn't make a vbo: {}", e))?;
         vbo.bind(BufferType::Array);
         self.vbo = vbo;
 
-        let ebo_null = Buffer::new();
-        let Some(ebo) = ebo_null else {
-            return Err("couldn't make a ebo");
-        };
+        let ebo = Buffer::new().map_err(|e| format!("couldn't make a ebo: {}", e))?;
         ebo.bind(BufferType::ElementArray);
         self.ebo = ebo;
 
-        let shader_program_ex =
-            ShaderProgram::from_vert_frag(vert, frag).inspect_err(|e| println!("{}", e));
-        let Ok(shader_program) = shader_program_ex else {
-            return Err("couldn't make shader program");
-        };
-        self.shader_program = shader_program;
+        self.shader_program = ShaderProgram::from_vert_frag(vert, frag)
+            .map_err(|e| format!("couldn't make shader program: {}", e))?;
+
+        VertexData::layout().apply();
+        self.shader_program.use_program();
+        self.shader_program.set_int(null_str!("texture0"), 0);
+
+        let mut white_texture = Texture::new(WHITE_BMP.to_vec())
+            .map_err(|e| format!("couldn't make the white texture: {}", e))?;
+        white_texture.load_to_gl();
+        self.white_texture = Some(white_texture);
+
+        self.set_depth_test(true);
+        self.set_depth_func(DepthFunc::Less);
+        self.set_front_face(FrontFace::Ccw);
+        self.set_cull_mode(CullMode::Back);
+        Ok(())
+    }
+
+    /// Enables or disables depth testing, so parts are drawn by depth instead of in order.
+    /// # Arguements
+    /// - `enabled`: if fragments are tested against the depth buffer
+    pub fn set_depth_test(&self, enabled: bool) {
+        depth_test(enabled);
+    }
+
+    /// Sets the color each frame is cleared to, applied by `render_frame` and `render_split`.
+    /// # Arguements
+    /// - `color`: the background color, black by default
+    pub fn set_clear_color(&self, color: Color3) {
+        self.clear_color.set(color);
+    }
+
+    /// Gets the color each frame is cleared to.
+    /// # Returns
+    /// The background color
+    pub fn get_clear_color(&self) -> Color3 {
+        self.clear_color.get()
+    }
+
+    /// Sets which buffers are cleared each frame, by `render_frame` and `render_split`.
+    /// # Arguements
+    /// - `flags`: the buffers, the color and depth buffers by default
+    pub fn set_clear_flags(&self, flags: ClearFlags) {
+        self.clear_flags.set(flags);
+    }
+
+    /// Gets which buffers are cleared each frame.
+    /// # Returns
+    /// The buffers
+    pub fn get_clear_flags(&self) -> ClearFlags {
+        self.clear_flags.get()
+    }
+
+    /// Clears the current viewport, using the clear color and flags.
+    fn clear(&self) {
+        clear_color(self.clear_color.get());
+        unsafe { glClear(self.clear_flags.get().to_bits()) };
+    }
+
+    /// Enables or disables blending, used by translucent parts.
+    /// # Arguements
+    /// - `enabled`: if fragments are blended with the color buffer
+    /// # Note
+    /// Enabling sets standard alpha blending (`SrcAlpha`, `OneMinusSrcAlpha`), use
+    /// `set_blend_func` afterwards for other blending.
+    pub fn set_blend(&self, enabled: bool) {
+        blend(enabled);
+        if enabled {
+            blend_func(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha);
+        }
+    }
+
+    /// Sets how fragments are blended with the color buffer.
+    /// # Arguements
+    /// - `src`: the factor of the fragment's color
+    /// - `dst`: the factor of the color buffer's color
+    pub fn set_blend_func(&self, src: BlendFactor, dst: BlendFactor) {
+        blend_func(src, dst);
+    }
+
+    /// Sets which faces are culled, see `cull_mode`.
+    /// # Arguements
+    /// - `mode`: the faces being culled, `CullMode::None` disables culling
+    pub fn set_cull_mode(&self, mode: CullMode) {
+        cull_mode(mode);
+    }
+
+    /// Sets the winding order of front faces.
+    /// # Arguements
+    /// - `face`: the winding order, meshes are counter-clockwise (`FrontFace::Ccw`)
+    pub fn set_front_face(&self, face: FrontFace) {
+        front_face(face);
+    }
+
+    /// Sets the comparison used in depth testing.
+    /// # Arguements
+    /// - `func`: the depth comparison, `DepthFunc::Less` by default
+    pub fn set_depth_func(&self, func: DepthFunc) {
+        depth_func(func);
+    }
+
+    /// Initilises the shader program used in the depth prepass.
+    /// # Arguements
+    /// - `vert`: the vertex shader source code, usually the same used by `shader_program`
+    /// # Returns
+    /// Nothing or an error message.
+    pub fn init_depth_prepass(&mut self, vert: &str) -> Result<(), String> {
+        let shader_program = ShaderProgram::from_vert_frag(vert, DEPTH_FRAG_SHADER)
+            .map_err(|e| format!("couldn't make depth prepass shader program: {}", e))?;
+        self.depth_shader_program = Some(shader_program);
         Ok(())
     }
 
+    /// Initilises the shader program and buffer used when drawing instances.
+    /// # Arguements
+    /// - `frag`: the fragment shader source code, usually the same used by `shader_program`
+    /// # Returns
+    /// Nothing or an error message.
+    /// # Note
+    /// Must be called after `init_objects`, as the instance attributes are added to the `vao`.
+    pub fn init_instancing(&mut self, frag: &str) -> Result<(), String> {
+        let shader_program = ShaderProgram::from_vert_frag(INSTANCED_VERT_SHADER, frag)
+            .map_err(|e| format!("couldn't make instanced shader program: {}", e))?;
+        let instance_buffer =
+            Buffer::new().map_err(|e| format!("couldn't make instance buffer: {}", e))?;
+
+        self.vao.bind();
+        instance_buffer.bind(BufferType::Array);
+        // a single instance, so the attributes are never read out of bounds
+        buffer_data(
+            BufferType::Array,
+            Mat4::identity().as_byte_slice(),
+            GL_STREAM_DRAW,
+        );
+        let stride = size_of::<Mat4>() as GLsizei;
+        for column in 0..4 {
+            let location = INSTANCE_ATTRIBUTE_LOCATION + column;
+            let offset = (column as usize * 4 * size_of::<f32>()) as *const c_void;
+            unsafe {
+                glVertexAttribPointer(location, 4, GL_FLOAT, GL_FALSE, stride, offset);
+                glVertexAttribDivisor(location, 1);
+                glEnableVertexAttribArray(location);
+            }
+        }
+        self.vbo.bind(BufferType::Array);
+
+        shader_program.use_program();
+        shader_program.set_int(null_str!("texture0"), 0);
+        self.shader_program.use_program();
+
+        self.instanced_shader_program = Some(shader_program);
+        self.instance_buffer = instance_buffer;
+        Ok(())
+    }
+
+    /// Creates a compute program, loading the compute functions from the window's context.
+    /// # Arguements
+    /// - `source`: the compute shader source code
+    /// # Returns
+    /// Either:
+    /// - The compute program
+    /// - An error message, see `ComputeProgram::new`
+    pub fn create_compute_program(&self, source: &str) -> Result<ComputeProgram, String> {
+        ComputeProgram::new(source, |name| unsafe {
+            self.window.get_proc_address(name.cast()).cast_const()
+        })
+    }
+
     /// Deletes the window.
     ///
     /// Comsumes `self`.
+    /// # Note
+    /// The same as dropping the window, the GL objects are deleted by their `Drop`.
     pub fn delete(self) {
-        unsafe {
-            glDeleteVertexArrays(1, self.vao.0 as *const _);
-            glDeleteBuffers(1, self.vbo.0 as *const _);
-            glDeleteBuffers(1, self.ebo.0 as *const _);
-        }
+        drop(self);
     }
 
-    fn render_part(&self, part: &Part) {
-        if !part.visable {
-            return;
-        }
+    /// Gets the statistics of what has been rendered, since the last reset.
+    /// # Returns
+    /// The render statistics
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats.get()
+    }
 
-        let transform = part.transform;
-        self.shader_program
-            .set_matrix4(null_str!("model"), transform);
-        self.shader_program
-            .set_color3(null_str!("obj_color"), part.color);
+    /// Gets the average frames per second, measured by the render loop.
+    /// # Returns
+    /// The frames per second, averaged over `FRAME_STATS_WINDOW` frames
+    pub fn fps(&self) -> f32 {
+        self.frame_stats.borrow().fps()
+    }
 
-        let mesh = part.get_mesh();
+    /// Gets the average length of a frame, measured by the render loop.
+    /// # Returns
+    /// The average in milliseconds, over `FRAME_STATS_WINDOW` frames
+    pub fn frame_time_ms(&self) -> f32 {
+        self.frame_stats.borrow().frame_time_ms()
+    }
 
+    /// Resets the render statistics, `render_loop` does this every frame.
+    pub fn reset_render_stats(&self) {
+        self.render_stats.set(RenderStats::default());
+    }
+
+    /// Counts a draw call in the render statistics.
+    fn count_draw_call(&self) {
+        let mut stats = self.render_stats.get();
+        stats.draw_calls += 1;
+        self.render_stats.set(stats);
+    }
+
+    /// Uploads the mesh's vertices and indices to the bound `vbo` and `ebo`.
+    fn upload_mesh(mesh: &Mesh) {
         buffer_data(
             BufferType::Array,
             bytemuck::cast_slice(mesh.to_vertex_data_internal().as_slice()),
@@ -135,145 +632,531 @@ impl Window {
             bytemuck::cast_slice(mesh.indices.as_slice()),
             GL_DYNAMIC_DRAW,
         );
+    }
+
+    /// Draws a texture over the entire viewport, using `Mesh::fullscreen_triangle`.
+    /// Used for post-processing, e.g. applying a tonemap to a framebuffer's output.
+    /// # Arguements
+    /// - `texture_id`: the GL texture, bound to texture unit 0 and the `texture0` sampler
+    /// - `program`: the shader program, which uses the same vertex layout as `VertexData`
+    /// # Note
+    /// - Depth testing is disabled while drawing, then restored.
+    /// - The texture coordinates given to the shader are (0, 0) at the bottom-left and (1, 1) at
+    ///   the top-right.
+    pub fn blit_fullscreen(&self, texture_id: GLuint, program: &ShaderProgram) {
+        let depth_enabled = unsafe { glIsEnabled(GL_DEPTH_TEST) } == GL_TRUE;
+        depth_test(false);
 
-        let texture_null = part.get_texture();
+        program.use_program();
+        program.set_int(null_str!("texture0"), 0);
 
-        if let Some(texture) = texture_null {
-            unsafe {
-                glBindTexture(GL_TEXTURE_2D, texture.texture_id);
-                glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_S, GL_REPEAT as GLint);
-                glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_WRAP_T, GL_REPEAT as GLint);
-                glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MIN_FILTER, GL_LINEAR as GLint);
-                glTexParameteri(GL_TEXTURE_2D, GL_TEXTURE_MAG_FILTER, GL_LINEAR as GLint);
-                glTexImage2D(
-                    GL_TEXTURE_2D,
-                    0,
-                    GL_RGBA as GLint,
-                    texture.width as GLsizei,
-                    texture.height as GLsizei,
-                    0,
-                    GL_RGBA,
-                    GL_UNSIGNED_BYTE,
-                    texture.pixels.cast(),
-                );
-                glGenerateMipmap(GL_TEXTURE_2D);
-
-                glDrawElements(
-                    GL_TRIANGLES,
-                    mesh.indices.len() as i32,
-                    GL_UNSIGNED_INT,
-                    ptr::null(),
-                );
-                self.shader_program.use_program();
-            }
+        let mesh = Mesh::fullscreen_triangle();
+        Self::upload_mesh(&mesh);
+        unsafe {
+            glActiveTexture(GL_TEXTURE0);
+            glBindTexture(GL_TEXTURE_2D, texture_id);
+            glDrawElements(
+                mesh.topology as GLenum,
+                mesh.indices.len() as i32,
+                GL_UNSIGNED_INT,
+                ptr::null(),
+            );
         }
+        self.count_draw_call();
+
+        depth_test(depth_enabled);
+        self.shader_program.use_program();
     }
 
-    /// Executes the render loop
+    fn render_part(
+        &self,
+        part: &Part,
+        transform: Mat4,
+        view_projection: (Mat4, Mat4),
+        program: &ShaderProgram,
+    ) {
+        if !part.visable {
+            return;
+        }
+
+        // materials only replace the shading program, not others like the depth program
+        let material = part
+            .material
+            .as_ref()
+            .filter(|_| program.0 == self.shader_program.0);
+
+        let mesh = part.get_mesh();
+        if let Some(material) = material {
+            let (view, projection) = view_projection;
+
+            material.apply();
+            let material_program = material.program.as_ref();
+            material_program.set_matrix4(null_str!("projection"), projection);
+            material_program.set_matrix4(null_str!("view"), view);
+            material_program.set_matrix4(null_str!("model"), transform);
+            material_program.set_matrix3(null_str!("normal_matrix"), normal_matrix(&transform));
+
+            Self::upload_mesh(mesh);
+            Self::draw_elements(mesh);
+            self.count_draw_call();
+
+            program.use_program();
+            return;
+        }
+
+        program.set_matrix4(null_str!("model"), transform);
+        program.set_matrix3(null_str!("normal_matrix"), normal_matrix(&transform));
+        program.set_color3(null_str!("obj_color"), part.color);
+
+        Self::upload_mesh(mesh);
+
+        part.texture_slots().bind(program);
+        if part.get_texture().is_none() {
+            // parts without a texture are drawn with only their `color`
+            self.bind_white_texture(program, part.texture_slots().len() as GLuint);
+        }
+
+        Self::draw_elements(mesh);
+        self.count_draw_call();
+    }
+
+    /// Binds the white texture as the diffuse texture, so the part is drawn with `obj_color`.
+    /// # Arguements
+    /// - `program`: the program the sampler uniform is set in, must be in use
+    /// - `unit`: the texture unit, after the part's other textures
+    fn bind_white_texture(&self, program: &ShaderProgram, unit: GLuint) {
+        let Some(white_texture) = &self.white_texture else {
+            return;
+        };
+
+        program.set_int(DIFFUSE_TEXTURE_SLOT, unit as i32);
+        unsafe {
+            glActiveTexture(GL_TEXTURE0 + unit);
+            glBindTexture(GL_TEXTURE_2D, white_texture.get_texture_id());
+            glActiveTexture(GL_TEXTURE0);
+        }
+    }
+
+    /// Binds a texture to texture unit 0 as the diffuse texture, or the white texture without
+    /// one.
+    /// # Arguements
+    /// - `program`: the program the sampler uniform is set in, must be in use
+    /// - `texture`: the texture, `None` draws with only `obj_color`
+    fn bind_diffuse_texture(&self, program: &ShaderProgram, texture: Option<&Texture>) {
+        let Some(texture) = texture else {
+            self.bind_white_texture(program, 0);
+            return;
+        };
+
+        program.set_int(DIFFUSE_TEXTURE_SLOT, 0);
+        unsafe {
+            glActiveTexture(GL_TEXTURE0);
+            glBindTexture(GL_TEXTURE_2D, texture.get_texture_id());
+        }
+    }
+
+    fn draw_elements(mesh: &Mesh) {
+        unsafe {
+            glDrawElements(
+                mesh.topology as GLenum,
+                mesh.indices.len() as i32,
+                GL_UNSIGNED_INT,
+                ptr::null(),
+            );
+        }
+    }
+
+    /// Sets the `view` and `projection` uniforms of a program, from a camera.
+    /// # Arguements
+    /// - `program`: the program, which is left in use
+    /// - `camera`: the camera
     /// # Note
-    /// The loop doesn't run in a different thread
-    pub fn render_loop(&self, tree_cell: Rc<RefCell<EntityTree>>) {
-        let entity_tree = tree_cell.borrow();
-        let head_binding = entity_tree.get_head().unwrap();
+    /// The aspect ratio is derived from the current `Viewport`.
+    pub fn set_camera_uniforms(&self, program: &ShaderProgram, camera: &Camera) {
+        let aspect_ratio = Viewport::current().aspect_ratio();
 
-        let head = head_binding.borrow();
-        let input_service_entity_null = entity_tree.find_first_child_mut(&head, "InputService");
-        let Some(mut input_service_entity) = input_service_entity_null else {
-            panic!("couldn't find service Entity InputService");
+        program.use_program();
+        program.set_matrix4(null_str!("projection"), camera.get_projection(aspect_ratio));
+        program.set_matrix4(null_str!("view"), camera.get_view_matrix());
+    }
+
+    /// Draws a mesh many times in a single draw call, once for each transform.
+    ///
+    /// Used for many identical parts, e.g. the bricks of a wall.
+    /// # Arguements
+    /// - `mesh`: the mesh drawn by every instance
+    /// - `transforms`: the model matrix of each instance
+    /// - `color`: the color of every instance, like a part's `color`
+    /// - `texture`: the texture of every instance, `None` draws with only the `color`
+    /// # Note
+    /// - Uses `instanced_shader_program`, it's `view` and `projection` uniforms must be set
+    ///   first (see `set_camera_uniforms`).
+    /// - Without `init_instancing`, each instance is drawn seperately with `shader_program`.
+    pub fn draw_instanced(
+        &self,
+        mesh: &Mesh,
+        transforms: &[Mat4],
+        color: Color3,
+        texture: Option<&Texture>,
+    ) {
+        if transforms.is_empty() {
+            return;
+        }
+
+        let Some(program) = self.instanced_shader_program.as_ref() else {
+            self.shader_program.use_program();
+            self.shader_program
+                .set_color3(null_str!("obj_color"), color);
+            self.bind_diffuse_texture(&self.shader_program, texture);
+            Self::upload_mesh(mesh);
+            for transform in transforms {
+                self.shader_program
+                    .set_matrix4(null_str!("model"), *transform);
+                self.shader_program
+                    .set_matrix3(null_str!("normal_matrix"), normal_matrix(transform));
+                Self::draw_elements(mesh);
+                self.count_draw_call();
+            }
+            return;
         };
 
-        let mut last_frame = 0_u32;
-        'main_loop: loop {
-            let current_frame = self.sdl.get_ticks();
-            let delta = (current_frame - last_frame) as f32 / 1000.0;
-            let EntityType::InputService(input_service) = input_service_entity.get_type_mut()
-            else {
-                panic!("couldn't borrow InputService");
+        let instance_data: Vec<u8> = transforms
+            .iter()
+            .flat_map(|transform| transform.as_byte_slice())
+            .copied()
+            .collect();
+        self.instance_buffer.bind(BufferType::Array);
+        buffer_data(BufferType::Array, &instance_data, GL_STREAM_DRAW);
+        self.vbo.bind(BufferType::Array);
+
+        program.use_program();
+        program.set_color3(null_str!("obj_color"), color);
+        self.bind_diffuse_texture(program, texture);
+        Self::upload_mesh(mesh);
+        unsafe {
+            glDrawElementsInstanced(
+                mesh.topology as GLenum,
+                mesh.indices.len() as i32,
+                GL_UNSIGNED_INT,
+                ptr::null(),
+                transforms.len() as GLsizei,
+            );
+        }
+        self.count_draw_call();
+        self.shader_program.use_program();
+    }
+
+    /// Renders every part of the tree from the view of a camera.
+    /// # Arguements
+    /// - `entity_tree`: the tree containing the parts
+    /// - `camera`: the camera used for the view and projection
+    /// # Note
+    /// - The aspect ratio is derived from the current `Viewport`.
+    /// - Hidden parts, or parts with a hidden ancestor, are skipped (see
+    ///   `EntityTree::render_order`).
+    /// - Translucent parts are drawn after the opaque parts, see `draw_translucent`.
+    pub fn render_tree(&self, entity_tree: &EntityTree, camera: &Camera) {
+        self.draw_tree(entity_tree, camera, &self.shader_program, false);
+        self.draw_translucent(entity_tree, camera);
+    }
+
+    /// Renders every part of the tree, after a depth prepass.
+    ///
+    /// The first pass only writes the depth of every opaque part, then the second pass shades
+    /// them with `DepthFunc::Equal`, so each pixel is only shaded once. Translucent parts are
+    /// drawn last, see `draw_translucent`.
+    /// # Arguements
+    /// - `entity_tree`: the tree containing the parts
+    /// - `camera`: the camera used for the view and projection
+    /// # Note
+    /// - A prepass helps when the fragment shader is expensive and parts overlap a lot, but hurts
+    ///   simple scenes as every part is drawn twice.
+    /// - Uses `depth_shader_program` if initilised, otherwise `shader_program` with the color
+    ///   writes masked.
+    /// - Leaves depth testing enabled, with `DepthFunc::Less`.
+    pub fn render_tree_with_prepass(&self, entity_tree: &EntityTree, camera: &Camera) {
+        let depth_program = self
+            .depth_shader_program
+            .as_ref()
+            .unwrap_or(&self.shader_program);
+
+        depth_test(true);
+
+        // depth pass
+        color_mask(false);
+        depth_mask(true);
+        depth_func(DepthFunc::Less);
+        self.draw_tree(entity_tree, camera, depth_program, false);
+
+        // shading pass
+        color_mask(true);
+        depth_mask(false);
+        depth_func(DepthFunc::Equal);
+        self.draw_tree(entity_tree, camera, &self.shader_program, false);
+
+        // translucent parts aren't in the prepass, so they can't be tested with `Equal`
+        depth_func(DepthFunc::Less);
+        self.draw_translucent(entity_tree, camera);
+    }
+
+    /// Draws the translucent parts of the tree, blended over what has already been drawn.
+    /// # Note
+    /// - The parts are tested against the depth buffer, but don't write to it, so translucent
+    ///   parts behind each other are all visable.
+    /// - Blending is enabled (with `set_blend`) while drawing, unless it's already enabled, then
+    ///   disabled again. The depth buffer is writable afterwards.
+    fn draw_translucent(&self, entity_tree: &EntityTree, camera: &Camera) {
+        let blend_enabled = unsafe { glIsEnabled(GL_BLEND) } == GL_TRUE;
+        if !blend_enabled {
+            self.set_blend(true);
+        }
+        depth_mask(false);
+
+        self.draw_tree(entity_tree, camera, &self.shader_program, true);
+
+        depth_mask(true);
+        if !blend_enabled {
+            self.set_blend(false);
+        }
+    }
+
+    /// Draws either the opaque or translucent parts of the tree.
+    /// # Arguements
+    /// - `entity_tree`: the tree containing the parts
+    /// - `camera`: the camera used for the view and projection
+    /// - `program`: the shader program the parts are drawn with
+    /// - `translucent`: if only translucent parts are drawn, otherwise only opaque parts are
+    /// # Note
+    /// Translucent parts are drawn back-to-front, so nearer parts blend over farther parts.
+    fn draw_tree(
+        &self,
+        entity_tree: &EntityTree,
+        camera: &Camera,
+        program: &ShaderProgram,
+        translucent: bool,
+    ) {
+        let aspect_ratio = Viewport::current().aspect_ratio();
+
+        let view = camera.get_view_matrix();
+        let projection = camera.get_projection(aspect_ratio);
+        let frustum = camera.get_frustum(aspect_ratio);
+
+        program.use_program();
+        program.set_matrix4(null_str!("projection"), projection);
+        program.set_matrix4(null_str!("view"), view);
+
+        let mut parts = Vec::new();
+        for id in entity_tree.render_order() {
+            let Some(entity_ref) = entity_tree.entity_map.get(&id) else {
+                continue;
+            };
+            let Ok(entity) = entity_ref.try_borrow() else {
+                continue;
             };
 
-            while let Some((event, _timestamp)) = self.sdl.poll_events() {
-                match event {
-                    Event::Quit => break 'main_loop,
-                    Event::Key {
-                        pressed, keycode, ..
-                    } => {
-                        input_service.provide_input(keycode, pressed);
-                    }
-                    _ => (),
-                }
+            let Ok(part_type) = Ref::filter_map(entity, |entity| match entity.get_type() {
+                EntityType::Part(part_type) => Some(part_type),
+                _ => None,
+            }) else {
+                continue;
+            };
+            if part_type.is_translucent() != translucent {
+                continue;
             }
 
-            unsafe {
-                glClear(GL_COLOR_BUFFER_BIT | GL_DEPTH_BUFFER_BIT);
+            let transform = entity_tree.world_transform(id);
+            let (min, max) = part_type.bounding_box_with(transform);
+            if frustum.contains_aabb(min, max) {
+                parts.push((part_type, transform));
             }
+        }
 
-            let main_camera_null = entity_tree.get_main_camera();
+        if translucent {
+            // the camera looks down -Z, so the farthest parts have the lowest view space Z
+            let view_depth = |transform: &Mat4| (view * *transform).cols[3].z;
+            parts.sort_by(|(_, a), (_, b)| view_depth(a).total_cmp(&view_depth(b)));
+        }
 
-            if let Some(main_camera) = main_camera_null {
-                let main_camera_borrow = main_camera.borrow();
+        for (part_type, transform) in parts {
+            self.render_part(&part_type, transform, (view, projection), program);
+        }
+    }
 
-                let EntityType::Camera(camera) = main_camera_borrow.get_type() else {
-                    panic!("camera doesn't isn't a camera type");
-                };
+    /// Renders the tree once per camera, each inside of it's own viewport (split-screen).
+    /// # Arguements
+    /// - `cameras`: the ID of each camera entity, paired with the viewport it renders to
+    /// - `entity_tree`: the tree containing the parts and cameras
+    /// # Note
+    /// - Each viewport is cleared seperately, with the scissor test enabled, so that clearing a
+    ///   viewport doesn't clear the others.
+    /// - The aspect ratio of each camera is derived from it's viewport, not the window.
+    /// - IDs that aren't a camera entity are skipped.
+    pub fn render_split(&self, cameras: &[(Uuid, Viewport)], entity_tree: &EntityTree) {
+        let full_viewport = Viewport::current();
+        scissor_test(true);
 
-                let window_size = self.window.get_window_size();
-                let aspect_ratio = (window_size.0 as f32) / (window_size.1 as f32);
+        for (id, viewport) in cameras {
+            let Some(camera_entity) = entity_tree.get_entity(*id) else {
+                continue;
+            };
+            let EntityType::Camera(camera) = camera_entity.get_type() else {
+                continue;
+            };
 
-                let view = camera.transform; // Mat4::from_translation(Vec3::new(0.0, 0.0, -1.0))
-                let projection = camera.get_projection(aspect_ratio);
+            viewport.apply();
+            self.clear();
+            self.render_tree(entity_tree, camera);
+        }
 
-                self.shader_program
-                    .set_matrix4(null_str!("projection"), projection);
-                self.shader_program.set_matrix4(null_str!("view"), view);
-            }
+        scissor_test(false);
+        full_viewport.apply();
+    }
 
-            for id in entity_tree.entity_map.keys() {
-                let entity_null_ref = entity_tree.get_entity_rc(*id);
-                let Some(entity_ref) = entity_null_ref else {
-                    continue;
-                };
+    /// Executes the render loop
+    /// # Note
+    /// The loop doesn't run in a different thread
+    pub fn render_loop(&self, tree_cell: Rc<RefCell<EntityTree>>) {
+        self.render_loop_with(tree_cell, |_| ());
+    }
 
-                let entity_res = entity_ref.try_borrow_mut();
-                let Ok(mut entity) = entity_res else {
-                    continue;
-                };
+    /// Executes the render loop, calling `update` every frame before drawing.
+    /// # Arguements
+    /// - `tree_cell`: the tree rendered and updated every frame
+    /// - `update`: called with the seconds since the last frame (see `FrameTimer`)
+    /// # Note
+    /// - The loop doesn't run in a different thread
+    /// - The tree isn't borrowed while `update` is called, so it can borrow the tree mutably.
+    /// - The tree is updated (see `EntityTree::update_all`) after `update`, before drawing.
+    /// - To drive the engine from another loop, use `poll_events`, `EntityTree::update_all`,
+    ///   `render_frame` and `present` instead.
+    pub fn render_loop_with<F: FnMut(f32)>(
+        &self,
+        tree_cell: Rc<RefCell<EntityTree>>,
+        mut update: F,
+    ) {
+        let input_service_id = {
+            let entity_tree = tree_cell.borrow();
+            let head_binding = entity_tree.get_head().unwrap();
 
-                if let EntityType::Part(part_type) = entity.get_type() {
-                    self.render_part(part_type);
+            let head = head_binding.borrow();
+            let input_service_entity_null = entity_tree.find_first_child(&head, "InputService");
+            let Some(input_service_entity) = input_service_entity_null else {
+                panic!("couldn't find service Entity InputService");
+            };
+            input_service_entity.get_uuid()
+        };
+
+        let mut frame_timer = FrameTimer::new();
+        loop {
+            let delta = frame_timer.tick();
+            self.frame_stats
+                .borrow_mut()
+                .record(frame_timer.last_frame_time());
+
+            let quit = Self::with_input_service(&tree_cell, input_service_id, |input_service| {
+                for event in self.poll_events() {
+                    if let Event::Quit = event {
+                        return true;
+                    }
+                    input_service.handle_event(&event);
                 }
+                false
+            });
+            if quit {
+                break;
+            }
 
-                let is_newly_created = entity.newly_created;
+            update(delta);
 
-                let ent_type = entity.get_type_mut();
+            tree_cell.borrow_mut().update_all(delta);
 
-                if is_newly_created {
-                    ent_type.start();
-                }
+            self.render_frame(&tree_cell.borrow());
+            self.present();
 
-                ent_type.update(delta);
+            Self::with_input_service(&tree_cell, input_service_id, |input_service| {
+                input_service.mark_cleanup()
+            });
+        }
+    }
 
-                entity.newly_created = false;
-            }
-            self.window.swap_window();
+    /// Calls `callback` with the input service, only borrowing the tree during the call.
+    /// # Arguements
+    /// - `tree_cell`: the tree containing the input service
+    /// - `id`: the ID of the input service entity
+    /// - `callback`: called with the input service
+    /// # Returns
+    /// What `callback` returns
+    /// # Panics
+    /// If the entity isn't in the tree, or isn't an input service
+    fn with_input_service<T>(
+        tree_cell: &RefCell<EntityTree>,
+        id: Uuid,
+        callback: impl FnOnce(&mut InputService) -> T,
+    ) -> T {
+        let entity_tree = tree_cell.borrow();
+        let Some(mut input_service_entity) = entity_tree.get_entity_mut(id) else {
+            panic!("couldn't find service Entity InputService");
+        };
+        let EntityType::InputService(input_service) = input_service_entity.get_type_mut() else {
+            panic!("couldn't borrow InputService");
+        };
+        callback(input_service)
+    }
+
+    /// Takes every event waiting in SDL's queue.
+    /// # Returns
+    /// The events, oldest first
+    /// # Note
+    /// Used to drive the engine from an external loop, with `render_frame` and `present`.
+    pub fn poll_events(&self) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Some((event, _timestamp)) = self.sdl.poll_events() {
+            events.push(event);
+        }
+        events
+    }
+
+    /// Renders a single frame of the tree from it's main camera.
+    /// # Arguements
+    /// - `entity_tree`: the tree being rendered
+    /// # Note
+    /// - Clears the frame (see `set_clear_color` and `set_clear_flags`) and resets the
+    ///   `RenderStats` first.
+    /// - Only clears the frame if the tree doesn't have a main camera.
+    pub fn render_frame(&self, entity_tree: &EntityTree) {
+        self.clear();
+        self.reset_render_stats();
+
+        if let Some(main_camera) = entity_tree.get_main_camera() {
+            let main_camera_borrow = main_camera.borrow();
 
-            let EntityType::InputService(input_service) = input_service_entity.get_type_mut()
-            else {
-                panic!("couldn't borrow InputService");
+            let EntityType::Camera(camera) = main_camera_borrow.get_type() else {
+                panic!("camera doesn't isn't a camera type");
             };
 
-            input_service.mark_cleanup();
-            last_frame = current_frame;
+            self.render_tree(entity_tree, camera);
         }
     }
 
+    /// Presents the rendered frame, by swapping the window's buffers.
+    /// # Note
+    /// The contents of the back buffer are undefined afterwards, so read the frame (e.g.
+    /// `read_pixels`) before presenting it.
+    pub fn present(&self) {
+        self.window.swap_window();
+    }
+
     /// Creates the Sdl with approprate flags set
     /// # Returns
     /// - Sdl
+    /// # Note
+    /// Requests a 24 bit depth buffer, SDL only requests 16 bits by default.
     fn init_sdl() -> Sdl {
         let sdl = Sdl::init(InitFlags::EVERYTHING);
         sdl.set_gl_context_major_version(3).unwrap();
-        sdl.set_gl_context_major_version(3).unwrap();
+        sdl.set_gl_context_minor_version(3).unwrap();
         sdl.set_gl_profile(GlProfile::Core).unwrap();
+        sdl.set_gl_depth_bits(24).unwrap();
 
         let mut flags = GlContextFlags::default();
 